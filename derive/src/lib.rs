@@ -0,0 +1,74 @@
+//! Derive macros for the `graphics` crate.
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, Data, DeriveInput, Fields};
+
+mod std140;
+mod vertex;
+
+/// Implement `VertexLayout` for a `#[repr(C)]` struct with named fields.
+///
+/// Offsets and the stride are taken from the struct's layout, the
+/// attribute type of every field comes from its `VertexAttribute` impl.
+/// Fields accept the following options:
+/// - `#[vertex(name = "...")]` uses a different attribute name than the field's
+/// - `#[vertex(normalized = false)]` overrides whether integers are normalized
+/// - `#[vertex(integer)]` passes integers to an `int`, `ivec` or `uvec` input as is
+/// - `#[vertex(skip)]` leaves out a field, e.g. padding
+#[proc_macro_derive(Vertex, attributes(vertex))]
+pub fn derive_vertex(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    vertex::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implement `Std140` for a struct with named fields.
+///
+/// Fields are laid out in declaration order following the GLSL `std140` rules,
+/// so the struct can mirror a uniform block field by field. The bytes are written
+/// one field at a time, so the struct doesn't need a particular `#[repr]`.
+#[proc_macro_derive(Std140)]
+pub fn derive_std140(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    std140::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Returns whether the type has `#[repr(C)]`, possibly alongside other representation hints.
+fn is_repr_c(input: &DeriveInput) -> bool {
+    input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("repr"))
+        .any(|attr| {
+            let mut repr_c = false;
+            let _ = attr.parse_nested_meta(|meta| {
+                repr_c |= meta.path.is_ident("C");
+                Ok(())
+            });
+            repr_c
+        })
+}
+
+fn named_fields<'a>(
+    input: &'a DeriveInput,
+    derive: &str,
+) -> syn::Result<&'a syn::punctuated::Punctuated<syn::Field, syn::token::Comma>> {
+    match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => Ok(&fields.named),
+            _ => Err(syn::Error::new_spanned(
+                &input.ident,
+                format!("{derive} can only be derived for structs with named fields"),
+            )),
+        },
+        _ => Err(syn::Error::new_spanned(
+            &input.ident,
+            format!("{derive} can only be derived for structs"),
+        )),
+    }
+}
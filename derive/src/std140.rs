@@ -0,0 +1,48 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::DeriveInput;
+
+use crate::named_fields;
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let fields = named_fields(&input, "Std140")?;
+    let idents: Vec<_> = fields.iter().map(|field| &field.ident).collect();
+    let types: Vec<_> = fields.iter().map(|field| &field.ty).collect();
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::graphics::Std140 for #ident #ty_generics #where_clause {
+            const ALIGN: usize = {
+                let mut align = 16;
+                #(if <#types as ::graphics::Std140>::ALIGN > align {
+                    align = <#types as ::graphics::Std140>::ALIGN;
+                })*
+                ::graphics::__private::align_to(align, 16)
+            };
+
+            const SIZE: usize = {
+                let mut offset = 0;
+                #(offset = ::graphics::__private::align_to(
+                    offset,
+                    <#types as ::graphics::Std140>::ALIGN,
+                ) + <#types as ::graphics::Std140>::SIZE;)*
+                ::graphics::__private::align_to(offset, Self::ALIGN)
+            };
+
+            fn write_std140(&self, out: &mut [u8]) {
+                let mut offset = 0;
+                #(
+                    offset = ::graphics::__private::align_to(
+                        offset,
+                        <#types as ::graphics::Std140>::ALIGN,
+                    );
+                    ::graphics::Std140::write_std140(&self.#idents, &mut out[offset..]);
+                    offset += <#types as ::graphics::Std140>::SIZE;
+                )*
+                let _ = offset;
+            }
+        }
+    })
+}
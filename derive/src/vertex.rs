@@ -0,0 +1,107 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, LitBool, LitStr};
+
+use crate::{is_repr_c, named_fields};
+
+struct FieldOptions {
+    name: Option<LitStr>,
+    normalized: Option<LitBool>,
+    integer: bool,
+    skip: bool,
+}
+
+impl FieldOptions {
+    fn parse(field: &syn::Field) -> syn::Result<Self> {
+        let mut options = Self {
+            name: None,
+            normalized: None,
+            integer: false,
+            skip: false,
+        };
+
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("vertex"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("name") {
+                    options.name = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("normalized") {
+                    options.normalized = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("integer") {
+                    options.integer = true;
+                } else if meta.path.is_ident("skip") {
+                    options.skip = true;
+                } else {
+                    return Err(meta.error("expected `name`, `normalized`, `integer` or `skip`"));
+                }
+
+                Ok(())
+            })?;
+        }
+
+        Ok(options)
+    }
+}
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    if !is_repr_c(&input) {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "Vertex requires #[repr(C)] for a predictable field layout",
+        ));
+    }
+
+    let mut descriptors = Vec::new();
+    for field in named_fields(&input, "Vertex")? {
+        let options = FieldOptions::parse(field)?;
+        if options.skip {
+            continue;
+        }
+
+        let ident = field.ident.as_ref().expect("named fields have identifiers");
+        let ty = &field.ty;
+        let name = options
+            .name
+            .unwrap_or_else(|| LitStr::new(&ident.to_string(), ident.span()));
+        if let (true, Some(normalized)) = (options.integer, &options.normalized) {
+            return Err(syn::Error::new_spanned(
+                normalized,
+                "integer attributes can't be normalized",
+            ));
+        }
+        let integer = options.integer;
+        let normalized = match options.normalized {
+            Some(normalized) => quote!(#normalized),
+            None => quote!(<#ty as ::graphics::VertexAttribute>::NORMALIZED),
+        };
+
+        descriptors.push(quote! {
+            ::graphics::AttributeDescriptor::new(
+                #name,
+                <#ty as ::graphics::VertexAttribute>::SIZE,
+                <#ty as ::graphics::VertexAttribute>::TYPE.size(),
+                <#ty as ::graphics::VertexAttribute>::TYPE,
+            )
+            .normalized(#normalized)
+            .integer(#integer)
+            .at_offset(::graphics::__private::offset_of!(Self, #ident))
+        });
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::graphics::VertexLayout for #ident #ty_generics #where_clause {
+            fn attributes() -> ::graphics::Attributes {
+                ::graphics::Attributes::with_stride(
+                    ::std::vec![#(#descriptors),*],
+                    ::graphics::__private::size_of::<Self>() as i32,
+                )
+            }
+        }
+    })
+}
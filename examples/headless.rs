@@ -0,0 +1,77 @@
+use graphics::{
+    attributes, Attachment, ClearFlags, Color, DrawMode, Framebuffer, HeadlessContext, Program,
+    Shader, Texture, Vao, Vbo,
+};
+
+const VERTEX_SOURCE: &str = r#"
+#version 330 core
+layout (location = 0) in vec2 position;
+layout (location = 1) in vec3 color;
+out vec3 fragment_color;
+void main() {
+    gl_Position = vec4(position, 1.0, 1.0);
+    fragment_color = color;
+}
+"#;
+
+const FRAGMENT_SOURCE: &str = r#"
+#version 330 core
+in vec3 fragment_color;
+out vec4 color;
+void main() {
+    color = vec4(fragment_color, 1.0);
+}
+"#;
+
+const TRIANGLE_DATA: [f32; 15] = [
+    0.0, 0.5, // first pos
+    1.0, 0.819, 0.729, // first color
+    0.5, -0.5, // second pos
+    0.807, 0.490, 0.647, // second color
+    -0.5, -0.5, // third pos
+    0.745, 0.8980, 0.749, // third color
+];
+
+const WIDTH: i32 = 256;
+const HEIGHT: i32 = 256;
+
+/// Renders the triangle into a texture without opening a window
+/// and saves it as `triangle.png`.
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let context = HeadlessContext::new(WIDTH, HEIGHT)?;
+    let draw_layer = context.draw_layer();
+
+    let target = Texture::empty(WIDTH, HEIGHT);
+    let framebuffer = Framebuffer::new();
+    framebuffer.attach_texture(Attachment::Color(0), &target, 0);
+    framebuffer.check_status()?;
+
+    draw_layer.bind_framebuffer(&framebuffer);
+    draw_layer.set_viewport(0, 0, WIDTH, HEIGHT);
+    draw_layer.set_clear_color(Color::WHITE);
+
+    let vao = Vao::new();
+    let vbo = Vbo::new(&vao);
+    vbo.bind_data(&TRIANGLE_DATA);
+
+    let vertex = Shader::compile(VERTEX_SOURCE)?;
+    let fragment = Shader::compile(FRAGMENT_SOURCE)?;
+    let program = Program::new(vertex, fragment)?;
+
+    let attrs = attributes! {
+        position: vec<f32, 2>,
+        color: vec<f32, 3>
+    };
+    attrs
+        .calculate_for(&program)
+        .ok_or("Failed to describe the memory layout")?;
+
+    draw_layer.use_program(&program);
+    draw_layer.clear(ClearFlags::COLOR);
+    draw_layer.draw_arrays(&vao, DrawMode::Triangles, 0, 3);
+
+    let pixels = draw_layer.read_pixels(0, 0, WIDTH as u32, HEIGHT as u32);
+    pixels.save_png("triangle.png")?;
+
+    Ok(())
+}
@@ -100,6 +100,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     active_texture.bind_texture(&texture);
     draw_layer.put_uniform(&program, "tex", &active_texture);
 
+    let time_uniform = program.uniform::<f32>("time")?;
+
     let mut event_pump = sdl.event_pump()?;
     let timer = sdl.timer()?;
 
@@ -117,7 +119,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
 
         let time = timer.ticks() as f32 / 150.0;
-        draw_layer.put_uniform(&program, "time", &time);
+        draw_layer.set(&time_uniform, &time);
         draw_layer.clear(ClearFlags::COLOR);
         draw_layer.draw_arrays(&vao, DrawMode::Triangles, 0, 3);
 
@@ -18,6 +18,26 @@ pub enum AttributeType {
     fixed = gl::FIXED,
 }
 
+impl AttributeType {
+    /// The size of a single component in bytes.
+    pub const fn size(self) -> usize {
+        match self {
+            Self::i8 | Self::u8 => 1,
+            Self::i16 | Self::u16 | Self::f16 => 2,
+            Self::f32 | Self::i32 | Self::u32 | Self::fixed => 4,
+            Self::f64 => 8,
+        }
+    }
+
+    /// Whether the components are integers, which the shader can read as such.
+    pub const fn is_integer(self) -> bool {
+        matches!(
+            self,
+            Self::i8 | Self::u8 | Self::i16 | Self::u16 | Self::i32 | Self::u32
+        )
+    }
+}
+
 pub struct Attribute(u32);
 
 impl Attribute {
@@ -51,6 +71,28 @@ impl Attribute {
 
         self
     }
+
+    /// Like [`Self::memory_layout`], but the values reach the shader as integers,
+    /// for `int`, `ivec` and `uvec` inputs.
+    pub fn integer_memory_layout(
+        &self,
+        size: i32,
+        mem_type: AttributeType,
+        stride: i32,
+        offset: usize,
+    ) -> &Self {
+        unsafe {
+            gl::VertexAttribIPointer(
+                self.0,
+                size,
+                mem_type as u32,
+                stride,
+                offset as *const c_void,
+            )
+        }
+
+        self
+    }
 }
 
 pub struct AttributeDescriptor {
@@ -58,6 +100,14 @@ pub struct AttributeDescriptor {
     pub vector_size: i32,
     pub mem_size: usize,
     pub mem_type: AttributeType,
+    /// Whether integer values are mapped to `[0, 1]` (or `[-1, 1]`) instead of converted as is.
+    pub normalized: bool,
+    /// Whether the values reach the shader as integers instead of floats,
+    /// [`Self::normalized`] doesn't apply then.
+    pub integer: bool,
+    /// The offset in bytes from the start of the vertex,
+    /// [`None`] places the attribute right after the previous one.
+    pub offset: Option<usize>,
 }
 
 impl AttributeDescriptor {
@@ -72,8 +122,33 @@ impl AttributeDescriptor {
             vector_size,
             mem_size,
             mem_type,
+            normalized: false,
+            integer: false,
+            offset: None,
         }
     }
+
+    pub fn normalized(mut self, normalized: bool) -> Self {
+        self.normalized = normalized;
+        self
+    }
+
+    /// # Panics
+    /// If the attribute's type isn't an integer type.
+    pub fn integer(mut self, integer: bool) -> Self {
+        assert!(
+            !integer || self.mem_type.is_integer(),
+            "The attribute {} has no integer type",
+            self.name
+        );
+        self.integer = integer;
+        self
+    }
+
+    pub fn at_offset(mut self, offset: usize) -> Self {
+        self.offset = Some(offset);
+        self
+    }
 }
 
 #[macro_export]
@@ -99,36 +174,44 @@ macro_rules! attributes {
 
 pub struct Attributes {
     attrs: Vec<AttributeDescriptor>,
+    stride: Option<i32>,
 }
 
 impl Attributes {
+    /// Describe tightly packed, interleaved attributes.
     pub fn new(attrs: Vec<AttributeDescriptor>) -> Self {
-        Self { attrs }
+        Self {
+            attrs,
+            stride: None,
+        }
     }
 
-    pub fn calculate_for(self, program: &Program) -> Option<()> {
-        let stride: i32 = self
-            .attrs
-            .iter()
-            .map(|attr| attr.mem_size as i32 * attr.vector_size)
-            .sum();
+    /// Describe attributes of a vertex that is `stride` bytes long,
+    /// e.g. because of padding between the fields.
+    pub fn with_stride(attrs: Vec<AttributeDescriptor>, stride: i32) -> Self {
+        Self {
+            attrs,
+            stride: Some(stride),
+        }
+    }
 
-        self.calculate_one_attribute(program, &self.attrs[0], stride, 0)?;
+    pub fn descriptors(&self) -> &[AttributeDescriptor] {
+        &self.attrs
+    }
 
-        let mut offset = 0;
+    pub fn calculate_for(self, program: &Program) -> Option<()> {
+        let stride = self.stride.unwrap_or_else(|| {
+            self.attrs
+                .iter()
+                .map(|attr| attr.mem_size as i32 * attr.vector_size)
+                .sum()
+        });
 
-        let errors: Vec<Option<()>> = self
-            .attrs
-            .windows(2)
-            .map(|attr_pair| {
-                offset += attr_pair[0].mem_size * attr_pair[0].vector_size as usize;
-                let descriptor = &attr_pair[1];
-                self.calculate_one_attribute(program, descriptor, stride, offset)
-            })
-            .collect();
-
-        for error in errors {
-            error?;
+        let mut offset = 0;
+        for descriptor in &self.attrs {
+            offset = descriptor.offset.unwrap_or(offset);
+            self.calculate_one_attribute(program, descriptor, stride, offset)?;
+            offset += descriptor.mem_size * descriptor.vector_size as usize;
         }
 
         Some(())
@@ -143,14 +226,95 @@ impl Attributes {
     ) -> Option<()> {
         let attr = program.get_attribute(descriptor.name)?;
         attr.enable();
-        attr.memory_layout(
-            descriptor.vector_size,
-            descriptor.mem_type,
-            false,
-            stride,
-            offset,
-        );
+        if descriptor.integer {
+            attr.integer_memory_layout(descriptor.vector_size, descriptor.mem_type, stride, offset);
+        } else {
+            attr.memory_layout(
+                descriptor.vector_size,
+                descriptor.mem_type,
+                descriptor.normalized,
+                stride,
+                offset,
+            );
+        }
 
         Some(())
     }
 }
+
+/// A vertex type whose attribute layout is known, usually implemented with `#[derive(Vertex)]`.
+///
+/// ```ignore
+/// #[derive(Vertex)]
+/// #[repr(C)]
+/// struct ColoredVertex {
+///     position: glm::Vec2,
+///     #[vertex(name = "color")]
+///     rgba: [u8; 4],
+/// }
+///
+/// ColoredVertex::attributes().calculate_for(&program);
+/// ```
+pub trait VertexLayout {
+    fn attributes() -> Attributes;
+}
+
+/// Types that can be the field of a vertex.
+///
+/// # Safety
+/// The type must consist of exactly [`Self::SIZE`] values of [`Self::TYPE`] with no padding.
+pub unsafe trait VertexAttribute {
+    /// The number of components, from 1 to 4.
+    const SIZE: i32;
+    const TYPE: AttributeType;
+    /// 8 and 16 bit integer components are normalized by default, use
+    /// `#[vertex(normalized = false)]` on the field to convert them to floats as is,
+    /// or `#[vertex(integer)]` to keep them integers.
+    const NORMALIZED: bool;
+}
+
+/// Scalars that can be the components of a vector [`VertexAttribute`].
+///
+/// # Safety
+/// The type must have the size and representation of [`Self::TYPE`].
+pub unsafe trait VertexComponent {
+    const TYPE: AttributeType;
+    const NORMALIZED: bool;
+}
+
+macro_rules! vertex_attributes {
+    ($($tp:ident: $normalized:literal),*) => {
+        $(unsafe impl VertexComponent for $tp {
+            const TYPE: AttributeType = AttributeType::$tp;
+            const NORMALIZED: bool = $normalized;
+        }
+
+        unsafe impl VertexAttribute for $tp {
+            const SIZE: i32 = 1;
+            const TYPE: AttributeType = AttributeType::$tp;
+            const NORMALIZED: bool = $normalized;
+        })*
+    };
+}
+
+vertex_attributes!(i8: true, u8: true, i16: true, u16: true, i32: false, u32: false, f32: false, f64: false);
+
+const fn component_count(n: usize) -> i32 {
+    assert!(n >= 1 && n <= 4, "Vertex attributes have 1 to 4 components");
+    n as i32
+}
+
+unsafe impl<T: VertexComponent, const N: usize> VertexAttribute for [T; N] {
+    const SIZE: i32 = component_count(N);
+    const TYPE: AttributeType = T::TYPE;
+    const NORMALIZED: bool = T::NORMALIZED;
+}
+
+unsafe impl<T, const N: usize> VertexAttribute for nalgebra_glm::TVec<T, N>
+where
+    T: VertexComponent + nalgebra_glm::Scalar,
+{
+    const SIZE: i32 = component_count(N);
+    const TYPE: AttributeType = T::TYPE;
+    const NORMALIZED: bool = T::NORMALIZED;
+}
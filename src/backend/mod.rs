@@ -0,0 +1,43 @@
+#[cfg(feature = "recording")]
+mod recording;
+
+#[cfg(feature = "recording")]
+pub use recording::*;
+
+use std::ffi::c_void;
+
+/// The source of the OpenGL functions used by every wrapper in this crate.
+///
+/// # Safety
+/// Every non-null pointer returned by [`Backend::get_proc_address`] must be a
+/// function with the signature OpenGL specifies for `symbol`.
+pub unsafe trait Backend {
+    /// Returns the address of the function `symbol` (e.g. `glGenBuffers`),
+    /// or a null pointer if it's not available.
+    fn get_proc_address(&self, symbol: &str) -> *const c_void;
+}
+
+/// A [`Backend`] that loads functions from a driver, such as SDL's
+/// `gl_get_proc_address`.
+pub struct LoaderBackend<F>(F);
+
+impl<T, F> LoaderBackend<F>
+where
+    F: Fn(&str) -> *const T,
+{
+    /// # Safety
+    /// `loader` must return valid OpenGL function pointers for the context
+    /// current on this thread, or null pointers.
+    pub unsafe fn new(loader: F) -> Self {
+        Self(loader)
+    }
+}
+
+unsafe impl<T, F> Backend for LoaderBackend<F>
+where
+    F: Fn(&str) -> *const T,
+{
+    fn get_proc_address(&self, symbol: &str) -> *const c_void {
+        (self.0)(symbol) as *const c_void
+    }
+}
@@ -0,0 +1,485 @@
+//! A fake OpenGL implementation that records every call instead of executing it.
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    ffi::{c_char, c_void, CStr},
+    fmt, ptr,
+};
+
+use super::Backend;
+
+/// An argument of a recorded call.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Arg {
+    /// Integers, enums, booleans and pointers (which are mostly buffer offsets).
+    Int(i64),
+    Float(f64),
+    /// Null terminated strings, such as attribute and uniform names.
+    Str(String),
+}
+
+impl fmt::Display for Arg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Int(value) => write!(f, "{value}"),
+            Self::Float(value) => write!(f, "{value:?}"),
+            Self::Str(value) => write!(f, "{value:?}"),
+        }
+    }
+}
+
+/// A single recorded OpenGL call, output pointers are not recorded.
+/// Formats as `VertexAttribPointer(0, 2, 5126, 0, 20, 8)`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Call {
+    /// The function name without the `gl` prefix.
+    pub name: &'static str,
+    pub args: Vec<Arg>,
+}
+
+impl fmt::Display for Call {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}(", self.name)?;
+        for (index, arg) in self.args.iter().enumerate() {
+            if index != 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{arg}")?;
+        }
+        write!(f, ")")
+    }
+}
+
+#[derive(Default)]
+struct Recording {
+    calls: Vec<Call>,
+    last_id: u32,
+    attribute_locations: HashMap<String, i32>,
+    uniform_locations: HashMap<String, i32>,
+    uniform_block_indices: HashMap<String, i32>,
+    resource_indices: HashMap<String, i32>,
+    integers: HashMap<u32, i32>,
+    /// Memory handed out by `glMapBufferRange`, kept until the recording is reset.
+    mappings: Vec<Box<[u64]>>,
+}
+
+thread_local! {
+    static RECORDING: RefCell<Recording> = RefCell::default();
+}
+
+fn with_recording<R>(f: impl FnOnce(&mut Recording) -> R) -> R {
+    RECORDING.with(|recording| f(&mut recording.borrow_mut()))
+}
+
+/// A [`Backend`] for testing without a context.
+///
+/// Every call is appended to a log of the calling thread, object names are
+/// handed out sequentially starting at 1, compile and link steps succeed and
+/// attribute or uniform names get the next free location unless one was set.
+/// Functions this backend doesn't know are not loaded and panic when called.
+pub struct RecordingBackend(());
+
+impl RecordingBackend {
+    /// Create a backend and reset the recording of the current thread.
+    pub fn new() -> Self {
+        with_recording(|recording| *recording = Recording::default());
+        Self(())
+    }
+
+    /// All calls made on the current thread so far.
+    pub fn calls(&self) -> Vec<Call> {
+        with_recording(|recording| recording.calls.clone())
+    }
+
+    /// The calls to `name` (without the `gl` prefix) made on the current thread.
+    pub fn calls_to(&self, name: &str) -> Vec<Call> {
+        with_recording(|recording| {
+            recording
+                .calls
+                .iter()
+                .filter(|call| call.name == name)
+                .cloned()
+                .collect()
+        })
+    }
+
+    /// Forget the calls recorded so far.
+    pub fn clear(&self) {
+        with_recording(|recording| recording.calls.clear())
+    }
+
+    /// Make `glGetAttribLocation` return `location` for `name`, `-1` marks a missing attribute.
+    pub fn set_attribute_location(&self, name: &str, location: i32) {
+        with_recording(|recording| {
+            recording
+                .attribute_locations
+                .insert(name.to_owned(), location)
+        });
+    }
+
+    /// Make `glGetUniformLocation` return `location` for `name`, `-1` marks a missing uniform.
+    pub fn set_uniform_location(&self, name: &str, location: i32) {
+        with_recording(|recording| {
+            recording
+                .uniform_locations
+                .insert(name.to_owned(), location)
+        });
+    }
+
+    /// Make `glGetIntegerv(parameter)` return `value` instead of 0.
+    pub fn set_integer(&self, parameter: u32, value: i32) {
+        with_recording(|recording| recording.integers.insert(parameter, value));
+    }
+}
+
+impl Default for RecordingBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl Backend for RecordingBackend {
+    fn get_proc_address(&self, symbol: &str) -> *const c_void {
+        lookup(symbol)
+    }
+}
+
+trait ToArg {
+    fn to_arg(&self) -> Option<Arg>;
+}
+
+macro_rules! int_args {
+    ($($tp:ty),*) => {
+        $(impl ToArg for $tp {
+            fn to_arg(&self) -> Option<Arg> {
+                Some(Arg::Int(*self as i64))
+            }
+        })*
+    };
+}
+
+int_args!(u8, i32, u32, u64, isize);
+
+impl ToArg for f32 {
+    fn to_arg(&self) -> Option<Arg> {
+        Some(Arg::Float(*self as f64))
+    }
+}
+
+impl ToArg for f64 {
+    fn to_arg(&self) -> Option<Arg> {
+        Some(Arg::Float(*self))
+    }
+}
+
+impl<T> ToArg for *const T {
+    fn to_arg(&self) -> Option<Arg> {
+        Some(Arg::Int(*self as i64))
+    }
+}
+
+/// A pointer the function writes its results to.
+#[repr(transparent)]
+struct Out<T>(*mut T);
+
+impl<T> Out<T> {
+    unsafe fn write(&self, index: usize, value: T) {
+        if !self.0.is_null() {
+            self.0.add(index).write(value)
+        }
+    }
+}
+
+impl<T> ToArg for Out<T> {
+    fn to_arg(&self) -> Option<Arg> {
+        None
+    }
+}
+
+/// A null terminated string argument.
+#[repr(transparent)]
+struct Name(*const c_char);
+
+impl Name {
+    unsafe fn to_owned_string(&self) -> String {
+        CStr::from_ptr(self.0).to_string_lossy().into_owned()
+    }
+}
+
+impl ToArg for Name {
+    fn to_arg(&self) -> Option<Arg> {
+        Some(Arg::Str(unsafe { self.to_owned_string() }))
+    }
+}
+
+fn next_id() -> u32 {
+    with_recording(|recording| {
+        recording.last_id += 1;
+        recording.last_id
+    })
+}
+
+unsafe fn generate(n: i32, ids: Out<u32>) {
+    for index in 0..n as usize {
+        ids.write(index, next_id())
+    }
+}
+
+fn location(name: Name, locations: fn(&mut Recording) -> &mut HashMap<String, i32>) -> i32 {
+    let name = unsafe { name.to_owned_string() };
+    with_recording(|recording| {
+        let locations = locations(recording);
+        let next = locations.values().max().map_or(0, |max| max + 1);
+        *locations.entry(name).or_insert(next)
+    })
+}
+
+macro_rules! stubs {
+    ($(fn $name:ident($($arg:ident: $tp:ty),*) $(-> $ret:ty)? $body:block)*) => {
+        #[allow(non_snake_case, unused_variables, clippy::too_many_arguments)]
+        mod stubs {
+            use super::*;
+
+            $(pub extern "system" fn $name($($arg: $tp),*) $(-> $ret)? {
+                let args: Vec<Option<Arg>> = vec![$(ToArg::to_arg(&$arg)),*];
+                with_recording(|recording| recording.calls.push(Call {
+                    name: stringify!($name),
+                    args: args.into_iter().flatten().collect(),
+                }));
+
+                #[allow(unused_unsafe)]
+                unsafe { $body }
+            })*
+        }
+
+        fn lookup(symbol: &str) -> *const c_void {
+            match symbol.strip_prefix("gl") {
+                $(Some(stringify!($name)) => stubs::$name as *const c_void,)*
+                _ => ptr::null(),
+            }
+        }
+    };
+}
+
+stubs! {
+    fn ActiveTexture(texture: u32) {}
+    fn ActiveShaderProgram(pipeline: u32, program: u32) {}
+    fn AttachShader(program: u32, shader: u32) {}
+    fn BindBuffer(target: u32, buffer: u32) {}
+    fn BindFramebuffer(target: u32, framebuffer: u32) {}
+    fn BindImageTexture(unit: u32, texture: u32, level: i32, layered: u8, layer: i32, access: u32, format: u32) {}
+    fn BindProgramPipeline(pipeline: u32) {}
+    fn BindRenderbuffer(target: u32, renderbuffer: u32) {}
+    fn BindSampler(unit: u32, sampler: u32) {}
+    fn BindTexture(target: u32, texture: u32) {}
+    fn BindVertexArray(array: u32) {}
+    fn BufferData(target: u32, size: isize, data: *const c_void, usage: u32) {}
+    fn BindBufferBase(target: u32, index: u32, buffer: u32) {}
+    fn BindBufferRange(target: u32, index: u32, buffer: u32, offset: isize, size: isize) {}
+    fn BufferStorage(target: u32, size: isize, data: *const c_void, flags: u32) {}
+    fn BufferSubData(target: u32, offset: isize, size: isize, data: *const c_void) {}
+    fn CheckFramebufferStatus(target: u32) -> u32 { gl::FRAMEBUFFER_COMPLETE }
+    fn Clear(mask: u32) {}
+    fn ClearColor(red: f32, green: f32, blue: f32, alpha: f32) {}
+    fn ClientWaitSync(sync: *const c_void, flags: u32, timeout: u64) -> u32 { gl::ALREADY_SIGNALED }
+    fn CompileShader(shader: u32) {}
+    fn CopyBufferSubData(read_target: u32, write_target: u32, read_offset: isize, write_offset: isize, size: isize) {}
+    fn CreateProgram() -> u32 { next_id() }
+    fn CreateShader(shader_type: u32) -> u32 { next_id() }
+    fn DeleteBuffers(n: i32, buffers: *const u32) {}
+    fn DeleteFramebuffers(n: i32, framebuffers: *const u32) {}
+    fn DeleteProgram(program: u32) {}
+    fn DeleteProgramPipelines(n: i32, pipelines: *const u32) {}
+    fn DeleteRenderbuffers(n: i32, renderbuffers: *const u32) {}
+    fn DeleteSamplers(n: i32, samplers: *const u32) {}
+    fn DeleteShader(shader: u32) {}
+    fn DeleteSync(sync: *const c_void) {}
+    fn DeleteTextures(n: i32, textures: *const u32) {}
+    fn DeleteVertexArrays(n: i32, arrays: *const u32) {}
+    fn DepthFunc(func: u32) {}
+    fn DispatchCompute(num_groups_x: u32, num_groups_y: u32, num_groups_z: u32) {}
+    fn DispatchComputeIndirect(indirect: isize) {}
+    fn DrawArrays(mode: u32, first: i32, count: i32) {}
+    fn DrawBuffers(n: i32, bufs: *const u32) {}
+    fn DrawElements(mode: u32, count: i32, index_type: u32, indices: *const c_void) {}
+    fn Enable(cap: u32) {}
+    fn EnableVertexAttribArray(index: u32) {}
+    fn FenceSync(condition: u32, flags: u32) -> *const c_void { next_id() as usize as *const c_void }
+    fn FramebufferRenderbuffer(target: u32, attachment: u32, renderbuffer_target: u32, renderbuffer: u32) {}
+    fn FramebufferTexture2D(target: u32, attachment: u32, texture_target: u32, texture: u32, level: i32) {}
+    fn GenBuffers(n: i32, buffers: Out<u32>) { generate(n, buffers) }
+    fn GenFramebuffers(n: i32, framebuffers: Out<u32>) { generate(n, framebuffers) }
+    fn GenProgramPipelines(n: i32, pipelines: Out<u32>) { generate(n, pipelines) }
+    fn GenRenderbuffers(n: i32, renderbuffers: Out<u32>) { generate(n, renderbuffers) }
+    fn GenSamplers(n: i32, samplers: Out<u32>) { generate(n, samplers) }
+    fn GenTextures(n: i32, textures: Out<u32>) { generate(n, textures) }
+    fn GenVertexArrays(n: i32, arrays: Out<u32>) { generate(n, arrays) }
+    fn GenerateMipmap(target: u32) {}
+    fn GetActiveAttrib(program: u32, index: u32, buf_size: i32, length: Out<i32>, size: Out<i32>, attribute_type: Out<u32>, name: Out<c_char>) {}
+    fn GetActiveUniform(program: u32, index: u32, buf_size: i32, length: Out<i32>, size: Out<i32>, uniform_type: Out<u32>, name: Out<c_char>) {}
+    fn GetActiveUniformBlockName(program: u32, index: u32, buf_size: i32, length: Out<i32>, name: Out<c_char>) {}
+    fn GetActiveUniformBlockiv(program: u32, index: u32, pname: u32, params: Out<i32>) {}
+    fn GetActiveUniformsiv(program: u32, count: i32, indices: *const u32, pname: u32, params: Out<i32>) {}
+    fn GetAttribLocation(program: u32, name: Name) -> i32 {
+        location(name, |recording| &mut recording.attribute_locations)
+    }
+    fn GetError() -> u32 { gl::NO_ERROR }
+    fn GetFloatv(pname: u32, data: Out<f32>) {
+        data.write(0, 0.0)
+    }
+    fn GetIntegerv(pname: u32, data: Out<i32>) {
+        let value = with_recording(|recording| recording.integers.get(&pname).copied());
+        data.write(0, value.unwrap_or(0))
+    }
+    fn GetProgramBinary(program: u32, buf_size: i32, length: Out<i32>, binary_format: Out<u32>, binary: Out<c_void>) {
+        length.write(0, 0)
+    }
+    fn GetProgramInfoLog(program: u32, buf_size: i32, length: Out<i32>, info_log: Out<c_char>) {
+        length.write(0, 0);
+        info_log.write(0, 0)
+    }
+    fn GetProgramInterfaceiv(program: u32, interface: u32, pname: u32, params: Out<i32>) {
+        params.write(0, 0)
+    }
+    fn GetProgramiv(program: u32, pname: u32, params: Out<i32>) {
+        params.write(0, (pname == gl::LINK_STATUS) as i32)
+    }
+    fn GetProgramPipelineInfoLog(pipeline: u32, buf_size: i32, length: Out<i32>, info_log: Out<c_char>) {
+        length.write(0, 0);
+        info_log.write(0, 0)
+    }
+    fn GetProgramPipelineiv(pipeline: u32, pname: u32, params: Out<i32>) {
+        params.write(0, (pname == gl::VALIDATE_STATUS) as i32)
+    }
+    fn GetProgramResourceIndex(program: u32, interface: u32, name: Name) -> u32 {
+        location(name, |recording| &mut recording.resource_indices) as u32
+    }
+    fn GetProgramResourceName(program: u32, interface: u32, index: u32, buf_size: i32, length: Out<i32>, name: Out<c_char>) {}
+    fn GetProgramResourceiv(
+        program: u32,
+        interface: u32,
+        index: u32,
+        prop_count: i32,
+        props: *const u32,
+        buf_size: i32,
+        length: Out<i32>,
+        params: Out<i32>
+    ) {}
+    fn GetShaderInfoLog(shader: u32, buf_size: i32, length: Out<i32>, info_log: Out<c_char>) {
+        length.write(0, 0);
+        info_log.write(0, 0)
+    }
+    fn GetShaderiv(shader: u32, pname: u32, params: Out<i32>) {
+        params.write(0, (pname == gl::COMPILE_STATUS) as i32)
+    }
+    fn GetString(name: u32) -> *const u8 { c"Recording".as_ptr() as *const u8 }
+    fn GetStringi(name: u32, index: u32) -> *const u8 { c"".as_ptr() as *const u8 }
+    fn GetUniformBlockIndex(program: u32, name: Name) -> u32 {
+        location(name, |recording| &mut recording.uniform_block_indices) as u32
+    }
+    fn GetUniformIndices(program: u32, count: i32, names: *const *const c_char, indices: Out<u32>) {
+        for index in 0..count as usize {
+            let name = Name(*names.add(index));
+            indices.write(index, location(name, |recording| &mut recording.uniform_locations) as u32)
+        }
+    }
+    fn GetUniformLocation(program: u32, name: Name) -> i32 {
+        location(name, |recording| &mut recording.uniform_locations)
+    }
+    fn LinkProgram(program: u32) {}
+    fn MapBufferRange(target: u32, offset: isize, length: isize, access: u32) -> *mut c_void {
+        // u64 words keep the memory aligned for every element type
+        let mut memory = vec![0_u64; (length as usize).div_ceil(8)].into_boxed_slice();
+        let data = memory.as_mut_ptr() as *mut c_void;
+        with_recording(|recording| recording.mappings.push(memory));
+        data
+    }
+    fn MemoryBarrier(barriers: u32) {}
+    fn PatchParameteri(pname: u32, value: i32) {}
+    fn PixelStorei(pname: u32, param: i32) {}
+    fn ProgramBinary(program: u32, binary_format: u32, binary: *const c_void, length: i32) {}
+    fn ProgramParameteri(program: u32, pname: u32, value: i32) {}
+    fn ReadPixels(x: i32, y: i32, width: i32, height: i32, format: u32, pixel_type: u32, pixels: Out<c_void>) {}
+    fn RenderbufferStorage(target: u32, internal_format: u32, width: i32, height: i32) {}
+    fn SamplerParameterf(sampler: u32, pname: u32, param: f32) {}
+    fn SamplerParameterfv(sampler: u32, pname: u32, params: *const f32) {}
+    fn SamplerParameteri(sampler: u32, pname: u32, param: i32) {}
+    fn ShaderBinary(count: i32, shaders: *const u32, binary_format: u32, binary: *const c_void, length: i32) {}
+    fn ShaderSource(shader: u32, count: i32, string: *const *const c_char, length: *const i32) {}
+    fn ShaderStorageBlockBinding(program: u32, block_index: u32, block_binding: u32) {}
+    fn SpecializeShader(shader: u32, entry_point: Name, count: u32, constant_index: *const u32, constant_value: *const u32) {}
+    fn TexImage2D(
+        target: u32,
+        level: i32,
+        internal_format: i32,
+        width: i32,
+        height: i32,
+        border: i32,
+        format: u32,
+        pixel_type: u32,
+        pixels: *const c_void
+    ) {}
+    fn TexParameterf(target: u32, pname: u32, param: f32) {}
+    fn TexParameterfv(target: u32, pname: u32, params: *const f32) {}
+    fn TexParameteri(target: u32, pname: u32, param: i32) {}
+    fn TexStorage2D(target: u32, levels: i32, internal_format: u32, width: i32, height: i32) {}
+    fn TexSubImage2D(
+        target: u32,
+        level: i32,
+        x_offset: i32,
+        y_offset: i32,
+        width: i32,
+        height: i32,
+        format: u32,
+        pixel_type: u32,
+        pixels: *const c_void
+    ) {}
+    fn Uniform1d(location: i32, v0: f64) {}
+    fn Uniform1f(location: i32, v0: f32) {}
+    fn Uniform1i(location: i32, v0: i32) {}
+    fn Uniform1ui(location: i32, v0: u32) {}
+    fn Uniform1dv(location: i32, count: i32, value: *const f64) {}
+    fn Uniform1fv(location: i32, count: i32, value: *const f32) {}
+    fn Uniform1iv(location: i32, count: i32, value: *const i32) {}
+    fn Uniform1uiv(location: i32, count: i32, value: *const u32) {}
+    fn Uniform2dv(location: i32, count: i32, value: *const f64) {}
+    fn Uniform2fv(location: i32, count: i32, value: *const f32) {}
+    fn Uniform2iv(location: i32, count: i32, value: *const i32) {}
+    fn Uniform2uiv(location: i32, count: i32, value: *const u32) {}
+    fn Uniform3dv(location: i32, count: i32, value: *const f64) {}
+    fn Uniform3fv(location: i32, count: i32, value: *const f32) {}
+    fn Uniform3iv(location: i32, count: i32, value: *const i32) {}
+    fn Uniform3uiv(location: i32, count: i32, value: *const u32) {}
+    fn Uniform4dv(location: i32, count: i32, value: *const f64) {}
+    fn Uniform4f(location: i32, v0: f32, v1: f32, v2: f32, v3: f32) {}
+    fn Uniform4fv(location: i32, count: i32, value: *const f32) {}
+    fn Uniform4iv(location: i32, count: i32, value: *const i32) {}
+    fn Uniform4uiv(location: i32, count: i32, value: *const u32) {}
+    fn UniformBlockBinding(program: u32, block_index: u32, block_binding: u32) {}
+    fn UniformMatrix2dv(location: i32, count: i32, transpose: u8, value: *const f64) {}
+    fn UniformMatrix2fv(location: i32, count: i32, transpose: u8, value: *const f32) {}
+    fn UniformMatrix2x3dv(location: i32, count: i32, transpose: u8, value: *const f64) {}
+    fn UniformMatrix2x3fv(location: i32, count: i32, transpose: u8, value: *const f32) {}
+    fn UniformMatrix2x4dv(location: i32, count: i32, transpose: u8, value: *const f64) {}
+    fn UniformMatrix2x4fv(location: i32, count: i32, transpose: u8, value: *const f32) {}
+    fn UniformMatrix3dv(location: i32, count: i32, transpose: u8, value: *const f64) {}
+    fn UniformMatrix3fv(location: i32, count: i32, transpose: u8, value: *const f32) {}
+    fn UniformMatrix3x2dv(location: i32, count: i32, transpose: u8, value: *const f64) {}
+    fn UniformMatrix3x2fv(location: i32, count: i32, transpose: u8, value: *const f32) {}
+    fn UniformMatrix3x4dv(location: i32, count: i32, transpose: u8, value: *const f64) {}
+    fn UniformMatrix3x4fv(location: i32, count: i32, transpose: u8, value: *const f32) {}
+    fn UniformMatrix4dv(location: i32, count: i32, transpose: u8, value: *const f64) {}
+    fn UniformMatrix4fv(location: i32, count: i32, transpose: u8, value: *const f32) {}
+    fn UniformMatrix4x2dv(location: i32, count: i32, transpose: u8, value: *const f64) {}
+    fn UniformMatrix4x2fv(location: i32, count: i32, transpose: u8, value: *const f32) {}
+    fn UniformMatrix4x3dv(location: i32, count: i32, transpose: u8, value: *const f64) {}
+    fn UniformMatrix4x3fv(location: i32, count: i32, transpose: u8, value: *const f32) {}
+    fn UnmapBuffer(target: u32) -> u8 { gl::TRUE }
+    fn UseProgram(program: u32) {}
+    fn UseProgramStages(pipeline: u32, stages: u32, program: u32) {}
+    fn ValidateProgramPipeline(pipeline: u32) {}
+    fn VertexAttribIPointer(index: u32, size: i32, attribute_type: u32, stride: i32, pointer: *const c_void) {}
+    fn VertexAttribPointer(index: u32, size: i32, attribute_type: u32, normalized: u8, stride: i32, pointer: *const c_void) {}
+    fn Viewport(x: i32, y: i32, width: i32, height: i32) {}
+}
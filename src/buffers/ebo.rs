@@ -1,40 +1,56 @@
-use super::{DrawTarget, DrawUsage, Vao};
-use std::{ffi, mem, ptr};
+use std::marker::PhantomData;
 
-pub struct Ebo(u32);
+use super::{typed_buffer_methods, BufferMapping, DrawTarget, DrawUsage, MapFlags, RawBuffer, Vao};
+use crate::AttributeType;
 
-impl Ebo {
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// The types OpenGL accepts as element indices: [`u8`], [`u16`] and [`u32`].
+pub trait IndexType: sealed::Sealed {
+    const TYPE: AttributeType;
+}
+
+macro_rules! index_types {
+    ($($tp:ident),*) => {
+        $(impl sealed::Sealed for $tp {}
+
+        impl IndexType for $tp {
+            const TYPE: AttributeType = AttributeType::$tp;
+        })*
+    };
+}
+
+index_types!(u8, u16, u32);
+
+/// An element buffer holding indices of type `I`.
+pub struct Ebo<I: IndexType> {
+    raw: RawBuffer,
+    data: PhantomData<I>,
+}
+
+impl<I: IndexType> Ebo<I> {
     pub fn new(vao: &Vao) -> Self {
         unsafe { vao.bind() };
-        let mut id = 0;
-        unsafe { gl::GenBuffers(1, ptr::addr_of_mut!(id)) };
 
-        Self(id)
+        Self {
+            raw: RawBuffer::new(DrawTarget::ElementArrayBuffer),
+            data: PhantomData,
+        }
     }
 
-    pub fn bind_data<T>(&self, data: &[T]) {
+    pub fn bind_data(&self, data: &[I]) {
         self.bind_data_ex(data, DrawTarget::ElementArrayBuffer, DrawUsage::StaticDraw);
     }
 
-    pub fn bind_data_ex<T>(&self, data: &[T], draw_target: DrawTarget, draw_type: DrawUsage) {
-        unsafe {
-            gl::BindBuffer(draw_target as u32, self.0);
-            gl::BufferData(
-                gl::ELEMENT_ARRAY_BUFFER,
-                (mem::size_of_val(data)) as isize,
-                data.as_ptr() as *const ffi::c_void,
-                draw_type as u32,
-            )
-        }
+    pub fn bind_data_ex(&self, data: &[I], draw_target: DrawTarget, draw_type: DrawUsage) {
+        self.raw.bind_data(data, draw_target, draw_type)
     }
 
     pub fn bind(&self) {
-        unsafe { gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, self.0) }
+        self.raw.bind()
     }
-}
 
-impl Drop for Ebo {
-    fn drop(&mut self) {
-        unsafe { gl::DeleteBuffers(1, ptr::addr_of!(self.0)) }
-    }
+    typed_buffer_methods!(I);
 }
@@ -1,12 +1,25 @@
 mod ebo;
+mod raw;
+mod ring;
+mod ssbo;
+mod ubo;
 mod vao;
 mod vbo;
 
+pub use raw::BufferMapping;
+pub(crate) use raw::{typed_buffer_methods, RawBuffer};
+
 pub use ebo::*;
+pub use ring::*;
+pub use ssbo::*;
+pub use ubo::*;
 pub use vao::*;
 pub use vbo::*;
 
+use std::ops;
+
 #[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum DrawUsage {
     StreamDraw = gl::STREAM_DRAW,
     StreamRead = gl::STREAM_READ,
@@ -19,7 +32,60 @@ pub enum DrawUsage {
     DynamicCopy = gl::DYNAMIC_COPY,
 }
 
+impl DrawUsage {
+    /// The flags [`Vbo::map_range`] and [`Ebo::map_range`] use for buffers with this usage.
+    ///
+    /// Stream buffers are rewritten as a whole, so their old contents are discarded,
+    /// dynamic buffers only discard the mapped range and buffers read by the
+    /// application keep their contents.
+    pub const fn default_map_flags(self) -> MapFlags {
+        match self {
+            Self::StreamDraw => MapFlags(gl::MAP_WRITE_BIT | gl::MAP_INVALIDATE_BUFFER_BIT),
+            Self::DynamicDraw => MapFlags(gl::MAP_WRITE_BIT | gl::MAP_INVALIDATE_RANGE_BIT),
+            Self::StaticDraw => MapFlags::WRITE,
+            Self::StreamRead | Self::StaticRead | Self::DynamicRead => MapFlags::READ,
+            Self::StreamCopy | Self::StaticCopy | Self::DynamicCopy => {
+                MapFlags(gl::MAP_READ_BIT | gl::MAP_WRITE_BIT)
+            }
+        }
+    }
+}
+
+/// Access flags for mapping a buffer into memory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MapFlags(u32);
+
+impl MapFlags {
+    pub const READ: Self = Self(gl::MAP_READ_BIT);
+    pub const WRITE: Self = Self(gl::MAP_WRITE_BIT);
+    /// The previous contents of the mapped range may be discarded.
+    pub const INVALIDATE_RANGE: Self = Self(gl::MAP_INVALIDATE_RANGE_BIT);
+    /// The previous contents of the whole buffer may be discarded.
+    pub const INVALIDATE_BUFFER: Self = Self(gl::MAP_INVALIDATE_BUFFER_BIT);
+    /// Don't wait for pending draw calls using the buffer,
+    /// the caller must not overwrite data that is still in use.
+    pub const UNSYNCHRONIZED: Self = Self(gl::MAP_UNSYNCHRONIZED_BIT);
+
+    pub(crate) const fn bits(self) -> u32 {
+        self.0
+    }
+
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl ops::BitOr for MapFlags {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
 #[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum DrawTarget {
     ArrayBuffer = gl::ARRAY_BUFFER,
     AtomicCounterBuffer = gl::ATOMIC_COUNTER_BUFFER,
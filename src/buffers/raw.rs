@@ -0,0 +1,350 @@
+use std::{
+    cell::Cell,
+    ffi::c_void,
+    mem,
+    ops::{Deref, DerefMut},
+    ptr, slice,
+};
+
+use super::{DrawTarget, DrawUsage, MapFlags};
+
+/// The untyped buffer object behind [`super::Vbo`] and [`super::Ebo`].
+/// Sizes are stored in bytes, the typed wrappers convert them to elements.
+pub(crate) struct RawBuffer {
+    id: u32,
+    target: DrawTarget,
+    len: Cell<usize>,
+    capacity: Cell<usize>,
+    usage: Cell<DrawUsage>,
+    mapped: Cell<bool>,
+}
+
+impl RawBuffer {
+    pub(crate) fn new(target: DrawTarget) -> Self {
+        let mut id = 0_u32;
+        unsafe { gl::GenBuffers(1, ptr::addr_of_mut!(id)) };
+
+        Self {
+            id,
+            target,
+            len: Cell::new(0),
+            capacity: Cell::new(0),
+            usage: Cell::new(DrawUsage::StaticDraw),
+            mapped: Cell::new(false),
+        }
+    }
+
+    pub(crate) fn bind_data<T>(&self, data: &[T], draw_target: DrawTarget, draw_type: DrawUsage) {
+        self.assert_unmapped();
+        unsafe {
+            gl::BindBuffer(draw_target as u32, self.id);
+            gl::BufferData(
+                draw_target as u32,
+                (mem::size_of_val(data)) as isize,
+                data.as_ptr() as *const c_void,
+                draw_type as u32,
+            )
+        }
+
+        self.len.set(mem::size_of_val(data));
+        self.capacity.set(mem::size_of_val(data));
+        self.usage.set(draw_type);
+    }
+
+    /// Replace the storage with `capacity` bytes of undefined contents.
+    pub(crate) fn allocate(&self, capacity: usize, usage: DrawUsage) {
+        self.assert_unmapped();
+        unsafe {
+            gl::BindBuffer(gl::COPY_WRITE_BUFFER, self.id);
+            gl::BufferData(
+                gl::COPY_WRITE_BUFFER,
+                capacity as isize,
+                ptr::null(),
+                usage as u32,
+            )
+        }
+
+        self.len.set(0);
+        self.capacity.set(capacity);
+        self.usage.set(usage);
+    }
+
+    /// Grow the storage to at least `capacity` bytes, keeping the contents.
+    pub(crate) fn reserve(&self, capacity: usize) {
+        // Reallocating unmaps the buffer behind a live mapping's back
+        self.assert_unmapped();
+        if capacity <= self.capacity.get() {
+            return;
+        }
+
+        let len = self.len.get();
+        if len == 0 {
+            return self.allocate(capacity, self.usage.get());
+        }
+
+        // The buffer's name has to stay the same because vertex arrays refer
+        // to it, so the contents take a trip through a temporary buffer.
+        let staging = Self::new(DrawTarget::CopyReadBuffer);
+        staging.allocate(len, DrawUsage::StreamCopy);
+        unsafe {
+            Self::copy(self.id, staging.id, len);
+            gl::BindBuffer(gl::COPY_WRITE_BUFFER, self.id);
+            gl::BufferData(
+                gl::COPY_WRITE_BUFFER,
+                capacity as isize,
+                ptr::null(),
+                self.usage.get() as u32,
+            );
+            Self::copy(staging.id, self.id, len);
+        }
+
+        self.capacity.set(capacity);
+    }
+
+    unsafe fn copy(from: u32, to: u32, size: usize) {
+        gl::BindBuffer(gl::COPY_READ_BUFFER, from);
+        gl::BindBuffer(gl::COPY_WRITE_BUFFER, to);
+        gl::CopyBufferSubData(
+            gl::COPY_READ_BUFFER,
+            gl::COPY_WRITE_BUFFER,
+            0,
+            0,
+            size as isize,
+        )
+    }
+
+    /// Let the driver hand out fresh storage of the same size, so writing
+    /// to the buffer doesn't wait for draw calls still using the old contents.
+    pub(crate) fn orphan(&self) {
+        self.allocate(self.capacity.get(), self.usage.get())
+    }
+
+    /// Write `data` starting `offset` bytes into the buffer.
+    pub(crate) fn update_range<T>(&self, offset: usize, data: &[T]) {
+        self.assert_unmapped();
+        let end = offset
+            .checked_add(mem::size_of_val(data))
+            .filter(|&end| end <= self.capacity.get())
+            .expect("The update goes outside of the buffer's capacity");
+
+        unsafe {
+            gl::BindBuffer(gl::COPY_WRITE_BUFFER, self.id);
+            gl::BufferSubData(
+                gl::COPY_WRITE_BUFFER,
+                offset as isize,
+                mem::size_of_val(data) as isize,
+                data.as_ptr() as *const c_void,
+            )
+        }
+
+        self.len.set(self.len.get().max(end));
+    }
+
+    /// Map `len` elements of `T` starting `offset` bytes into the buffer.
+    pub(crate) fn map_range<T: bytemuck::Pod>(
+        &self,
+        offset: usize,
+        len: usize,
+        flags: MapFlags,
+    ) -> BufferMapping<'_, T> {
+        self.assert_unmapped();
+        let end = len
+            .checked_mul(mem::size_of::<T>())
+            .and_then(|size| offset.checked_add(size))
+            .filter(|&end| end <= self.capacity.get())
+            .expect("The mapped range goes outside of the buffer's capacity");
+        let size = end - offset;
+        assert!(
+            offset.is_multiple_of(mem::align_of::<T>()),
+            "The mapped range is not aligned for its type"
+        );
+        assert!(
+            flags.contains(MapFlags::READ) || flags.contains(MapFlags::WRITE),
+            "A buffer has to be mapped for reading, writing or both"
+        );
+
+        let data = if size == 0 {
+            ptr::NonNull::dangling().as_ptr()
+        } else {
+            let data = unsafe {
+                gl::BindBuffer(gl::COPY_WRITE_BUFFER, self.id);
+                gl::MapBufferRange(
+                    gl::COPY_WRITE_BUFFER,
+                    offset as isize,
+                    size as isize,
+                    flags.bits(),
+                )
+            };
+            assert!(!data.is_null(), "The buffer could not be mapped");
+            self.mapped.set(true);
+
+            data as *mut T
+        };
+
+        if flags.contains(MapFlags::WRITE) {
+            self.len.set(self.len.get().max(end));
+        }
+
+        BufferMapping {
+            buffer: self,
+            data,
+            len,
+            writable: flags.contains(MapFlags::WRITE),
+        }
+    }
+
+    fn assert_unmapped(&self) {
+        assert!(!self.mapped.get(), "The buffer is currently mapped");
+    }
+
+    pub(crate) fn bind(&self) {
+        unsafe { gl::BindBuffer(self.target as u32, self.id) }
+    }
+
+    /// Bind to `target` instead of the buffer's own target.
+    pub(crate) fn bind_to(&self, target: DrawTarget) {
+        unsafe { gl::BindBuffer(target as u32, self.id) }
+    }
+
+    /// Bind to the indexed binding point `index` of the buffer's target.
+    pub(crate) fn bind_base(&self, index: u32) {
+        unsafe { gl::BindBufferBase(self.target as u32, index, self.id) }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.len.get()
+    }
+
+    pub(crate) fn capacity(&self) -> usize {
+        self.capacity.get()
+    }
+
+    pub(crate) fn usage(&self) -> DrawUsage {
+        self.usage.get()
+    }
+}
+
+impl Drop for RawBuffer {
+    fn drop(&mut self) {
+        unsafe { gl::DeleteBuffers(1, ptr::addr_of!(self.id)) }
+    }
+}
+
+/// A range of a buffer mapped into memory, unmapped when dropped.
+///
+/// Writing through a mapping requires [`MapFlags::WRITE`],
+/// mutably dereferencing a read-only mapping panics.
+pub struct BufferMapping<'a, T> {
+    buffer: &'a RawBuffer,
+    data: *mut T,
+    len: usize,
+    writable: bool,
+}
+
+impl<T> Deref for BufferMapping<'_, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { slice::from_raw_parts(self.data, self.len) }
+    }
+}
+
+impl<T> DerefMut for BufferMapping<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        assert!(self.writable, "The buffer is not mapped for writing");
+        unsafe { slice::from_raw_parts_mut(self.data, self.len) }
+    }
+}
+
+impl<T> Drop for BufferMapping<'_, T> {
+    fn drop(&mut self) {
+        if !self.buffer.mapped.get() {
+            return;
+        }
+
+        unsafe {
+            gl::BindBuffer(gl::COPY_WRITE_BUFFER, self.buffer.id);
+            gl::UnmapBuffer(gl::COPY_WRITE_BUFFER);
+        }
+        self.buffer.mapped.set(false);
+    }
+}
+
+/// The methods shared by the typed buffers, `$elem` is their element type.
+macro_rules! typed_buffer_methods {
+    ($elem:ident) => {
+        /// The number of elements written to the buffer so far.
+        pub fn len(&self) -> usize {
+            self.raw.len() / std::mem::size_of::<$elem>().max(1)
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.len() == 0
+        }
+
+        /// The number of elements the buffer has storage for.
+        pub fn capacity(&self) -> usize {
+            self.raw.capacity() / std::mem::size_of::<$elem>().max(1)
+        }
+
+        pub fn usage(&self) -> DrawUsage {
+            self.raw.usage()
+        }
+
+        /// Replace the storage with room for `capacity` elements of undefined contents.
+        pub fn allocate(&self, capacity: usize, usage: DrawUsage) {
+            let capacity = capacity
+                .checked_mul(std::mem::size_of::<$elem>())
+                .expect("The buffer's capacity doesn't fit into usize");
+            self.raw.allocate(capacity, usage)
+        }
+
+        /// Grow the storage to at least `capacity` elements, keeping the contents.
+        pub fn reserve(&self, capacity: usize) {
+            let capacity = capacity
+                .checked_mul(std::mem::size_of::<$elem>())
+                .expect("The buffer's capacity doesn't fit into usize");
+            self.raw.reserve(capacity)
+        }
+
+        /// Discard the contents and get new storage of the same capacity,
+        /// so the buffer can be refilled without waiting for pending draw calls.
+        pub fn orphan(&self) {
+            self.raw.orphan()
+        }
+
+        /// Overwrite the elements starting at `offset`, the range has to fit in the capacity.
+        pub fn update_range(&self, offset: usize, data: &[$elem]) {
+            let offset = offset
+                .checked_mul(std::mem::size_of::<$elem>())
+                .expect("The update goes outside of the buffer's capacity");
+            self.raw.update_range(offset, data)
+        }
+
+        /// Map `len` elements starting at `offset`, using the flags of the buffer's usage.
+        pub fn map(&self, offset: usize, len: usize) -> BufferMapping<'_, $elem>
+        where
+            $elem: bytemuck::Pod,
+        {
+            self.map_range(offset, len, self.usage().default_map_flags())
+        }
+
+        /// Map `len` elements starting at `offset`.
+        pub fn map_range(
+            &self,
+            offset: usize,
+            len: usize,
+            flags: MapFlags,
+        ) -> BufferMapping<'_, $elem>
+        where
+            $elem: bytemuck::Pod,
+        {
+            let offset = offset
+                .checked_mul(std::mem::size_of::<$elem>())
+                .expect("The mapped range goes outside of the buffer's capacity");
+            self.raw.map_range(offset, len, flags)
+        }
+    };
+}
+
+pub(crate) use typed_buffer_methods;
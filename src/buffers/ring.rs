@@ -0,0 +1,228 @@
+use std::{error, fmt, mem, ptr};
+
+use super::{DrawTarget, IndexType};
+use crate::capabilities::{gl_version, has_extension};
+
+/// The reason a [`RingBuffer`] can't be created.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RingBufferError {
+    /// The driver has neither OpenGL 4.4 nor `GL_ARB_buffer_storage`.
+    Unsupported,
+    /// Segments of 0 bytes can't hold any data.
+    EmptySegment,
+    /// A ring buffer needs at least one frame in flight.
+    NoFrames,
+    /// The segments together are larger than a buffer can be.
+    TooLarge,
+}
+
+impl fmt::Display for RingBufferError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsupported => write!(
+                f,
+                "Ring buffers require OpenGL 4.4 or the GL_ARB_buffer_storage extension"
+            ),
+            Self::EmptySegment => write!(f, "The ring buffer's segments are empty"),
+            Self::NoFrames => write!(f, "A ring buffer needs at least one segment"),
+            Self::TooLarge => write!(f, "The ring buffer is too large"),
+        }
+    }
+}
+
+impl error::Error for RingBufferError {}
+
+/// A region of a [`RingBuffer`] written during the current frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RingSlice {
+    /// The offset in bytes from the start of the buffer.
+    pub offset: usize,
+    /// The number of elements.
+    pub len: usize,
+    element_size: usize,
+}
+
+impl RingSlice {
+    /// The size in bytes.
+    pub fn size(&self) -> usize {
+        self.len * self.element_size
+    }
+
+    /// The index of the first element when the buffer is viewed as an array
+    /// of the pushed type, e.g. the `first` argument of [`crate::DrawLayer::draw_arrays`].
+    pub fn first(&self) -> i32 {
+        (self.offset / self.element_size.max(1)) as i32
+    }
+}
+
+/// A persistently mapped buffer for data that changes every frame.
+///
+/// The buffer is split into one segment per frame in flight. Data pushed
+/// during a frame goes into the current segment, [`RingBuffer::next_frame`]
+/// fences it and moves on, waiting only if the GPU is still reading the next
+/// segment. Requires OpenGL 4.4 or `ARB_buffer_storage`.
+pub struct RingBuffer {
+    id: u32,
+    data: *mut u8,
+    segment_size: usize,
+    fences: Vec<gl::types::GLsync>,
+    segment: usize,
+    head: usize,
+    uniform_alignment: usize,
+}
+
+impl RingBuffer {
+    /// Create a buffer with `frames` segments of `segment_size` bytes each.
+    pub fn new(segment_size: usize, frames: usize) -> Result<Self, RingBufferError> {
+        if !Self::supported() {
+            return Err(RingBufferError::Unsupported);
+        }
+        if segment_size == 0 {
+            return Err(RingBufferError::EmptySegment);
+        }
+        if frames == 0 {
+            return Err(RingBufferError::NoFrames);
+        }
+        let size = segment_size
+            .checked_mul(frames)
+            .filter(|&size| isize::try_from(size).is_ok())
+            .ok_or(RingBufferError::TooLarge)?;
+
+        let mut id = 0_u32;
+        let mut uniform_alignment = 0;
+        let flags = gl::MAP_WRITE_BIT | gl::MAP_PERSISTENT_BIT | gl::MAP_COHERENT_BIT;
+
+        let data = unsafe {
+            gl::GetIntegerv(gl::UNIFORM_BUFFER_OFFSET_ALIGNMENT, &mut uniform_alignment);
+            gl::GenBuffers(1, ptr::addr_of_mut!(id));
+            gl::BindBuffer(gl::COPY_WRITE_BUFFER, id);
+            gl::BufferStorage(gl::COPY_WRITE_BUFFER, size as isize, ptr::null(), flags);
+            gl::MapBufferRange(gl::COPY_WRITE_BUFFER, 0, size as isize, flags)
+        };
+        assert!(!data.is_null(), "The ring buffer could not be mapped");
+
+        Ok(Self {
+            id,
+            data: data as *mut u8,
+            segment_size,
+            fences: vec![ptr::null(); frames],
+            segment: 0,
+            head: 0,
+            uniform_alignment: uniform_alignment.max(1) as usize,
+        })
+    }
+
+    /// Whether the current context can create ring buffers.
+    pub fn supported() -> bool {
+        gl::BufferStorage::is_loaded()
+            && (gl_version() >= (4, 4) || has_extension(c"GL_ARB_buffer_storage"))
+    }
+
+    /// Copy `data` into the current segment at an offset aligned to `alignment` bytes.
+    /// Returns [`None`] if the segment is full.
+    pub fn push<T: bytemuck::Pod>(&mut self, data: &[T], alignment: usize) -> Option<RingSlice> {
+        let bytes: &[u8] = bytemuck::cast_slice(data);
+        let segment_start = self.segment * self.segment_size;
+        // Offsets are aligned from the start of the buffer, which is what the GPU sees
+        let offset = (segment_start + self.head).next_multiple_of(alignment.max(1));
+        if offset + bytes.len() > segment_start + self.segment_size {
+            return None;
+        }
+
+        unsafe { ptr::copy_nonoverlapping(bytes.as_ptr(), self.data.add(offset), bytes.len()) };
+        self.head = offset + bytes.len() - segment_start;
+
+        Some(RingSlice {
+            offset,
+            len: data.len(),
+            element_size: mem::size_of::<T>(),
+        })
+    }
+
+    /// Push vertices so that [`RingSlice::first`] is the index of the first one.
+    pub fn push_vertices<V: bytemuck::Pod>(&mut self, vertices: &[V]) -> Option<RingSlice> {
+        self.push(vertices, mem::size_of::<V>())
+    }
+
+    /// Push indices, [`RingSlice::offset`] is the `indicies` argument
+    /// of [`crate::DrawLayer::draw_elements`].
+    pub fn push_indices<I>(&mut self, indices: &[I]) -> Option<RingSlice>
+    where
+        I: IndexType + bytemuck::Pod,
+    {
+        self.push(indices, mem::size_of::<I>())
+    }
+
+    /// Push a value at the alignment the driver requires for uniform buffer ranges.
+    pub fn push_uniform<T: bytemuck::Pod>(&mut self, value: &T) -> Option<RingSlice> {
+        self.push(std::slice::from_ref(value), self.uniform_alignment)
+    }
+
+    /// Bind the whole buffer, e.g. as the source of vertex attributes.
+    pub fn bind(&self, target: DrawTarget) {
+        unsafe { gl::BindBuffer(target as u32, self.id) }
+    }
+
+    /// Bind a slice to the indexed binding point `index` of an uniform,
+    /// shader storage, atomic counter or transform feedback `target`.
+    pub fn bind_range(&self, target: DrawTarget, index: u32, slice: RingSlice) {
+        unsafe {
+            gl::BindBufferRange(
+                target as u32,
+                index,
+                self.id,
+                slice.offset as isize,
+                slice.size() as isize,
+            )
+        }
+    }
+
+    /// Finish the current frame and move on to the next segment. Call this after
+    /// the draw calls using the frame's data, it blocks until the GPU is done
+    /// with the segment that is reused.
+    pub fn next_frame(&mut self) {
+        unsafe {
+            self.fences[self.segment] = gl::FenceSync(gl::SYNC_GPU_COMMANDS_COMPLETE, 0);
+        }
+
+        self.segment = (self.segment + 1) % self.fences.len();
+        self.head = 0;
+
+        let fence = mem::replace(&mut self.fences[self.segment], ptr::null());
+        if !fence.is_null() {
+            unsafe { Self::wait(fence) }
+        }
+    }
+
+    unsafe fn wait(fence: gl::types::GLsync) {
+        const TIMEOUT_NS: u64 = 1_000_000_000;
+
+        loop {
+            let status = gl::ClientWaitSync(fence, gl::SYNC_FLUSH_COMMANDS_BIT, TIMEOUT_NS);
+            if status != gl::TIMEOUT_EXPIRED {
+                break;
+            }
+        }
+
+        gl::DeleteSync(fence)
+    }
+
+    /// The number of bytes left in the current segment.
+    pub fn remaining(&self) -> usize {
+        self.segment_size - self.head
+    }
+}
+
+impl Drop for RingBuffer {
+    fn drop(&mut self) {
+        unsafe {
+            for fence in self.fences.iter().filter(|fence| !fence.is_null()) {
+                gl::DeleteSync(*fence);
+            }
+
+            gl::BindBuffer(gl::COPY_WRITE_BUFFER, self.id);
+            gl::UnmapBuffer(gl::COPY_WRITE_BUFFER);
+            gl::DeleteBuffers(1, ptr::addr_of!(self.id))
+        }
+    }
+}
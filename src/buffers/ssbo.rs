@@ -0,0 +1,56 @@
+use std::marker::PhantomData;
+
+use super::{typed_buffer_methods, BufferMapping, DrawTarget, DrawUsage, MapFlags, RawBuffer};
+
+/// A shader storage buffer holding values of `T`, read and written by shaders
+/// through a `buffer` block.
+///
+/// ```ignore
+/// let particles = StorageBuffer::new();
+/// particles.bind_data_ex(&initial, DrawUsage::DynamicCopy);
+/// particles.bind_base(0);
+/// program.as_program().bind_storage_block("Particles", 0);
+/// ```
+pub struct StorageBuffer<T> {
+    raw: RawBuffer,
+    data: PhantomData<T>,
+}
+
+impl<T> StorageBuffer<T> {
+    pub fn new() -> Self {
+        Self {
+            raw: RawBuffer::new(DrawTarget::ShaderStorageBuffer),
+            data: PhantomData,
+        }
+    }
+
+    pub fn bind_data(&self, data: &[T]) {
+        self.bind_data_ex(data, DrawUsage::DynamicDraw);
+    }
+
+    pub fn bind_data_ex(&self, data: &[T], draw_type: DrawUsage) {
+        self.raw
+            .bind_data(data, DrawTarget::ShaderStorageBuffer, draw_type)
+    }
+
+    pub fn bind(&self) {
+        self.raw.bind()
+    }
+
+    /// Bind the buffer to the shader storage binding point `binding`.
+    pub fn bind_base(&self, binding: u32) {
+        self.raw.bind_base(binding)
+    }
+
+    pub(crate) fn bind_to(&self, target: DrawTarget) {
+        self.raw.bind_to(target)
+    }
+
+    typed_buffer_methods!(T);
+}
+
+impl<T> Default for StorageBuffer<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
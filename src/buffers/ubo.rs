@@ -0,0 +1,52 @@
+use std::{cell::RefCell, marker::PhantomData};
+
+use super::{DrawTarget, DrawUsage, RawBuffer};
+use crate::Std140;
+
+/// A buffer holding a single `T` laid out for a `std140` uniform block.
+///
+/// ```ignore
+/// #[derive(Std140)]
+/// #[repr(C)]
+/// struct Camera {
+///     view: glm::Mat4,
+///     projection: glm::Mat4,
+///     position: glm::Vec3,
+/// }
+///
+/// let camera = UniformBuffer::new(&camera);
+/// camera.bind_base(0);
+/// program.bind_uniform_block("Camera", 0);
+/// ```
+pub struct UniformBuffer<T: Std140> {
+    raw: RawBuffer,
+    /// Reused between updates to avoid an allocation per upload.
+    bytes: RefCell<Vec<u8>>,
+    data: PhantomData<T>,
+}
+
+impl<T: Std140> UniformBuffer<T> {
+    pub fn new(value: &T) -> Self {
+        let buffer = Self {
+            raw: RawBuffer::new(DrawTarget::UniformBuffer),
+            bytes: RefCell::new(vec![0; T::SIZE]),
+            data: PhantomData,
+        };
+        buffer.raw.allocate(T::SIZE, DrawUsage::DynamicDraw);
+        buffer.update(value);
+
+        buffer
+    }
+
+    /// Upload a new value.
+    pub fn update(&self, value: &T) {
+        let mut bytes = self.bytes.borrow_mut();
+        value.write_std140(&mut bytes);
+        self.raw.update_range(0, &bytes);
+    }
+
+    /// Bind the buffer to the uniform block binding point `binding`.
+    pub fn bind_base(&self, binding: u32) {
+        self.raw.bind_base(binding)
+    }
+}
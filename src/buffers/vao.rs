@@ -13,6 +13,10 @@ impl Vao {
         Self(id)
     }
 
+    /// Bind the vertex array.
+    ///
+    /// # Safety
+    /// OpenGL must be loaded and a context must be current on this thread.
     pub unsafe fn bind(&self) {
         gl::BindVertexArray(self.0)
     }
@@ -1,42 +1,34 @@
-use std::{
-    ffi::c_void,
-    mem::{self, MaybeUninit},
-    ptr,
-};
+use std::marker::PhantomData;
 
-use super::{DrawTarget, DrawUsage, Vao};
+use super::{typed_buffer_methods, BufferMapping, DrawTarget, DrawUsage, MapFlags, RawBuffer, Vao};
 
-pub struct Vbo(u32);
+/// A vertex buffer holding values of `V`.
+pub struct Vbo<V> {
+    raw: RawBuffer,
+    data: PhantomData<V>,
+}
 
-impl Vbo {
+impl<V> Vbo<V> {
     pub fn new(vao: &Vao) -> Self {
         unsafe { vao.bind() };
-        let mut id: MaybeUninit<u32> = MaybeUninit::uninit();
-        unsafe { gl::GenBuffers(1, id.as_mut_ptr()) };
-        let id = unsafe { id.assume_init() };
 
-        Self(id)
+        Self {
+            raw: RawBuffer::new(DrawTarget::ArrayBuffer),
+            data: PhantomData,
+        }
     }
 
-    pub fn bind_data<T>(&self, data: &[T]) {
+    pub fn bind_data(&self, data: &[V]) {
         self.bind_data_ex(data, DrawTarget::ArrayBuffer, DrawUsage::StaticDraw);
     }
 
-    pub fn bind_data_ex<T>(&self, data: &[T], draw_target: DrawTarget, draw_type: DrawUsage) {
-        unsafe {
-            gl::BindBuffer(draw_target as u32, self.0);
-            gl::BufferData(
-                gl::ARRAY_BUFFER,
-                (mem::size_of_val(data)) as isize,
-                data.as_ptr() as *const c_void,
-                draw_type as u32,
-            )
-        }
+    pub fn bind_data_ex(&self, data: &[V], draw_target: DrawTarget, draw_type: DrawUsage) {
+        self.raw.bind_data(data, draw_target, draw_type)
     }
-}
 
-impl Drop for Vbo {
-    fn drop(&mut self) {
-        unsafe { gl::DeleteBuffers(1, ptr::addr_of!(self.0)) }
+    pub fn bind(&self) {
+        self.raw.bind()
     }
+
+    typed_buffer_methods!(V);
 }
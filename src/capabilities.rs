@@ -0,0 +1,23 @@
+use std::{ffi::CStr, ptr};
+
+/// The version of the current context as `(major, minor)`.
+pub(crate) fn gl_version() -> (i32, i32) {
+    let (mut major, mut minor) = (0, 0);
+    unsafe {
+        gl::GetIntegerv(gl::MAJOR_VERSION, ptr::addr_of_mut!(major));
+        gl::GetIntegerv(gl::MINOR_VERSION, ptr::addr_of_mut!(minor));
+    }
+
+    (major, minor)
+}
+
+/// Whether the current context has the extension `name`, e.g. `GL_ARB_gl_spirv`.
+pub(crate) fn has_extension(name: &CStr) -> bool {
+    let mut count = 0;
+    unsafe { gl::GetIntegerv(gl::NUM_EXTENSIONS, ptr::addr_of_mut!(count)) };
+
+    (0..count.max(0) as u32).any(|index| {
+        let extension = unsafe { gl::GetStringi(gl::EXTENSIONS, index) };
+        !extension.is_null() && unsafe { CStr::from_ptr(extension as *const _) } == name
+    })
+}
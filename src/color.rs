@@ -1,4 +1,6 @@
-#[derive(Debug)]
+use crate::{GlslType, UniformResource};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Color {
     pub r: f32,
     pub g: f32,
@@ -20,3 +22,15 @@ impl Default for Color {
         Self::WHITE
     }
 }
+
+/// Uploaded as a `vec4`.
+impl UniformResource for Color {
+    #[inline]
+    unsafe fn uniform(&self, location: i32) {
+        gl::Uniform4f(location, self.r, self.g, self.b, self.a)
+    }
+
+    fn accepts(ty: GlslType) -> bool {
+        ty == GlslType::Vec4
+    }
+}
@@ -1,13 +1,27 @@
-use crate::{AttributeType, Color, Program, Vao};
+use crate::{
+    AttributeType, Backend, Color, ComputeProgram, DrawTarget, Ebo, Framebuffer, IndexType,
+    PixelBuffer, Program, ProgramPipeline, StorageBuffer, Texture, TextureError, Uniform,
+    UniformResource, Vao,
+};
 use std::{
     ffi::{c_void, CString},
-    ops,
+    ops, ptr,
 };
 
 #[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum DrawMode {
-    /* TODO */
+    Points = gl::POINTS,
+    Lines = gl::LINES,
+    LineStrip = gl::LINE_STRIP,
+    LineLoop = gl::LINE_LOOP,
     Triangles = gl::TRIANGLES,
+    TriangleStrip = gl::TRIANGLE_STRIP,
+    TriangleFan = gl::TRIANGLE_FAN,
+    LinesAdjacency = gl::LINES_ADJACENCY,
+    TrianglesAdjacency = gl::TRIANGLES_ADJACENCY,
+    /// Input for tessellation shaders, see [`DrawLayer::set_patch_vertices`].
+    Patches = gl::PATCHES,
 }
 
 pub struct ClearFlags(u32);
@@ -27,16 +41,75 @@ impl ops::BitOr for ClearFlags {
     }
 }
 
+/// Which writes of earlier shader invocations must be visible to later commands,
+/// see [`DrawLayer::memory_barrier`].
+/// Each flag names the way the data is read afterwards.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BarrierFlags(u32);
+
+impl BarrierFlags {
+    pub const VERTEX_ATTRIB_ARRAY: Self = Self(gl::VERTEX_ATTRIB_ARRAY_BARRIER_BIT);
+    pub const ELEMENT_ARRAY: Self = Self(gl::ELEMENT_ARRAY_BARRIER_BIT);
+    pub const UNIFORM: Self = Self(gl::UNIFORM_BARRIER_BIT);
+    pub const TEXTURE_FETCH: Self = Self(gl::TEXTURE_FETCH_BARRIER_BIT);
+    pub const SHADER_IMAGE_ACCESS: Self = Self(gl::SHADER_IMAGE_ACCESS_BARRIER_BIT);
+    /// Indirect draw and dispatch arguments.
+    pub const COMMAND: Self = Self(gl::COMMAND_BARRIER_BIT);
+    pub const PIXEL_BUFFER: Self = Self(gl::PIXEL_BUFFER_BARRIER_BIT);
+    pub const TEXTURE_UPDATE: Self = Self(gl::TEXTURE_UPDATE_BARRIER_BIT);
+    /// Buffer reads and writes through the API, including mapping.
+    pub const BUFFER_UPDATE: Self = Self(gl::BUFFER_UPDATE_BARRIER_BIT);
+    pub const FRAMEBUFFER: Self = Self(gl::FRAMEBUFFER_BARRIER_BIT);
+    pub const TRANSFORM_FEEDBACK: Self = Self(gl::TRANSFORM_FEEDBACK_BARRIER_BIT);
+    pub const ATOMIC_COUNTER: Self = Self(gl::ATOMIC_COUNTER_BARRIER_BIT);
+    pub const SHADER_STORAGE: Self = Self(gl::SHADER_STORAGE_BARRIER_BIT);
+    pub const CLIENT_MAPPED_BUFFER: Self = Self(gl::CLIENT_MAPPED_BUFFER_BARRIER_BIT);
+    pub const QUERY_BUFFER: Self = Self(gl::QUERY_BUFFER_BARRIER_BIT);
+    pub const ALL: Self = Self(gl::ALL_BARRIER_BITS);
+}
+
+impl ops::BitOr for BarrierFlags {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// How a shader accesses an image bound with [`DrawLayer::bind_image_texture`].
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageAccess {
+    ReadOnly = gl::READ_ONLY,
+    WriteOnly = gl::WRITE_ONLY,
+    ReadWrite = gl::READ_WRITE,
+}
+
 pub struct DrawLayer;
 
 impl DrawLayer {
     /// Initialize OpenGL and create a [`DrawLayer`].
     /// `loader` is the function which will be used to initialize OpenGL.
+    ///
+    /// # Safety
+    /// An OpenGL context must be current on this thread and `loader` must
+    /// return valid function pointers for it.
     pub unsafe fn new<T, F>(mut loader: F) -> Self
     where
         F: FnMut(&'static str) -> *const T,
     {
         gl::load_with(|s| loader(s) as *const c_void);
+        crate::shader::load_with(|s| loader(s) as *const c_void);
+        crate::texture::reset_capabilities();
+        Self
+    }
+
+    /// Initialize OpenGL with the functions of `backend`.
+    pub fn with_backend<B: Backend>(backend: &B) -> Self {
+        gl::load_with(|s| backend.get_proc_address(s));
+        crate::shader::load_with(|s| backend.get_proc_address(s));
+        crate::texture::reset_capabilities();
         Self
     }
 
@@ -55,6 +128,72 @@ impl DrawLayer {
         unsafe { program.use_internal() }
     }
 
+    /// Use the stages of `pipeline`, replacing the program set by [`Self::use_program`].
+    pub fn use_pipeline(&self, pipeline: &ProgramPipeline) {
+        unsafe {
+            // A program in use takes precedence over the bound pipeline.
+            gl::UseProgram(0);
+            gl::BindProgramPipeline(pipeline.get_inner());
+        }
+    }
+
+    /// Use a compute program for the following [`Self::dispatch`] calls.
+    pub fn use_compute_program(&self, program: &ComputeProgram) {
+        unsafe { program.as_program().use_internal() }
+    }
+
+    /// Run the current compute program with `x` * `y` * `z` work groups.
+    pub fn dispatch(&self, x: u32, y: u32, z: u32) {
+        unsafe { gl::DispatchCompute(x, y, z) }
+    }
+
+    /// Run the current compute program with the work group counts stored as a
+    /// [`crate::DispatchIndirectCommand`] `offset` bytes into `buffer`.
+    pub fn dispatch_indirect<T>(&self, buffer: &StorageBuffer<T>, offset: usize) {
+        assert!(
+            offset.is_multiple_of(4),
+            "The offset of an indirect dispatch must be a multiple of 4"
+        );
+        unsafe {
+            buffer.bind_to(DrawTarget::DispatchIndirectBuffer);
+            gl::DispatchComputeIndirect(offset as isize)
+        }
+    }
+
+    /// Make the writes of earlier shaders visible to the accesses named by `flags`.
+    pub fn memory_barrier(&self, flags: BarrierFlags) {
+        unsafe { gl::MemoryBarrier(flags.0) }
+    }
+
+    /// Bind `level` of `texture` to the image unit `unit` in the texture's format,
+    /// which has to be one of the formats images can have.
+    pub fn bind_image_texture(
+        &self,
+        unit: u32,
+        texture: &Texture,
+        level: i32,
+        access: ImageAccess,
+    ) -> Result<(), TextureError> {
+        if !texture.format().is_image_format() {
+            return Err(TextureError::UnsupportedImageFormat(texture.format()));
+        }
+        texture.check_level(level)?;
+
+        unsafe {
+            gl::BindImageTexture(
+                unit,
+                texture.get_inner(),
+                level,
+                gl::FALSE,
+                0,
+                access as u32,
+                texture.format() as u32,
+            )
+        }
+
+        Ok(())
+    }
+
     /// Draw the information behind [`Vao`] to the screen
     pub fn draw_arrays(&self, vao: &Vao, mode: DrawMode, first: i32, count: i32) {
         unsafe {
@@ -78,6 +217,35 @@ impl DrawLayer {
         }
     }
 
+    /// Draw every index of `ebo` using the vertices behind [`Vao`]
+    pub fn draw_indexed<I: IndexType>(&self, vao: &Vao, ebo: &Ebo<I>, mode: DrawMode) {
+        unsafe {
+            vao.bind();
+            ebo.bind();
+            gl::DrawElements(mode as u32, ebo.len() as i32, I::TYPE as u32, ptr::null())
+        }
+    }
+
+    /// Set the number of vertices in each patch drawn with [`DrawMode::Patches`].
+    pub fn set_patch_vertices(&self, count: i32) {
+        unsafe { gl::PatchParameteri(gl::PATCH_VERTICES, count) }
+    }
+
+    /// Render into `framebuffer` instead of the window.
+    pub fn bind_framebuffer(&self, framebuffer: &Framebuffer) {
+        unsafe { framebuffer.bind() }
+    }
+
+    /// Render into the window again.
+    pub fn unbind_framebuffer(&self) {
+        unsafe { gl::BindFramebuffer(gl::FRAMEBUFFER, 0) }
+    }
+
+    /// Set the viewport in pixels, unlike [`Self::resize_to`] the size is used as is.
+    pub fn set_viewport(&self, x: i32, y: i32, width: i32, height: i32) {
+        unsafe { gl::Viewport(x, y, width, height) }
+    }
+
     pub fn resize_to(&self, width: i32, height: i32) {
         #[cfg(target_os = "macos")]
         unsafe {
@@ -97,33 +265,39 @@ impl DrawLayer {
         }
     }
 
-    pub fn get_gl_error(&self) -> u32 {
-        unsafe { gl::GetError() }
+    /// Filter across the edges of cube map faces instead of clamping to each face,
+    /// which hides the seams of a [`crate::Skybox`] and of blurry mip levels.
+    pub fn enable_seamless_cube_maps(&self) {
+        unsafe { gl::Enable(gl::TEXTURE_CUBE_MAP_SEAMLESS) }
     }
-}
 
-pub trait UniformResource {
-    unsafe fn uniform(&self, location: i32);
-}
+    /// Read RGBA8 pixels from the bound framebuffer.
+    /// `x` and `y` are the bottom left corner of the region, like in [`Self::set_viewport`],
+    /// the returned rows are ordered from top to bottom.
+    pub fn read_pixels(&self, x: i32, y: i32, width: u32, height: u32) -> PixelBuffer {
+        let mut data = vec![0_u8; width as usize * height as usize * 4];
 
-impl UniformResource for u32 {
-    #[inline]
-    unsafe fn uniform(&self, location: i32) {
-        gl::Uniform1ui(location, *self)
-    }
-}
+        unsafe {
+            gl::PixelStorei(gl::PACK_ALIGNMENT, 4);
+            gl::ReadPixels(
+                x,
+                y,
+                width as i32,
+                height as i32,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                data.as_mut_ptr() as *mut c_void,
+            )
+        }
 
-impl UniformResource for f32 {
-    #[inline]
-    unsafe fn uniform(&self, location: i32) {
-        gl::Uniform1f(location, *self)
+        let mut pixels = PixelBuffer::new(width, height, data);
+        pixels.flip_rows();
+
+        pixels
     }
-}
 
-// mat4x4
-impl UniformResource for [f32; 16] {
-    unsafe fn uniform(&self, location: i32) {
-        gl::UniformMatrix4fv(location, 1, gl::FALSE, self.as_ptr())
+    pub fn get_gl_error(&self) -> u32 {
+        unsafe { gl::GetError() }
     }
 }
 
@@ -132,7 +306,7 @@ impl DrawLayer {
     /// or if location isn't a uniform inside the shader.
     pub fn put_uniform<R>(&self, program: &Program, location: &str, uniform: &R) -> Option<()>
     where
-        R: UniformResource,
+        R: UniformResource + ?Sized,
     {
         let cstr = CString::new(location).ok()?;
         let cstr = cstr.as_ptr();
@@ -146,4 +320,13 @@ impl DrawLayer {
 
         Some(())
     }
+
+    /// Upload `value` to a uniform resolved with [`Program::uniform`].
+    /// The uniform's program has to be in use.
+    pub fn set<T>(&self, uniform: &Uniform<T>, value: &T)
+    where
+        T: UniformResource + ?Sized,
+    {
+        unsafe { value.uniform(uniform.location()) }
+    }
 }
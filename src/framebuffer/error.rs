@@ -0,0 +1,62 @@
+use std::{error, fmt};
+
+/// The reason a [`crate::Framebuffer`] cannot be rendered to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FramebufferError {
+    Undefined,
+    IncompleteAttachment,
+    MissingAttachment,
+    IncompleteDrawBuffer,
+    IncompleteReadBuffer,
+    Unsupported,
+    IncompleteMultisample,
+    IncompleteLayerTargets,
+    Unknown(u32),
+}
+
+impl FramebufferError {
+    /// Returns [`None`] if `status` is `GL_FRAMEBUFFER_COMPLETE`.
+    pub(crate) fn from_status(status: u32) -> Option<Self> {
+        let err = match status {
+            gl::FRAMEBUFFER_COMPLETE => return None,
+            gl::FRAMEBUFFER_UNDEFINED => Self::Undefined,
+            gl::FRAMEBUFFER_INCOMPLETE_ATTACHMENT => Self::IncompleteAttachment,
+            gl::FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT => Self::MissingAttachment,
+            gl::FRAMEBUFFER_INCOMPLETE_DRAW_BUFFER => Self::IncompleteDrawBuffer,
+            gl::FRAMEBUFFER_INCOMPLETE_READ_BUFFER => Self::IncompleteReadBuffer,
+            gl::FRAMEBUFFER_UNSUPPORTED => Self::Unsupported,
+            gl::FRAMEBUFFER_INCOMPLETE_MULTISAMPLE => Self::IncompleteMultisample,
+            gl::FRAMEBUFFER_INCOMPLETE_LAYER_TARGETS => Self::IncompleteLayerTargets,
+            other => Self::Unknown(other),
+        };
+
+        Some(err)
+    }
+}
+
+impl fmt::Display for FramebufferError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Undefined => write!(f, "The default framebuffer does not exist"),
+            Self::IncompleteAttachment => write!(f, "An attachment is incomplete"),
+            Self::MissingAttachment => write!(f, "The framebuffer has no attachments"),
+            Self::IncompleteDrawBuffer => {
+                write!(f, "A draw buffer points to a missing color attachment")
+            }
+            Self::IncompleteReadBuffer => {
+                write!(f, "The read buffer points to a missing color attachment")
+            }
+            Self::Unsupported => write!(
+                f,
+                "The combination of attachment formats is not supported by the driver"
+            ),
+            Self::IncompleteMultisample => {
+                write!(f, "The attachments have mismatched sample counts")
+            }
+            Self::IncompleteLayerTargets => write!(f, "The attachments are not equally layered"),
+            Self::Unknown(status) => write!(f, "Unknown framebuffer status: {status:#x}"),
+        }
+    }
+}
+
+impl error::Error for FramebufferError {}
@@ -0,0 +1,118 @@
+use std::{cell::RefCell, ptr};
+
+use super::{Attachment, FramebufferError, Renderbuffer};
+use crate::Texture;
+
+/// A render target other than the window.
+///
+/// The framebuffer does not own its attachments, they have to outlive
+/// every draw call made while the framebuffer is bound.
+/// Changing the attachments leaves the bound framebuffer as it was,
+/// draws only go here after [`crate::DrawLayer::bind_framebuffer`].
+pub struct Framebuffer {
+    id: u32,
+    draw_buffers: RefCell<Vec<u32>>,
+}
+
+impl Framebuffer {
+    pub fn new() -> Self {
+        let mut id = 0_u32;
+        unsafe { gl::GenFramebuffers(1, ptr::addr_of_mut!(id)) };
+
+        Self {
+            id,
+            draw_buffers: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Attach the mip `level` of a texture.
+    pub fn attach_texture(&self, attachment: Attachment, texture: &Texture, level: i32) -> &Self {
+        self.with_bound(|| unsafe {
+            gl::FramebufferTexture2D(
+                gl::DRAW_FRAMEBUFFER,
+                attachment.as_gl_enum(),
+                gl::TEXTURE_2D,
+                texture.get_inner(),
+                level,
+            );
+            self.track_draw_buffer(attachment);
+        });
+
+        self
+    }
+
+    pub fn attach_renderbuffer(
+        &self,
+        attachment: Attachment,
+        renderbuffer: &Renderbuffer,
+    ) -> &Self {
+        self.with_bound(|| unsafe {
+            gl::FramebufferRenderbuffer(
+                gl::DRAW_FRAMEBUFFER,
+                attachment.as_gl_enum(),
+                gl::RENDERBUFFER,
+                renderbuffer.get_inner(),
+            );
+            self.track_draw_buffer(attachment);
+        });
+
+        self
+    }
+
+    /// Check whether the framebuffer can be rendered to with its current attachments.
+    pub fn check_status(&self) -> Result<(), FramebufferError> {
+        let status =
+            self.with_bound(|| unsafe { gl::CheckFramebufferStatus(gl::DRAW_FRAMEBUFFER) });
+
+        match FramebufferError::from_status(status) {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    /// Run `f` with the framebuffer bound as the draw framebuffer, then bind the previous one again.
+    fn with_bound<R>(&self, f: impl FnOnce() -> R) -> R {
+        let mut previous = 0;
+        unsafe {
+            gl::GetIntegerv(gl::DRAW_FRAMEBUFFER_BINDING, ptr::addr_of_mut!(previous));
+            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, self.id);
+        }
+
+        let result = f();
+        unsafe { gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, previous as u32) };
+        result
+    }
+
+    /// Fragment output `n` is written to the color attachment `n`,
+    /// outputs without an attachment are discarded. Expects the framebuffer to be bound.
+    unsafe fn track_draw_buffer(&self, attachment: Attachment) {
+        let Attachment::Color(index) = attachment else {
+            return;
+        };
+
+        let mut draw_buffers = self.draw_buffers.borrow_mut();
+        let index = index as usize;
+        if draw_buffers.len() <= index {
+            draw_buffers.resize(index + 1, gl::NONE);
+        }
+        draw_buffers[index] = attachment.as_gl_enum();
+
+        gl::DrawBuffers(draw_buffers.len() as i32, draw_buffers.as_ptr())
+    }
+
+    pub(crate) unsafe fn bind(&self) {
+        gl::BindFramebuffer(gl::FRAMEBUFFER, self.id)
+    }
+}
+
+impl Default for Framebuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Framebuffer {
+    fn drop(&mut self) {
+        unsafe { gl::DeleteFramebuffers(1, ptr::addr_of!(self.id)) }
+    }
+}
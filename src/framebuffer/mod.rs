@@ -0,0 +1,32 @@
+mod error;
+mod fbo;
+mod renderbuffer;
+
+pub use {error::*, fbo::*, renderbuffer::*};
+
+/// The point of a [`Framebuffer`] a texture or a [`Renderbuffer`] is attached to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Attachment {
+    /// A color attachment, indices from 0 to 7 are always available.
+    Color(u32),
+    Depth,
+    Stencil,
+    DepthStencil,
+}
+
+impl Attachment {
+    pub(crate) fn as_gl_enum(self) -> u32 {
+        match self {
+            Self::Color(index) => {
+                assert!(
+                    index < 8,
+                    "The color attachment index goes outside of the guaranteed attachment range"
+                );
+                gl::COLOR_ATTACHMENT0 + index
+            }
+            Self::Depth => gl::DEPTH_ATTACHMENT,
+            Self::Stencil => gl::STENCIL_ATTACHMENT,
+            Self::DepthStencil => gl::DEPTH_STENCIL_ATTACHMENT,
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use std::ptr;
+
+/// Storage formats a [`Renderbuffer`] can be created with.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RenderbufferFormat {
+    Rgba8 = gl::RGBA8,
+    Rgba16F = gl::RGBA16F,
+    Depth24 = gl::DEPTH_COMPONENT24,
+    Depth32F = gl::DEPTH_COMPONENT32F,
+    Stencil8 = gl::STENCIL_INDEX8,
+    Depth24Stencil8 = gl::DEPTH24_STENCIL8,
+}
+
+/// An image that can only be rendered to, used for attachments that are
+/// never sampled, such as depth and stencil buffers.
+pub struct Renderbuffer(u32);
+
+impl Renderbuffer {
+    pub fn new(format: RenderbufferFormat, width: i32, height: i32) -> Self {
+        let mut id = 0_u32;
+
+        unsafe {
+            gl::GenRenderbuffers(1, ptr::addr_of_mut!(id));
+            gl::BindRenderbuffer(gl::RENDERBUFFER, id);
+            gl::RenderbufferStorage(gl::RENDERBUFFER, format as u32, width, height);
+        }
+
+        Self(id)
+    }
+
+    pub(crate) fn get_inner(&self) -> u32 {
+        self.0
+    }
+}
+
+impl Drop for Renderbuffer {
+    fn drop(&mut self) {
+        unsafe { gl::DeleteRenderbuffers(1, ptr::addr_of!(self.0)) }
+    }
+}
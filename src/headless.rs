@@ -0,0 +1,204 @@
+use std::{error, ffi::c_void, fmt, ptr};
+
+use khronos_egl as egl;
+
+use crate::{Backend, DrawLayer};
+
+type Egl = egl::DynamicInstance<egl::EGL1_5>;
+
+const PLATFORM_SURFACELESS_MESA: egl::Enum = 0x31DD;
+
+/// Core profile versions tried by [`HeadlessContext::new`], newest first.
+const VERSIONS: [(i32, i32); 8] = [
+    (4, 6),
+    (4, 5),
+    (4, 4),
+    (4, 3),
+    (4, 2),
+    (4, 1),
+    (4, 0),
+    (3, 3),
+];
+
+#[derive(Debug)]
+pub enum HeadlessError {
+    /// `libEGL` could not be loaded.
+    Load(String),
+    Egl(egl::Error),
+    /// There is no default EGL display, e.g. without a display server or surfaceless platform.
+    NoDisplay,
+    /// The display has no RGBA8 configuration usable for OpenGL.
+    NoConfig,
+    /// None of the requested OpenGL versions is supported.
+    NoContext,
+}
+
+impl fmt::Display for HeadlessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Load(err) => write!(f, "Cannot load EGL: {err}"),
+            Self::Egl(err) => write!(f, "EGL call failed: {err}"),
+            Self::NoDisplay => write!(f, "No EGL display available"),
+            Self::NoConfig => write!(f, "No suitable EGL config found"),
+            Self::NoContext => write!(f, "Cannot create an OpenGL core context"),
+        }
+    }
+}
+
+impl error::Error for HeadlessError {}
+
+impl From<egl::Error> for HeadlessError {
+    fn from(value: egl::Error) -> Self {
+        Self::Egl(value)
+    }
+}
+
+/// An OpenGL context without a window, backed by an EGL pbuffer.
+///
+/// Mesa's surfaceless platform is preferred when available, so software
+/// drivers such as llvmpipe work on machines with no GPU or display server.
+/// The context is current on the thread that created it.
+pub struct HeadlessContext {
+    egl: Egl,
+    display: egl::Display,
+    surface: egl::Surface,
+    context: egl::Context,
+}
+
+impl HeadlessContext {
+    /// Create a context with the newest core profile the driver supports
+    /// and a `width` x `height` default framebuffer.
+    pub fn new(width: i32, height: i32) -> Result<Self, HeadlessError> {
+        Self::with_versions(width, height, &VERSIONS)
+    }
+
+    /// Like [`Self::new`] but only requests the given core profile version.
+    pub fn with_version(
+        width: i32,
+        height: i32,
+        major: i32,
+        minor: i32,
+    ) -> Result<Self, HeadlessError> {
+        Self::with_versions(width, height, &[(major, minor)])
+    }
+
+    fn with_versions(
+        width: i32,
+        height: i32,
+        versions: &[(i32, i32)],
+    ) -> Result<Self, HeadlessError> {
+        let egl =
+            unsafe { Egl::load_required() }.map_err(|err| HeadlessError::Load(err.to_string()))?;
+
+        let display = Self::get_display(&egl)?;
+        egl.initialize(display)?;
+
+        #[rustfmt::skip]
+        let config_attributes = [
+            egl::SURFACE_TYPE, egl::PBUFFER_BIT,
+            egl::RENDERABLE_TYPE, egl::OPENGL_BIT,
+            egl::RED_SIZE, 8,
+            egl::GREEN_SIZE, 8,
+            egl::BLUE_SIZE, 8,
+            egl::ALPHA_SIZE, 8,
+            egl::DEPTH_SIZE, 24,
+            egl::STENCIL_SIZE, 8,
+            egl::NONE,
+        ];
+        let config = egl
+            .choose_first_config(display, &config_attributes)?
+            .ok_or(HeadlessError::NoConfig)?;
+
+        egl.bind_api(egl::OPENGL_API)?;
+        let context = versions
+            .iter()
+            .find_map(|&(major, minor)| {
+                #[rustfmt::skip]
+                let context_attributes = [
+                    egl::CONTEXT_MAJOR_VERSION, major,
+                    egl::CONTEXT_MINOR_VERSION, minor,
+                    egl::CONTEXT_OPENGL_PROFILE_MASK, egl::CONTEXT_OPENGL_CORE_PROFILE_BIT,
+                    egl::NONE,
+                ];
+                egl.create_context(display, config, None, &context_attributes)
+                    .ok()
+            })
+            .ok_or(HeadlessError::NoContext)?;
+
+        let surface_attributes = [egl::WIDTH, width, egl::HEIGHT, height, egl::NONE];
+        let surface = match egl.create_pbuffer_surface(display, config, &surface_attributes) {
+            Ok(surface) => surface,
+            Err(err) => {
+                egl.destroy_context(display, context)?;
+                return Err(err.into());
+            }
+        };
+
+        let headless = Self {
+            egl,
+            display,
+            surface,
+            context,
+        };
+        headless.make_current()?;
+
+        Ok(headless)
+    }
+
+    fn get_display(egl: &Egl) -> Result<egl::Display, HeadlessError> {
+        let surfaceless = egl.query_string(None, egl::EXTENSIONS).is_ok_and(|ext| {
+            ext.to_string_lossy()
+                .split(' ')
+                .any(|ext| ext == "EGL_MESA_platform_surfaceless")
+        });
+
+        let display = if surfaceless {
+            unsafe {
+                egl.get_platform_display(
+                    PLATFORM_SURFACELESS_MESA,
+                    egl::DEFAULT_DISPLAY,
+                    &[egl::ATTRIB_NONE],
+                )
+            }?
+        } else {
+            unsafe { egl.get_display(egl::DEFAULT_DISPLAY) }.ok_or(HeadlessError::NoDisplay)?
+        };
+
+        Ok(display)
+    }
+
+    /// Make the context current on the calling thread.
+    pub fn make_current(&self) -> Result<(), HeadlessError> {
+        self.egl.make_current(
+            self.display,
+            Some(self.surface),
+            Some(self.surface),
+            Some(self.context),
+        )?;
+
+        Ok(())
+    }
+
+    /// Initialize OpenGL with this context, which must be current on this thread.
+    pub fn draw_layer(&self) -> DrawLayer {
+        DrawLayer::with_backend(self)
+    }
+}
+
+unsafe impl Backend for HeadlessContext {
+    fn get_proc_address(&self, symbol: &str) -> *const c_void {
+        self.egl
+            .get_proc_address(symbol)
+            .map_or(ptr::null(), |f| f as *const c_void)
+    }
+}
+
+impl Drop for HeadlessContext {
+    fn drop(&mut self) {
+        // The display is shared with every other context of the process,
+        // so it is never terminated here.
+        let _ = self.egl.make_current(self.display, None, None, None);
+        let _ = self.egl.destroy_surface(self.display, self.surface);
+        let _ = self.egl.destroy_context(self.display, self.context);
+    }
+}
@@ -1,8 +1,34 @@
 mod attribute;
+mod backend;
 mod buffers;
+mod capabilities;
 mod color;
 mod draw_layer;
+mod framebuffer;
+#[cfg(feature = "headless")]
+mod headless;
+mod pixels;
 mod shader;
+mod skybox;
+mod std140;
 mod texture;
+mod uniform;
 
-pub use {attribute::*, buffers::*, color::*, draw_layer::*, shader::*, texture::*};
+pub use {
+    attribute::*, backend::*, buffers::*, color::*, draw_layer::*, framebuffer::*, pixels::*,
+    shader::*, skybox::*, texture::*, uniform::*,
+};
+
+pub use std140::Std140;
+
+#[cfg(feature = "headless")]
+pub use headless::*;
+
+#[cfg(feature = "derive")]
+pub use graphics_derive::{Std140, Vertex};
+
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::std140::align_to;
+    pub use core::mem::{offset_of, size_of};
+}
@@ -0,0 +1,141 @@
+/// RGBA8 pixels read back from the GPU, rows are stored from top to bottom.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PixelBuffer {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+impl PixelBuffer {
+    pub fn new(width: u32, height: u32, data: Vec<u8>) -> Self {
+        assert_eq!(
+            data.len(),
+            width as usize * height as usize * 4,
+            "The pixel data doesn't match the RGBA8 size of the image"
+        );
+
+        Self {
+            width,
+            height,
+            data,
+        }
+    }
+
+    /// The RGBA value at `x`, `y`, counting from the top left corner.
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        assert!(
+            x < self.width && y < self.height,
+            "The pixel is out of bounds"
+        );
+        let start = (y as usize * self.width as usize + x as usize) * 4;
+        let mut pixel = [0; 4];
+        pixel.copy_from_slice(&self.data[start..start + 4]);
+
+        pixel
+    }
+
+    /// Compare two images of the same size, channels differing by at most
+    /// `tolerance` are considered equal.
+    pub fn diff(&self, other: &Self, tolerance: u8) -> PixelDiff {
+        assert!(
+            self.width == other.width && self.height == other.height,
+            "Cannot compare images of different sizes"
+        );
+
+        let mut differing_pixels = 0;
+        let mut max_difference = 0;
+        let mut image = Vec::with_capacity(self.data.len());
+
+        for (left, right) in self.data.chunks_exact(4).zip(other.data.chunks_exact(4)) {
+            let difference = left
+                .iter()
+                .zip(right)
+                .map(|(l, r)| l.abs_diff(*r))
+                .max()
+                .unwrap_or(0);
+            max_difference = max_difference.max(difference);
+
+            if difference > tolerance {
+                differing_pixels += 1;
+                image.extend_from_slice(&[255, 0, 0, 255]);
+            } else {
+                // Matching pixels are kept as a faded grayscale version of `self`
+                let luma = (left[0] as u32 * 3 + left[1] as u32 * 6 + left[2] as u32) / 10;
+                let faded = (luma / 4 + 191) as u8;
+                image.extend_from_slice(&[faded, faded, faded, 255]);
+            }
+        }
+
+        PixelDiff {
+            differing_pixels,
+            max_difference,
+            image: Self::new(self.width, self.height, image),
+        }
+    }
+
+    /// Swap the order of the rows, OpenGL stores the bottom row first.
+    pub(crate) fn flip_rows(&mut self) {
+        let row_len = self.width as usize * 4;
+        let height = self.height as usize;
+
+        for row in 0..height / 2 {
+            let (top, bottom) = self.data.split_at_mut((height - row - 1) * row_len);
+            top[row * row_len..(row + 1) * row_len].swap_with_slice(&mut bottom[..row_len]);
+        }
+    }
+}
+
+/// The result of [`PixelBuffer::diff`].
+#[derive(Debug, Clone)]
+pub struct PixelDiff {
+    /// The number of pixels with a channel outside of the tolerance.
+    pub differing_pixels: usize,
+    /// The largest difference of a single channel.
+    pub max_difference: u8,
+    /// Differing pixels in red on top of a faded version of the image.
+    pub image: PixelBuffer,
+}
+
+#[cfg(feature = "png")]
+impl PixelBuffer {
+    /// Decode a PNG file, converting it to RGBA8.
+    pub fn load_png(path: impl AsRef<std::path::Path>) -> Result<Self, png::DecodingError> {
+        let file = std::io::BufReader::new(std::fs::File::open(path)?);
+        let mut decoder = png::Decoder::new(file);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info()?;
+
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer)?;
+        buffer.truncate(info.buffer_size());
+
+        let data = match info.color_type {
+            png::ColorType::Rgba => buffer,
+            png::ColorType::Rgb => buffer
+                .chunks_exact(3)
+                .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], 255])
+                .collect(),
+            png::ColorType::GrayscaleAlpha => buffer
+                .chunks_exact(2)
+                .flat_map(|ga| [ga[0], ga[0], ga[0], ga[1]])
+                .collect(),
+            png::ColorType::Grayscale => buffer.iter().flat_map(|&g| [g, g, g, 255]).collect(),
+            png::ColorType::Indexed => unreachable!("Indexed images are expanded when decoding"),
+        };
+
+        Ok(Self::new(info.width, info.height, data))
+    }
+
+    /// Encode the pixels as an RGBA8 PNG file.
+    pub fn save_png(&self, path: impl AsRef<std::path::Path>) -> Result<(), png::EncodingError> {
+        let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        let mut encoder = png::Encoder::new(file, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.data)?;
+
+        writer.finish()
+    }
+}
@@ -0,0 +1,92 @@
+use super::{
+    Fragment, Geometry, Program, Shader, ShaderError, ShaderType, TessControl, TessEvaluation,
+    Vertex,
+};
+
+/// Links any combination of the render stages into a [`Program`].
+///
+/// ```ignore
+/// let program = Program::builder()
+///     .vertex(Shader::compile(VERTEX)?)
+///     .tess_control(Shader::compile(TESS_CONTROL)?)
+///     .tess_evaluation(Shader::compile(TESS_EVALUATION)?)
+///     .fragment(Shader::compile(FRAGMENT)?)
+///     .build()?;
+/// ```
+#[derive(Default)]
+pub struct ProgramBuilder {
+    vertex: Option<Shader<Vertex>>,
+    tess_control: Option<Shader<TessControl>>,
+    tess_evaluation: Option<Shader<TessEvaluation>>,
+    geometry: Option<Shader<Geometry>>,
+    fragment: Option<Shader<Fragment>>,
+}
+
+impl ProgramBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn vertex(mut self, shader: Shader<Vertex>) -> Self {
+        self.vertex = Some(shader);
+        self
+    }
+
+    pub fn tess_control(mut self, shader: Shader<TessControl>) -> Self {
+        self.tess_control = Some(shader);
+        self
+    }
+
+    pub fn tess_evaluation(mut self, shader: Shader<TessEvaluation>) -> Self {
+        self.tess_evaluation = Some(shader);
+        self
+    }
+
+    pub fn geometry(mut self, shader: Shader<Geometry>) -> Self {
+        self.geometry = Some(shader);
+        self
+    }
+
+    /// Without a fragment shader only depth is written.
+    pub fn fragment(mut self, shader: Shader<Fragment>) -> Self {
+        self.fragment = Some(shader);
+        self
+    }
+
+    /// Check that the stages fit together and link them.
+    /// A vertex shader is always required and a tessellation control shader
+    /// needs a tessellation evaluation shader. If the driver rejects the program,
+    /// the [`super::LinkError`] names the stage that failed, where the driver's log does.
+    pub fn build(self) -> Result<Program, ShaderError> {
+        self.validate()?;
+
+        let handles = [
+            self.vertex.as_ref().map(|shader| shader.handle),
+            self.tess_control.as_ref().map(|shader| shader.handle),
+            self.tess_evaluation.as_ref().map(|shader| shader.handle),
+            self.geometry.as_ref().map(|shader| shader.handle),
+            self.fragment.as_ref().map(|shader| shader.handle),
+        ];
+        let handles: Vec<u32> = handles.into_iter().flatten().collect();
+
+        unsafe { Program::link_internal(&handles) }
+    }
+
+    fn validate(&self) -> Result<(), ShaderError> {
+        if self.vertex.is_none() {
+            return Err(ShaderError::MissingStage {
+                stage: ShaderType::Vertex,
+                required_by: None,
+            });
+        }
+
+        if self.tess_control.is_some() && self.tess_evaluation.is_none() {
+            return Err(ShaderError::MissingStage {
+                stage: ShaderType::TessEvaluation,
+                required_by: Some(ShaderType::TessControl),
+            });
+        }
+
+        Ok(())
+    }
+}
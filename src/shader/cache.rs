@@ -0,0 +1,188 @@
+use std::{
+    ffi::CStr,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use super::{compile_stage, Program, ProgramBinary, ShaderError, ShaderType};
+
+/// Identifies cache files, followed by [`FORMAT_VERSION`].
+const MAGIC: &[u8; 4] = b"GLPB";
+/// Bumped whenever the layout of a cache file changes, older files are rebuilt.
+const FORMAT_VERSION: u32 = 1;
+/// Magic, version, key, binary format, data length and checksum.
+const HEADER_LENGTH: usize = 4 + 4 + 8 + 4 + 8 + 8;
+
+/// Linked programs stored on disk with `glGetProgramBinary`, so later runs skip compiling.
+///
+/// A program is stored under a hash of the source of every stage and the vendor,
+/// renderer and version strings of the driver. Defines are part of the key as long
+/// as they are part of the sources, which is the case for [`super::PreprocessedSource::code`].
+/// Files written by another driver, another version of this format or
+/// damaged on disk are ignored and replaced by a fresh build.
+///
+/// ```ignore
+/// let cache = ProgramCache::new("cache/shaders")?;
+/// let program = cache.link(&[
+///     (ShaderType::Vertex, VERTEX_SOURCE),
+///     (ShaderType::Fragment, FRAGMENT_SOURCE),
+/// ])?;
+/// ```
+pub struct ProgramCache {
+    directory: PathBuf,
+    driver: u64,
+}
+
+impl ProgramCache {
+    /// Store programs in `directory`, creating it if needed.
+    /// The current context decides which driver the programs are cached for.
+    pub fn new(directory: impl Into<PathBuf>) -> io::Result<Self> {
+        let directory = directory.into();
+        fs::create_dir_all(&directory)?;
+
+        let mut driver = Fnv::new();
+        for name in [gl::VENDOR, gl::RENDERER, gl::VERSION] {
+            let string = unsafe { gl::GetString(name) };
+            if !string.is_null() {
+                driver.write(unsafe { CStr::from_ptr(string as *const _) }.to_bytes());
+            }
+            driver.write(&[0]);
+        }
+
+        Ok(Self {
+            directory,
+            driver: driver.finish(),
+        })
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// The file a program built from `stages` is stored in.
+    pub fn path(&self, stages: &[(ShaderType, &str)]) -> PathBuf {
+        self.directory
+            .join(format!("{:016x}.bin", self.key(stages)))
+    }
+
+    /// Load the program built from `stages` if it was stored before
+    /// and the driver still accepts it.
+    pub fn load(&self, stages: &[(ShaderType, &str)]) -> Option<Program> {
+        let key = self.key(stages);
+        let file = fs::read(self.path(stages)).ok()?;
+        let binary = decode(&file, key)?;
+
+        Program::from_binary(&binary).ok()
+    }
+
+    /// Load the program built from `stages`, or compile and link it and store the result.
+    ///
+    /// Failing to write the cache file is not an error, the program is just built again next time.
+    pub fn link(&self, stages: &[(ShaderType, &str)]) -> Result<Program, ShaderError> {
+        if let Some(program) = self.load(stages) {
+            return Ok(program);
+        }
+
+        let mut handles = Vec::with_capacity(stages.len());
+        let program = stages
+            .iter()
+            .try_for_each(|&(stage, source)| {
+                handles.push(compile_stage(stage, source)?);
+                Ok(())
+            })
+            .and_then(|()| unsafe { Program::link_retrievable(&handles) });
+        for handle in handles {
+            unsafe { gl::DeleteShader(handle) };
+        }
+        let program = program?;
+
+        if let Some(binary) = program.binary() {
+            let _ = self.store(stages, &binary);
+        }
+
+        Ok(program)
+    }
+
+    /// Write to a temporary file first, so an interrupted write never leaves a damaged file behind.
+    fn store(&self, stages: &[(ShaderType, &str)], binary: &ProgramBinary) -> io::Result<()> {
+        let path = self.path(stages);
+        let temporary = path.with_extension("tmp");
+        fs::write(&temporary, encode(binary, self.key(stages)))?;
+        fs::rename(temporary, path)
+    }
+
+    fn key(&self, stages: &[(ShaderType, &str)]) -> u64 {
+        let mut hash = Fnv::new();
+        for &(stage, source) in stages {
+            hash.write(&(stage as u32).to_le_bytes());
+            hash.write(&(source.len() as u64).to_le_bytes());
+            hash.write(source.as_bytes());
+        }
+        hash.write(&self.driver.to_le_bytes());
+
+        hash.finish()
+    }
+}
+
+fn encode(binary: &ProgramBinary, key: u64) -> Vec<u8> {
+    let mut file = Vec::with_capacity(HEADER_LENGTH + binary.data.len());
+    file.extend_from_slice(MAGIC);
+    file.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    file.extend_from_slice(&key.to_le_bytes());
+    file.extend_from_slice(&binary.format.to_le_bytes());
+    file.extend_from_slice(&(binary.data.len() as u64).to_le_bytes());
+    file.extend_from_slice(&checksum(&binary.data).to_le_bytes());
+    file.extend_from_slice(&binary.data);
+
+    file
+}
+
+/// Returns [`None`] unless `file` is a complete cache file of this version for `key`.
+fn decode(file: &[u8], key: u64) -> Option<ProgramBinary> {
+    let (header, data) = file.split_at_checked(HEADER_LENGTH)?;
+    let (magic, header) = header.split_at(4);
+    let (version, header) = header.split_at(4);
+    let (file_key, header) = header.split_at(8);
+    let (format, header) = header.split_at(4);
+    let (length, sum) = header.split_at(8);
+
+    let valid = magic == MAGIC
+        && u32::from_le_bytes(version.try_into().ok()?) == FORMAT_VERSION
+        && u64::from_le_bytes(file_key.try_into().ok()?) == key
+        && u64::from_le_bytes(length.try_into().ok()?) == data.len() as u64
+        && u64::from_le_bytes(sum.try_into().ok()?) == checksum(data);
+    if !valid {
+        return None;
+    }
+
+    Some(ProgramBinary {
+        format: u32::from_le_bytes(format.try_into().ok()?),
+        data: data.to_vec(),
+    })
+}
+
+fn checksum(data: &[u8]) -> u64 {
+    let mut hash = Fnv::new();
+    hash.write(data);
+    hash.finish()
+}
+
+/// 64 bit FNV-1a, unlike [`std::hash::DefaultHasher`] it is the same for every build.
+struct Fnv(u64);
+
+impl Fnv {
+    fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
@@ -0,0 +1,45 @@
+use super::{Compute, Program, Shader, ShaderError};
+
+/// A program made of a single compute shader, run with [`crate::DrawLayer::dispatch`].
+/// Requires OpenGL 4.3.
+pub struct ComputeProgram(Program);
+
+impl ComputeProgram {
+    pub fn new(compute_shader: Shader<Compute>) -> Result<Self, ShaderError> {
+        let program = unsafe { Program::link_internal(&[compute_shader.handle]) }?;
+        Ok(Self(program))
+    }
+
+    /// The underlying program, e.g. to set uniforms with [`crate::DrawLayer::put_uniform`].
+    pub fn as_program(&self) -> &Program {
+        &self.0
+    }
+
+    /// The size of a work group declared by the shader's `local_size_*` layout.
+    pub fn work_group_size(&self) -> [i32; 3] {
+        let mut size = [0; 3];
+        unsafe {
+            gl::GetProgramiv(
+                self.0.get_inner(),
+                gl::COMPUTE_WORK_GROUP_SIZE,
+                size.as_mut_ptr(),
+            )
+        };
+
+        size
+    }
+}
+
+/// The arguments of an indirect dispatch, as read from a buffer by
+/// [`crate::DrawLayer::dispatch_indirect`].
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DispatchIndirectCommand {
+    pub num_groups_x: u32,
+    pub num_groups_y: u32,
+    pub num_groups_z: u32,
+}
+
+// SAFETY: three `u32`s without padding
+unsafe impl bytemuck::Zeroable for DispatchIndirectCommand {}
+unsafe impl bytemuck::Pod for DispatchIndirectCommand {}
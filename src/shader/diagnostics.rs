@@ -0,0 +1,235 @@
+use std::{error, fmt};
+
+use super::ShaderType;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+        })
+    }
+}
+
+/// A single message of a driver's compile log.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub stage: ShaderType,
+    /// The source string number, the file index set by `#line` directives.
+    pub file: u32,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl Diagnostic {
+    /// Parse the messages of a compile log in the Mesa, NVIDIA or AMD format.
+    /// Lines in other formats are skipped.
+    pub fn parse_log(stage: ShaderType, log: &str) -> Vec<Self> {
+        log.lines()
+            .filter_map(|line| {
+                let line = line.trim();
+                parse_mesa(line)
+                    .or_else(|| parse_nvidia(line))
+                    .or_else(|| parse_amd(line))
+            })
+            .map(|parsed| Self {
+                stage,
+                file: parsed.file,
+                line: parsed.line,
+                column: parsed.column,
+                severity: parsed.severity,
+                message: parsed.message.to_owned(),
+            })
+            .collect()
+    }
+}
+
+/// A shader that failed to compile, displayed with the offending source lines.
+#[derive(Clone, Debug)]
+pub struct CompileError {
+    pub stage: ShaderType,
+    /// The complete log of the driver.
+    pub log: String,
+    pub diagnostics: Vec<Diagnostic>,
+    /// The compiled source, after preprocessing.
+    pub source: String,
+    /// The names of the files `#line` directives refer to,
+    /// filled in by [`super::Preprocessor::compile`].
+    pub files: Vec<String>,
+}
+
+impl CompileError {
+    pub(crate) fn new(stage: ShaderType, log: String, source: String) -> Self {
+        Self {
+            stage,
+            diagnostics: Diagnostic::parse_log(stage, &log),
+            log,
+            source,
+            files: Vec::new(),
+        }
+    }
+
+    /// The name of the file `diagnostic` refers to, if known.
+    pub fn file_name(&self, diagnostic: &Diagnostic) -> Option<&str> {
+        self.files.get(diagnostic.file as usize).map(String::as_str)
+    }
+
+    /// The line of the source `diagnostic` points at.
+    pub fn source_line(&self, diagnostic: &Diagnostic) -> Option<&str> {
+        let target = (diagnostic.file, diagnostic.line?);
+        let (mut file, mut line) = (0, 1);
+        let mut found = None;
+
+        for text in self.source.lines() {
+            if let Some((next_line, next_file)) = line_directive(text) {
+                line = next_line;
+                file = next_file.unwrap_or(file);
+                continue;
+            }
+
+            // The injected lines before the first `#line` share numbers with
+            // the start of the file, so the last match wins
+            if (file, line) == target {
+                found = Some(text);
+            }
+            line += 1;
+        }
+
+        found
+    }
+}
+
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.diagnostics.is_empty() {
+            return write!(f, "{} shader: {}", self.stage, self.log.trim_end());
+        }
+
+        for (index, diagnostic) in self.diagnostics.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            writeln!(f, "{}: {}", diagnostic.severity, diagnostic.message)?;
+
+            let file = match self.file_name(diagnostic) {
+                Some(name) => name.to_owned(),
+                None => diagnostic.file.to_string(),
+            };
+            write!(f, "  --> {file}")?;
+            if let Some(line) = diagnostic.line {
+                write!(f, ":{line}")?;
+            }
+            if let Some(column) = diagnostic.column {
+                write!(f, ":{column}")?;
+            }
+            write!(f, " ({} shader)", diagnostic.stage)?;
+
+            if let (Some(line), Some(text)) = (diagnostic.line, self.source_line(diagnostic)) {
+                let width = line.to_string().len();
+                write!(f, "\n{:width$} |\n{line} | {text}", "")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl error::Error for CompileError {}
+
+struct Parsed<'a> {
+    file: u32,
+    line: Option<u32>,
+    column: Option<u32>,
+    severity: Severity,
+    message: &'a str,
+}
+
+/// `0:12(5): error: message`
+fn parse_mesa(text: &str) -> Option<Parsed<'_>> {
+    let (file, rest) = number(text)?;
+    let (line, rest) = number(rest.strip_prefix(':')?)?;
+    let (column, rest) = number(rest.strip_prefix('(')?)?;
+    let (severity, message) = severity(rest.strip_prefix("): ")?, ": ")?;
+
+    Some(Parsed {
+        file,
+        line: Some(line),
+        column: Some(column),
+        severity,
+        message,
+    })
+}
+
+/// `0(12) : error C1008: message`
+fn parse_nvidia(text: &str) -> Option<Parsed<'_>> {
+    let (file, rest) = number(text)?;
+    let (line, rest) = number(rest.strip_prefix('(')?)?;
+    let (severity, rest) = severity(rest.strip_prefix(") : ")?, " ")?;
+    let (_code, message) = rest.split_once(": ")?;
+
+    Some(Parsed {
+        file,
+        line: Some(line),
+        column: None,
+        severity,
+        message,
+    })
+}
+
+/// `ERROR: 0:12: message`
+fn parse_amd(text: &str) -> Option<Parsed<'_>> {
+    let (severity, rest) = if let Some(rest) = text.strip_prefix("ERROR: ") {
+        (Severity::Error, rest)
+    } else {
+        (Severity::Warning, text.strip_prefix("WARNING: ")?)
+    };
+    let (file, rest) = number(rest)?;
+    let (line, rest) = number(rest.strip_prefix(':')?)?;
+    let message = rest.strip_prefix(':')?.trim_start();
+
+    Some(Parsed {
+        file,
+        line: Some(line),
+        column: None,
+        severity,
+        message,
+    })
+}
+
+/// A leading number and the rest of `text`.
+fn number(text: &str) -> Option<(u32, &str)> {
+    let end = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    Some((text[..end].parse().ok()?, &text[end..]))
+}
+
+/// `error` or `warning` followed by `separator`.
+fn severity<'a>(text: &'a str, separator: &str) -> Option<(Severity, &'a str)> {
+    let (word, rest) = text.split_once(separator)?;
+    let severity = match word {
+        "error" => Severity::Error,
+        "warning" => Severity::Warning,
+        _ => return None,
+    };
+
+    Some((severity, rest))
+}
+
+/// The line and source string number of `#line line [source]`.
+fn line_directive(text: &str) -> Option<(u32, Option<u32>)> {
+    let rest = text.trim_start().strip_prefix('#')?.trim_start();
+    let mut arguments = rest.strip_prefix("line")?.split_whitespace();
+    let line = arguments.next()?.parse().ok()?;
+    let file = arguments.next().and_then(|file| file.parse().ok());
+
+    Some((line, file))
+}
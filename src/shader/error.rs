@@ -1,22 +1,147 @@
 use std::{error, ffi::NulError, fmt};
 
+use super::{CompileError, GlslType, PreprocessError, ShaderType};
+
 #[derive(Debug)]
 pub enum ShaderError {
-    CompilationError(String),
-    LinkingError(String),
+    CompilationError(CompileError),
+    LinkingError(LinkError),
+    /// The complete validation log of a [`super::ProgramPipeline`].
+    ValidationError(String),
     CStringConversion(NulError),
+    Preprocessing(PreprocessError),
+    /// The stages given to a [`super::ProgramBuilder`] can't form a program,
+    /// `required_by` is the stage that needs the missing one.
+    MissingStage {
+        stage: ShaderType,
+        required_by: Option<ShaderType>,
+    },
+    /// The driver has neither OpenGL 4.6 nor `GL_ARB_gl_spirv`.
+    SpirvUnsupported,
+    /// The bytes given to [`super::Shader::from_spirv`] are not a SPIR-V module.
+    InvalidSpirv(&'static str),
 }
 
 impl fmt::Display for ShaderError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::CompilationError(err) => write!(f, "Cannot compile the shader: {err}"),
+            Self::CompilationError(err) => write!(f, "Cannot compile the shader:\n{err}"),
             Self::CStringConversion(err) => {
                 write!(f, "Cannot convert string to a C pointer: {err}")
             }
-            Self::LinkingError(err) => write!(f, "Cannot link the shaders: {err}"),
+            Self::LinkingError(err) => write!(f, "{err}"),
+            Self::ValidationError(err) => write!(f, "The program pipeline is invalid: {err}"),
+            Self::Preprocessing(err) => write!(f, "Cannot preprocess the shader: {err}"),
+            Self::MissingStage {
+                stage,
+                required_by: Some(required_by),
+            } => write!(f, "The {required_by} shader requires a {stage} shader"),
+            Self::MissingStage {
+                stage,
+                required_by: None,
+            } => write!(f, "The program has no {stage} shader"),
+            Self::SpirvUnsupported => write!(
+                f,
+                "SPIR-V shaders require OpenGL 4.6 or the GL_ARB_gl_spirv extension"
+            ),
+            Self::InvalidSpirv(reason) => write!(f, "Invalid SPIR-V module: {reason}"),
         }
     }
 }
 
 impl error::Error for ShaderError {}
+
+/// A program the driver refused to link.
+///
+/// Drivers don't report the failing stage in a structured way, so [`Self::stage`] is taken
+/// from the log when it names exactly one of the linked stages. Errors between two stages,
+/// such as mismatched interfaces, and logs without stage names leave it empty.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkError {
+    /// Empty for programs loaded with [`super::Program::from_binary`].
+    pub stages: Vec<ShaderType>,
+    /// The stage that failed to link, if the log names one.
+    pub stage: Option<ShaderType>,
+    /// The complete link log of the driver.
+    pub log: String,
+}
+
+impl LinkError {
+    pub(crate) fn new(stages: Vec<ShaderType>, log: String) -> Self {
+        let lowercase = log.to_lowercase();
+        let mut named = stages.iter().filter(|stage| {
+            lowercase.contains(&format!("{stage} shader"))
+                || lowercase.contains(&format!("{stage} info"))
+        });
+        let stage = match (named.next(), named.next()) {
+            (Some(&stage), None) => Some(stage),
+            _ => None,
+        };
+
+        Self { stages, stage, log }
+    }
+}
+
+impl fmt::Display for LinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(stage) = self.stage {
+            return write!(f, "Cannot link the {stage} shader: {}", self.log);
+        }
+        if self.stages.is_empty() {
+            return write!(f, "Cannot link the program: {}", self.log);
+        }
+
+        f.write_str("Cannot link the ")?;
+        for (index, stage) in self.stages.iter().enumerate() {
+            match index {
+                0 => {}
+                _ if index + 1 == self.stages.len() => f.write_str(" and ")?,
+                _ => f.write_str(", ")?,
+            }
+            write!(f, "{stage}")?;
+        }
+        let noun = if self.stages.len() == 1 {
+            "shader"
+        } else {
+            "shaders"
+        };
+        write!(f, " {noun}: {}", self.log)
+    }
+}
+
+/// Why [`super::Program::uniform`] couldn't resolve a uniform.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UniformError {
+    /// The program has no active uniform with this name,
+    /// uniforms the compiler optimized out are not active.
+    NotFound(String),
+    /// The uniform is a member of a uniform block, set it through a [`crate::UniformBuffer`].
+    InUniformBlock(String),
+    /// The uniform is declared with a type the Rust type can't be uploaded to.
+    TypeMismatch {
+        name: String,
+        declared: GlslType,
+        rust_type: &'static str,
+    },
+}
+
+impl fmt::Display for UniformError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound(name) => write!(f, "The program has no active uniform `{name}`"),
+            Self::InUniformBlock(name) => {
+                write!(f, "The uniform `{name}` is part of a uniform block")
+            }
+            Self::TypeMismatch {
+                name,
+                declared,
+                rust_type,
+            } => write!(
+                f,
+                "The uniform `{name}` is declared as {declared}, which can't hold a {rust_type}"
+            ),
+        }
+    }
+}
+
+impl error::Error for UniformError {}
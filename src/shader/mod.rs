@@ -1,5 +1,24 @@
+mod builder;
+mod cache;
+mod compute;
+mod diagnostics;
 mod error;
+mod pipeline;
+mod preprocessor;
 mod program;
+mod reflection;
+#[allow(clippy::module_inception)]
 mod shader;
+mod spirv;
+mod uniform;
+mod variants;
+#[cfg(feature = "hot-reload")]
+mod watcher;
 
-pub use {error::*, program::*, shader::*};
+pub use {
+    builder::*, cache::*, compute::*, diagnostics::*, error::*, pipeline::*, preprocessor::*,
+    program::*, reflection::*, shader::*, spirv::*, uniform::*, variants::*,
+};
+
+#[cfg(feature = "hot-reload")]
+pub use watcher::*;
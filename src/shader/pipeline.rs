@@ -0,0 +1,111 @@
+use std::{ffi::c_char, marker::PhantomData, ptr};
+
+use super::{AsShaderType, Program, Shader, ShaderError, ShaderType};
+
+/// A program made of a single stage, combined with other stages by a [`ProgramPipeline`]
+/// without linking them together. Requires OpenGL 4.1.
+///
+/// The outputs of one stage must match the inputs of the next by location or by name and type,
+/// vertex shaders have to redeclare the `gl_PerVertex` block they write.
+pub struct SeparableProgram<S: AsShaderType> {
+    program: Program,
+    data: PhantomData<S>,
+}
+
+impl<S: AsShaderType> SeparableProgram<S> {
+    pub fn new(shader: Shader<S>) -> Result<Self, ShaderError> {
+        let program = unsafe { Program::link_separable(&[shader.handle]) }?;
+        Ok(Self {
+            program,
+            data: PhantomData,
+        })
+    }
+
+    /// The underlying program, e.g. to resolve uniforms with [`Program::uniform`].
+    pub fn as_program(&self) -> &Program {
+        &self.program
+    }
+}
+
+/// Stages taken from several [`SeparableProgram`]s, used with [`crate::DrawLayer::use_pipeline`].
+///
+/// The pipeline doesn't keep its programs alive, they must outlive its use.
+pub struct ProgramPipeline(u32);
+
+impl ProgramPipeline {
+    pub fn new() -> Self {
+        let mut id = 0;
+        unsafe { gl::GenProgramPipelines(1, ptr::addr_of_mut!(id)) };
+        Self(id)
+    }
+
+    /// Run the stage of `program` in this pipeline, replacing the previous program of that stage.
+    pub fn use_stage<S: AsShaderType>(&self, program: &SeparableProgram<S>) {
+        unsafe {
+            gl::UseProgramStages(
+                self.0,
+                S::as_shader_type().stage_bit(),
+                program.program.get_inner(),
+            )
+        }
+    }
+
+    /// Remove the program of `stage`, it is skipped from now on.
+    pub fn remove_stage(&self, stage: ShaderType) {
+        unsafe { gl::UseProgramStages(self.0, stage.stage_bit(), 0) }
+    }
+
+    /// Send the uniforms set by [`crate::DrawLayer::set`] and [`crate::DrawLayer::put_uniform`]
+    /// to `program` while this pipeline is in use.
+    pub fn set_active_program<S: AsShaderType>(&self, program: &SeparableProgram<S>) {
+        unsafe { gl::ActiveShaderProgram(self.0, program.program.get_inner()) }
+    }
+
+    /// Check whether the stages can run together in the current state,
+    /// failing with [`ShaderError::ValidationError`] and the driver's log otherwise.
+    pub fn validate(&self) -> Result<(), ShaderError> {
+        let mut status = 0;
+        unsafe {
+            gl::ValidateProgramPipeline(self.0);
+            gl::GetProgramPipelineiv(self.0, gl::VALIDATE_STATUS, ptr::addr_of_mut!(status));
+        }
+        if status != 0 {
+            return Ok(());
+        }
+
+        let mut length = 0;
+        unsafe { gl::GetProgramPipelineiv(self.0, gl::INFO_LOG_LENGTH, ptr::addr_of_mut!(length)) };
+
+        let mut buffer = vec![0_u8; length.max(1) as usize];
+        let mut written = 0;
+        unsafe {
+            gl::GetProgramPipelineInfoLog(
+                self.0,
+                buffer.len() as i32,
+                ptr::addr_of_mut!(written),
+                buffer.as_mut_ptr() as *mut c_char,
+            )
+        };
+        buffer.truncate(written.max(0) as usize);
+
+        Err(ShaderError::ValidationError(
+            String::from_utf8_lossy(&buffer).into_owned(),
+        ))
+    }
+
+    pub(crate) fn get_inner(&self) -> u32 {
+        self.0
+    }
+}
+
+impl Default for ProgramPipeline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for ProgramPipeline {
+    fn drop(&mut self) {
+        unsafe { gl::DeleteProgramPipelines(1, ptr::addr_of!(self.0)) }
+    }
+}
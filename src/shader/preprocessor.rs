@@ -0,0 +1,328 @@
+use std::{
+    collections::HashMap,
+    error,
+    fmt::{self, Write},
+    fs, io,
+    path::PathBuf,
+    time::SystemTime,
+};
+
+use super::{AsShaderType, Shader, ShaderError};
+
+/// Where a [`Preprocessor`] reads shader files from.
+pub trait ShaderFiles {
+    /// Read the file at `path`, a `/` separated path relative to the root.
+    fn read(&self, path: &str) -> io::Result<String>;
+
+    /// When the file at `path` was last changed, used to reload shaders.
+    /// [`None`] if the files can't change.
+    fn modified(&self, path: &str) -> Option<SystemTime> {
+        let _ = path;
+        None
+    }
+}
+
+/// Files kept in memory, keyed by their path.
+impl ShaderFiles for HashMap<String, String> {
+    fn read(&self, path: &str) -> io::Result<String> {
+        self.get(path).cloned().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, format!("No shader file `{path}`"))
+        })
+    }
+}
+
+/// Files read from a directory on disk.
+pub struct ShaderDirectory(PathBuf);
+
+impl ShaderDirectory {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self(root.into())
+    }
+}
+
+impl ShaderFiles for ShaderDirectory {
+    fn read(&self, path: &str) -> io::Result<String> {
+        fs::read_to_string(self.0.join(path))
+    }
+
+    fn modified(&self, path: &str) -> Option<SystemTime> {
+        fs::metadata(self.0.join(path))
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+}
+
+#[derive(Debug)]
+pub enum PreprocessError {
+    /// A file couldn't be read, `included_from` is the file and line of the `#include`.
+    Read {
+        path: String,
+        included_from: Option<(String, usize)>,
+        error: io::Error,
+    },
+    /// A file includes itself, directly or through other files.
+    IncludeCycle(Vec<String>),
+    /// An `#include` without a quoted path.
+    InvalidInclude { file: String, line: usize },
+}
+
+impl fmt::Display for PreprocessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Read {
+                path,
+                included_from: Some((file, line)),
+                error,
+            } => write!(
+                f,
+                "Cannot read `{path}` included from {file}:{line}: {error}"
+            ),
+            Self::Read { path, error, .. } => write!(f, "Cannot read `{path}`: {error}"),
+            Self::IncludeCycle(files) => write!(f, "Include cycle: {}", files.join(" -> ")),
+            Self::InvalidInclude { file, line } => {
+                write!(f, "Expected a quoted path after #include at {file}:{line}")
+            }
+        }
+    }
+}
+
+impl error::Error for PreprocessError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Read { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// The output of [`Preprocessor::process`].
+///
+/// `#line` directives refer to files by their index in `files`, the first one
+/// is the file that was processed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PreprocessedSource {
+    pub code: String,
+    pub files: Vec<String>,
+}
+
+/// Resolves `#include "file"` directives and injects the `#version` line and `#define`s.
+///
+/// Included paths are relative to the including file. Files containing
+/// `#pragma once` are only included the first time. Conditionals are left
+/// to the shader compiler, so an `#include` inside `#ifdef` is always resolved.
+///
+/// ```ignore
+/// let preprocessor = Preprocessor::new(ShaderDirectory::new("shaders"))
+///     .version("330 core")
+///     .define("MAX_LIGHTS", "4");
+/// let fragment: Shader<Fragment> = preprocessor.compile("lit.frag", &["HAS_NORMAL_MAP"])?;
+/// ```
+pub struct Preprocessor<F: ShaderFiles> {
+    files: F,
+    version: Option<String>,
+    defines: Vec<(String, String)>,
+}
+
+impl<F: ShaderFiles> Preprocessor<F> {
+    pub fn new(files: F) -> Self {
+        Self {
+            files,
+            version: None,
+            defines: Vec::new(),
+        }
+    }
+
+    /// Replace the `#version` of the processed files, like `"330 core"`.
+    pub fn version(mut self, version: &str) -> Self {
+        self.version = Some(version.to_owned());
+        self
+    }
+
+    /// Add `#define name value` to every processed file.
+    pub fn define(mut self, name: &str, value: &str) -> Self {
+        self.defines.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    pub fn files(&self) -> &F {
+        &self.files
+    }
+
+    /// Process `path` with an empty `#define` for each of `features`.
+    pub fn process(
+        &self,
+        path: &str,
+        features: &[&str],
+    ) -> Result<PreprocessedSource, PreprocessError> {
+        let mut state = State {
+            files: Vec::new(),
+            stack: Vec::new(),
+            once: Vec::new(),
+            version: None,
+            body: String::new(),
+        };
+        let text = self.read(path, None)?;
+        self.include(&mut state, path, &text)?;
+
+        let mut code = String::new();
+        if let Some(version) = self.version.as_ref().or(state.version.as_ref()) {
+            writeln!(code, "#version {version}").unwrap();
+        }
+        for (name, value) in &self.defines {
+            writeln!(code, "#define {name} {value}").unwrap();
+        }
+        for feature in features {
+            writeln!(code, "#define {feature}").unwrap();
+        }
+        code.push_str("#line 1 0\n");
+        code.push_str(&state.body);
+
+        Ok(PreprocessedSource {
+            code,
+            files: state.files,
+        })
+    }
+
+    /// Process and compile `path`, compile errors refer to the original files.
+    pub fn compile<S: AsShaderType>(
+        &self,
+        path: &str,
+        features: &[&str],
+    ) -> Result<Shader<S>, ShaderError> {
+        let source = self
+            .process(path, features)
+            .map_err(ShaderError::Preprocessing)?;
+        self.compile_processed(source)
+    }
+
+    pub(crate) fn compile_processed<S: AsShaderType>(
+        &self,
+        source: PreprocessedSource,
+    ) -> Result<Shader<S>, ShaderError> {
+        Shader::compile(&source.code).map_err(|err| match err {
+            ShaderError::CompilationError(mut err) => {
+                err.files = source.files;
+                ShaderError::CompilationError(err)
+            }
+            err => err,
+        })
+    }
+
+    fn read(
+        &self,
+        path: &str,
+        included_from: Option<(&str, usize)>,
+    ) -> Result<String, PreprocessError> {
+        self.files
+            .read(path)
+            .map_err(|error| PreprocessError::Read {
+                path: path.to_owned(),
+                included_from: included_from.map(|(file, line)| (file.to_owned(), line)),
+                error,
+            })
+    }
+
+    fn include(&self, state: &mut State, path: &str, text: &str) -> Result<(), PreprocessError> {
+        if state.stack.iter().any(|file| file == path) {
+            let mut cycle = state.stack.clone();
+            cycle.push(path.to_owned());
+            return Err(PreprocessError::IncludeCycle(cycle));
+        }
+
+        let index = match state.files.iter().position(|file| file == path) {
+            Some(index) => index,
+            None => {
+                state.files.push(path.to_owned());
+                state.files.len() - 1
+            }
+        };
+        state.stack.push(path.to_owned());
+        if state.stack.len() > 1 {
+            state.push_line(&format!("#line 1 {index}"));
+        }
+
+        for (number, line) in text.lines().enumerate() {
+            let number = number + 1;
+            let Some(directive) = line.trim_start().strip_prefix('#') else {
+                state.push_line(line);
+                continue;
+            };
+
+            let directive = directive.trim_start();
+            if let Some(version) = directive.strip_prefix("version") {
+                // Only the first file decides the version, the line is kept
+                // empty so the following lines keep their numbers
+                if state.stack.len() == 1 && state.version.is_none() {
+                    state.version = Some(version.trim().to_owned());
+                }
+                state.push_line("");
+            } else if directive.strip_prefix("pragma").map(str::trim) == Some("once") {
+                if !state.once.iter().any(|file| file == path) {
+                    state.once.push(path.to_owned());
+                }
+                state.push_line("");
+            } else if let Some(argument) = directive.strip_prefix("include") {
+                let included = quoted(argument).ok_or_else(|| PreprocessError::InvalidInclude {
+                    file: path.to_owned(),
+                    line: number,
+                })?;
+                let included = resolve(path, included);
+
+                if !state.once.contains(&included) {
+                    let text = self.read(&included, Some((path, number)))?;
+                    self.include(state, &included, &text)?;
+                }
+                state.push_line(&format!("#line {} {index}", number + 1));
+            } else {
+                state.push_line(line);
+            }
+        }
+
+        state.stack.pop();
+        Ok(())
+    }
+}
+
+struct State {
+    files: Vec<String>,
+    /// The files currently being included, to detect cycles.
+    stack: Vec<String>,
+    /// Files marked with `#pragma once`.
+    once: Vec<String>,
+    version: Option<String>,
+    body: String,
+}
+
+impl State {
+    fn push_line(&mut self, line: &str) {
+        self.body.push_str(line);
+        self.body.push('\n');
+    }
+}
+
+/// The path between the quotes of `"path"`.
+fn quoted(argument: &str) -> Option<&str> {
+    argument
+        .trim()
+        .strip_prefix('"')?
+        .split_once('"')
+        .map(|(path, _)| path)
+}
+
+/// Resolve `path` relative to the directory of `from`.
+fn resolve(from: &str, path: &str) -> String {
+    let mut components: Vec<&str> = from.split('/').collect();
+    components.pop();
+
+    for component in path.split('/') {
+        match component {
+            "" | "." => (),
+            ".." => {
+                components.pop();
+            }
+            component => components.push(component),
+        }
+    }
+
+    components.join("/")
+}
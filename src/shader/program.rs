@@ -1,52 +1,182 @@
 use crate::Attribute;
 
-use super::{Fragment, Shader, ShaderError, Vertex};
+use super::{Fragment, LinkError, ProgramBuilder, Shader, ShaderError, ShaderType, Vertex};
 use std::{
-    ffi::{c_char, CStr, CString},
+    ffi::{c_char, CString},
     ptr,
 };
 
 pub struct Program(u32);
 
+/// A linked program as returned by `glGetProgramBinary`,
+/// only the driver that produced it can load it again.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProgramBinary {
+    /// The driver specific format of `data`.
+    pub format: u32,
+    pub data: Vec<u8>,
+}
+
 impl Program {
     pub fn new(
         vertex_shader: Shader<Vertex>,
         fragment_shader: Shader<Fragment>,
     ) -> Result<Self, ShaderError> {
-        let id = unsafe { Self::link_internal(vertex_shader, fragment_shader) }?;
-        Ok(Self(id))
+        unsafe { Self::link_internal(&[vertex_shader.handle, fragment_shader.handle]) }
     }
 
-    unsafe fn link_internal(
-        vertex_shader: Shader<Vertex>,
-        fragment_shader: Shader<Fragment>,
-    ) -> Result<u32, ShaderError> {
+    /// Start a program with stages other than a vertex and a fragment shader.
+    pub fn builder() -> ProgramBuilder {
+        ProgramBuilder::new()
+    }
+
+    /// Link the shaders behind `handles` into a new program.
+    pub(crate) unsafe fn link_internal(handles: &[u32]) -> Result<Self, ShaderError> {
+        Self::link_with_parameters(handles, &[])
+    }
+
+    /// Like [`Self::link_internal`], but hints the driver that [`Self::binary`] will be called.
+    pub(crate) unsafe fn link_retrievable(handles: &[u32]) -> Result<Self, ShaderError> {
+        Self::link_with_parameters(handles, &[(gl::PROGRAM_BINARY_RETRIEVABLE_HINT, 1)])
+    }
+
+    /// Like [`Self::link_internal`], but the program can be bound to a [`super::ProgramPipeline`].
+    pub(crate) unsafe fn link_separable(handles: &[u32]) -> Result<Self, ShaderError> {
+        Self::link_with_parameters(handles, &[(gl::PROGRAM_SEPARABLE, 1)])
+    }
+
+    /// `parameters` are set with `glProgramParameteri` before linking.
+    unsafe fn link_with_parameters(
+        handles: &[u32],
+        parameters: &[(u32, i32)],
+    ) -> Result<Self, ShaderError> {
         let id = gl::CreateProgram();
-        gl::AttachShader(id, vertex_shader.handle);
-        gl::AttachShader(id, fragment_shader.handle);
+        for &(name, value) in parameters {
+            gl::ProgramParameteri(id, name, value);
+        }
+        for handle in handles {
+            gl::AttachShader(id, *handle);
+        }
         gl::LinkProgram(id);
 
         if !Self::check_link_status(id) {
-            return Err(Self::get_error(id));
+            let err = Self::get_error(id, handles);
+            gl::DeleteProgram(id);
+            return Err(err);
         }
 
-        Ok(id)
+        Ok(Self(id))
     }
 
-    unsafe fn get_error(id: u32) -> ShaderError {
-        let mut buffer = [c_char::MIN; 512];
-        gl::GetProgramInfoLog(id, 512, ptr::null_mut(), buffer.as_mut_ptr());
+    /// Load a program from a binary returned by [`Self::binary`].
+    /// Fails with [`ShaderError::LinkingError`] if the driver rejects it,
+    /// which happens whenever the driver or its version changed.
+    pub fn from_binary(binary: &ProgramBinary) -> Result<Self, ShaderError> {
+        // Unknown formats raise `GL_INVALID_ENUM` instead of failing the link.
+        if !Self::binary_formats().contains(&binary.format) {
+            return Err(ShaderError::LinkingError(LinkError::new(
+                Vec::new(),
+                format!(
+                    "The driver doesn't support the program binary format {:#x}",
+                    binary.format
+                ),
+            )));
+        }
 
-        let err = CStr::from_ptr(buffer.as_ptr())
-            .to_string_lossy()
-            .to_string();
+        unsafe {
+            let id = gl::CreateProgram();
+            gl::ProgramBinary(
+                id,
+                binary.format,
+                binary.data.as_ptr() as *const _,
+                binary.data.len() as i32,
+            );
+
+            if !Self::check_link_status(id) {
+                let err = Self::get_error(id, &[]);
+                gl::DeleteProgram(id);
+                return Err(err);
+            }
+
+            Ok(Self(id))
+        }
+    }
 
-        ShaderError::LinkingError(err)
+    /// The formats [`Self::from_binary`] accepts, empty if program binaries aren't supported.
+    pub fn binary_formats() -> Vec<u32> {
+        let mut count = 0;
+        unsafe { gl::GetIntegerv(gl::NUM_PROGRAM_BINARY_FORMATS, ptr::addr_of_mut!(count)) };
+
+        let mut formats = vec![0_i32; count.max(0) as usize];
+        if !formats.is_empty() {
+            unsafe { gl::GetIntegerv(gl::PROGRAM_BINARY_FORMATS, formats.as_mut_ptr()) };
+        }
+
+        formats.into_iter().map(|format| format as u32).collect()
+    }
+
+    /// Retrieve the linked program in the driver's own format.
+    /// Returns [`None`] if the driver doesn't support any binary format.
+    pub fn binary(&self) -> Option<ProgramBinary> {
+        let mut length = 0;
+        unsafe { gl::GetProgramiv(self.0, gl::PROGRAM_BINARY_LENGTH, ptr::addr_of_mut!(length)) };
+        if length <= 0 {
+            return None;
+        }
+
+        let mut data = vec![0_u8; length as usize];
+        let mut written = 0;
+        let mut format = 0;
+        unsafe {
+            gl::GetProgramBinary(
+                self.0,
+                length,
+                ptr::addr_of_mut!(written),
+                ptr::addr_of_mut!(format),
+                data.as_mut_ptr() as *mut _,
+            )
+        };
+        if written <= 0 {
+            return None;
+        }
+        data.truncate(written as usize);
+
+        Some(ProgramBinary { format, data })
+    }
+
+    /// `handles` are the shaders that were linked, to name their stages.
+    unsafe fn get_error(id: u32, handles: &[u32]) -> ShaderError {
+        let mut length = 0;
+        gl::GetProgramiv(id, gl::INFO_LOG_LENGTH, ptr::addr_of_mut!(length));
+
+        let mut buffer = vec![0_u8; length.max(1) as usize];
+        let mut written = 0;
+        gl::GetProgramInfoLog(
+            id,
+            buffer.len() as i32,
+            ptr::addr_of_mut!(written),
+            buffer.as_mut_ptr() as *mut c_char,
+        );
+        buffer.truncate(written.max(0) as usize);
+
+        let stages = handles
+            .iter()
+            .filter_map(|&handle| {
+                let mut stage = 0;
+                gl::GetShaderiv(handle, gl::SHADER_TYPE, ptr::addr_of_mut!(stage));
+                ShaderType::from_gl(stage as u32)
+            })
+            .collect();
+
+        ShaderError::LinkingError(LinkError::new(
+            stages,
+            String::from_utf8_lossy(&buffer).into_owned(),
+        ))
     }
 
     unsafe fn check_link_status(id: u32) -> bool {
         let mut success = 1;
-        gl::GetProgramiv(id, gl::COMPILE_STATUS, ptr::addr_of_mut!(success));
+        gl::GetProgramiv(id, gl::LINK_STATUS, ptr::addr_of_mut!(success));
 
         success != 0
     }
@@ -69,12 +199,45 @@ impl Program {
         Some(attribute_id)
     }
 
+    /// Connect the shader storage block `name` to the binding point `binding`,
+    /// see [`crate::StorageBuffer::bind_base`].
+    /// Returns [`None`] if the program has no such storage block.
+    pub fn bind_storage_block(&self, name: &str, binding: u32) -> Option<()> {
+        let cstr = CString::new(name).ok()?;
+        let index =
+            unsafe { gl::GetProgramResourceIndex(self.0, gl::SHADER_STORAGE_BLOCK, cstr.as_ptr()) };
+        if index == gl::INVALID_INDEX {
+            return None;
+        }
+
+        unsafe { gl::ShaderStorageBlockBinding(self.0, index, binding) };
+        Some(())
+    }
+
+    /// Connect the uniform block `name` to the binding point `binding`,
+    /// see [`crate::UniformBuffer::bind_base`].
+    /// Returns [`None`] if the program has no such uniform block.
+    pub fn bind_uniform_block(&self, name: &str, binding: u32) -> Option<()> {
+        let cstr = CString::new(name).ok()?;
+        let index = unsafe { gl::GetUniformBlockIndex(self.0, cstr.as_ptr()) };
+        if index == gl::INVALID_INDEX {
+            return None;
+        }
+
+        unsafe { gl::UniformBlockBinding(self.0, index, binding) };
+        Some(())
+    }
+
     pub(crate) fn get_inner(&self) -> u32 {
         self.0
     }
 }
 
 impl Program {
+    /// Make this program the active one.
+    ///
+    /// # Safety
+    /// OpenGL must be loaded and a context must be current on this thread.
     pub unsafe fn use_internal(&self) {
         gl::UseProgram(self.0)
     }
@@ -0,0 +1,512 @@
+use std::{
+    ffi::{c_char, CString},
+    fmt, ptr,
+};
+
+use crate::capabilities::{gl_version, has_extension};
+
+use super::Program;
+
+macro_rules! glsl_types {
+    (
+        values { $($name:ident = $gl:ident $keyword:literal,)* }
+        samplers { $($sampler:ident = $sampler_gl:ident $sampler_keyword:literal,)* }
+        images { $($image:ident = $image_gl:ident $image_keyword:literal,)* }
+    ) => {
+        glsl_types!(@enum
+            $($name = $gl $keyword,)*
+            $($sampler = $sampler_gl $sampler_keyword,)*
+            $($image = $image_gl $image_keyword,)*
+        );
+
+        impl GlslType {
+            /// Whether the type is an opaque sampler, set to the index of a texture unit.
+            pub const fn is_sampler(self) -> bool {
+                matches!(self, $(Self::$sampler)|*)
+            }
+
+            /// Whether the type is an opaque image, set to the index of an image unit.
+            pub const fn is_image(self) -> bool {
+                matches!(self, $(Self::$image)|*)
+            }
+        }
+    };
+
+    (@enum $($name:ident = $gl:ident $keyword:literal,)*) => {
+        /// The type of a shader variable as reported by OpenGL.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub enum GlslType {
+            $($name,)*
+            /// A type without a variant, holding the raw GL enum.
+            Other(u32),
+        }
+
+        impl GlslType {
+            pub const fn from_gl(value: u32) -> Self {
+                match value {
+                    $(gl::$gl => Self::$name,)*
+                    other => Self::Other(other),
+                }
+            }
+
+            pub const fn as_gl(self) -> u32 {
+                match self {
+                    $(Self::$name => gl::$gl,)*
+                    Self::Other(value) => value,
+                }
+            }
+        }
+
+        impl fmt::Display for GlslType {
+            /// Writes the GLSL keyword of the type.
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                    $(Self::$name => f.write_str($keyword),)*
+                    Self::Other(value) => write!(f, "<type {value:#x}>"),
+                }
+            }
+        }
+    };
+}
+
+glsl_types! {
+    values {
+        Float = FLOAT "float",
+        Vec2 = FLOAT_VEC2 "vec2",
+        Vec3 = FLOAT_VEC3 "vec3",
+        Vec4 = FLOAT_VEC4 "vec4",
+        Double = DOUBLE "double",
+        DVec2 = DOUBLE_VEC2 "dvec2",
+        DVec3 = DOUBLE_VEC3 "dvec3",
+        DVec4 = DOUBLE_VEC4 "dvec4",
+        Int = INT "int",
+        IVec2 = INT_VEC2 "ivec2",
+        IVec3 = INT_VEC3 "ivec3",
+        IVec4 = INT_VEC4 "ivec4",
+        UInt = UNSIGNED_INT "uint",
+        UVec2 = UNSIGNED_INT_VEC2 "uvec2",
+        UVec3 = UNSIGNED_INT_VEC3 "uvec3",
+        UVec4 = UNSIGNED_INT_VEC4 "uvec4",
+        Bool = BOOL "bool",
+        BVec2 = BOOL_VEC2 "bvec2",
+        BVec3 = BOOL_VEC3 "bvec3",
+        BVec4 = BOOL_VEC4 "bvec4",
+        Mat2 = FLOAT_MAT2 "mat2",
+        Mat3 = FLOAT_MAT3 "mat3",
+        Mat4 = FLOAT_MAT4 "mat4",
+        Mat2x3 = FLOAT_MAT2x3 "mat2x3",
+        Mat2x4 = FLOAT_MAT2x4 "mat2x4",
+        Mat3x2 = FLOAT_MAT3x2 "mat3x2",
+        Mat3x4 = FLOAT_MAT3x4 "mat3x4",
+        Mat4x2 = FLOAT_MAT4x2 "mat4x2",
+        Mat4x3 = FLOAT_MAT4x3 "mat4x3",
+        DMat2 = DOUBLE_MAT2 "dmat2",
+        DMat3 = DOUBLE_MAT3 "dmat3",
+        DMat4 = DOUBLE_MAT4 "dmat4",
+        DMat2x3 = DOUBLE_MAT2x3 "dmat2x3",
+        DMat2x4 = DOUBLE_MAT2x4 "dmat2x4",
+        DMat3x2 = DOUBLE_MAT3x2 "dmat3x2",
+        DMat3x4 = DOUBLE_MAT3x4 "dmat3x4",
+        DMat4x2 = DOUBLE_MAT4x2 "dmat4x2",
+        DMat4x3 = DOUBLE_MAT4x3 "dmat4x3",
+    }
+    samplers {
+        Sampler1D = SAMPLER_1D "sampler1D",
+        Sampler2D = SAMPLER_2D "sampler2D",
+        Sampler3D = SAMPLER_3D "sampler3D",
+        SamplerCube = SAMPLER_CUBE "samplerCube",
+        Sampler2DRect = SAMPLER_2D_RECT "sampler2DRect",
+        Sampler1DArray = SAMPLER_1D_ARRAY "sampler1DArray",
+        Sampler2DArray = SAMPLER_2D_ARRAY "sampler2DArray",
+        SamplerCubeArray = SAMPLER_CUBE_MAP_ARRAY "samplerCubeArray",
+        SamplerBuffer = SAMPLER_BUFFER "samplerBuffer",
+        Sampler2DMS = SAMPLER_2D_MULTISAMPLE "sampler2DMS",
+        Sampler2DMSArray = SAMPLER_2D_MULTISAMPLE_ARRAY "sampler2DMSArray",
+        Sampler1DShadow = SAMPLER_1D_SHADOW "sampler1DShadow",
+        Sampler2DShadow = SAMPLER_2D_SHADOW "sampler2DShadow",
+        SamplerCubeShadow = SAMPLER_CUBE_SHADOW "samplerCubeShadow",
+        Sampler2DRectShadow = SAMPLER_2D_RECT_SHADOW "sampler2DRectShadow",
+        Sampler1DArrayShadow = SAMPLER_1D_ARRAY_SHADOW "sampler1DArrayShadow",
+        Sampler2DArrayShadow = SAMPLER_2D_ARRAY_SHADOW "sampler2DArrayShadow",
+        SamplerCubeArrayShadow = SAMPLER_CUBE_MAP_ARRAY_SHADOW "samplerCubeArrayShadow",
+        ISampler1D = INT_SAMPLER_1D "isampler1D",
+        ISampler2D = INT_SAMPLER_2D "isampler2D",
+        ISampler3D = INT_SAMPLER_3D "isampler3D",
+        ISamplerCube = INT_SAMPLER_CUBE "isamplerCube",
+        ISampler2DRect = INT_SAMPLER_2D_RECT "isampler2DRect",
+        ISampler1DArray = INT_SAMPLER_1D_ARRAY "isampler1DArray",
+        ISampler2DArray = INT_SAMPLER_2D_ARRAY "isampler2DArray",
+        ISamplerCubeArray = INT_SAMPLER_CUBE_MAP_ARRAY "isamplerCubeArray",
+        ISamplerBuffer = INT_SAMPLER_BUFFER "isamplerBuffer",
+        ISampler2DMS = INT_SAMPLER_2D_MULTISAMPLE "isampler2DMS",
+        ISampler2DMSArray = INT_SAMPLER_2D_MULTISAMPLE_ARRAY "isampler2DMSArray",
+        USampler1D = UNSIGNED_INT_SAMPLER_1D "usampler1D",
+        USampler2D = UNSIGNED_INT_SAMPLER_2D "usampler2D",
+        USampler3D = UNSIGNED_INT_SAMPLER_3D "usampler3D",
+        USamplerCube = UNSIGNED_INT_SAMPLER_CUBE "usamplerCube",
+        USampler2DRect = UNSIGNED_INT_SAMPLER_2D_RECT "usampler2DRect",
+        USampler1DArray = UNSIGNED_INT_SAMPLER_1D_ARRAY "usampler1DArray",
+        USampler2DArray = UNSIGNED_INT_SAMPLER_2D_ARRAY "usampler2DArray",
+        USamplerCubeArray = UNSIGNED_INT_SAMPLER_CUBE_MAP_ARRAY "usamplerCubeArray",
+        USamplerBuffer = UNSIGNED_INT_SAMPLER_BUFFER "usamplerBuffer",
+        USampler2DMS = UNSIGNED_INT_SAMPLER_2D_MULTISAMPLE "usampler2DMS",
+        USampler2DMSArray = UNSIGNED_INT_SAMPLER_2D_MULTISAMPLE_ARRAY "usampler2DMSArray",
+    }
+    images {
+        Image1D = IMAGE_1D "image1D",
+        Image2D = IMAGE_2D "image2D",
+        Image3D = IMAGE_3D "image3D",
+        ImageCube = IMAGE_CUBE "imageCube",
+        Image2DRect = IMAGE_2D_RECT "image2DRect",
+        Image1DArray = IMAGE_1D_ARRAY "image1DArray",
+        Image2DArray = IMAGE_2D_ARRAY "image2DArray",
+        ImageCubeArray = IMAGE_CUBE_MAP_ARRAY "imageCubeArray",
+        ImageBuffer = IMAGE_BUFFER "imageBuffer",
+        Image2DMS = IMAGE_2D_MULTISAMPLE "image2DMS",
+        Image2DMSArray = IMAGE_2D_MULTISAMPLE_ARRAY "image2DMSArray",
+        IImage1D = INT_IMAGE_1D "iimage1D",
+        IImage2D = INT_IMAGE_2D "iimage2D",
+        IImage3D = INT_IMAGE_3D "iimage3D",
+        IImageCube = INT_IMAGE_CUBE "iimageCube",
+        IImage2DRect = INT_IMAGE_2D_RECT "iimage2DRect",
+        IImage1DArray = INT_IMAGE_1D_ARRAY "iimage1DArray",
+        IImage2DArray = INT_IMAGE_2D_ARRAY "iimage2DArray",
+        IImageCubeArray = INT_IMAGE_CUBE_MAP_ARRAY "iimageCubeArray",
+        IImageBuffer = INT_IMAGE_BUFFER "iimageBuffer",
+        IImage2DMS = INT_IMAGE_2D_MULTISAMPLE "iimage2DMS",
+        IImage2DMSArray = INT_IMAGE_2D_MULTISAMPLE_ARRAY "iimage2DMSArray",
+        UImage1D = UNSIGNED_INT_IMAGE_1D "uimage1D",
+        UImage2D = UNSIGNED_INT_IMAGE_2D "uimage2D",
+        UImage3D = UNSIGNED_INT_IMAGE_3D "uimage3D",
+        UImageCube = UNSIGNED_INT_IMAGE_CUBE "uimageCube",
+        UImage2DRect = UNSIGNED_INT_IMAGE_2D_RECT "uimage2DRect",
+        UImage1DArray = UNSIGNED_INT_IMAGE_1D_ARRAY "uimage1DArray",
+        UImage2DArray = UNSIGNED_INT_IMAGE_2D_ARRAY "uimage2DArray",
+        UImageCubeArray = UNSIGNED_INT_IMAGE_CUBE_MAP_ARRAY "uimageCubeArray",
+        UImageBuffer = UNSIGNED_INT_IMAGE_BUFFER "uimageBuffer",
+        UImage2DMS = UNSIGNED_INT_IMAGE_2D_MULTISAMPLE "uimage2DMS",
+        UImage2DMSArray = UNSIGNED_INT_IMAGE_2D_MULTISAMPLE_ARRAY "uimage2DMSArray",
+    }
+}
+
+/// A vertex input of a program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ActiveAttribute {
+    pub name: String,
+    pub ty: GlslType,
+    /// The number of elements, 1 for attributes which aren't arrays.
+    pub array_size: i32,
+    pub location: i32,
+}
+
+/// A uniform of a program, either a plain one or a member of a uniform block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ActiveUniform {
+    /// Arrays are named after their first element, like `lights[0]`.
+    pub name: String,
+    pub ty: GlslType,
+    /// The number of elements, 1 for uniforms which aren't arrays.
+    pub array_size: i32,
+    /// [`None`] for members of a uniform block.
+    pub location: Option<i32>,
+    /// The index into [`ProgramInterface::uniform_blocks`] and the offset in bytes
+    /// of a block member.
+    pub block: Option<(usize, usize)>,
+}
+
+/// A uniform or shader storage block of a program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ActiveBlock {
+    pub name: String,
+    /// The binding point the block is connected to.
+    pub binding: u32,
+    /// The minimum size of a buffer backing the block, in bytes.
+    pub data_size: usize,
+}
+
+/// Everything a linked program declares, see [`Program::reflect`].
+/// Built-in variables like `gl_VertexID` are left out.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProgramInterface {
+    pub attributes: Vec<ActiveAttribute>,
+    pub uniforms: Vec<ActiveUniform>,
+    pub uniform_blocks: Vec<ActiveBlock>,
+    /// Always empty before OpenGL 4.3.
+    pub storage_blocks: Vec<ActiveBlock>,
+}
+
+impl ProgramInterface {
+    /// Find an attribute, arrays can be named with or without the `[0]` suffix.
+    pub fn attribute(&self, name: &str) -> Option<&ActiveAttribute> {
+        self.attributes.iter().find(|attribute| {
+            attribute.name == name || attribute.name.strip_suffix("[0]") == Some(name)
+        })
+    }
+
+    /// Find a uniform, arrays can be named with or without the `[0]` suffix.
+    pub fn uniform(&self, name: &str) -> Option<&ActiveUniform> {
+        self.uniforms
+            .iter()
+            .find(|uniform| uniform.name == name || uniform.name.strip_suffix("[0]") == Some(name))
+    }
+
+    pub fn uniform_block(&self, name: &str) -> Option<&ActiveBlock> {
+        self.uniform_blocks.iter().find(|block| block.name == name)
+    }
+
+    pub fn storage_block(&self, name: &str) -> Option<&ActiveBlock> {
+        self.storage_blocks.iter().find(|block| block.name == name)
+    }
+}
+
+impl Program {
+    /// Query the interface of the program from OpenGL.
+    pub fn reflect(&self) -> ProgramInterface {
+        unsafe {
+            ProgramInterface {
+                attributes: self.active_attributes(),
+                uniforms: self.active_uniforms(),
+                uniform_blocks: self.active_uniform_blocks(),
+                storage_blocks: self.active_storage_blocks(),
+            }
+        }
+    }
+
+    unsafe fn program_parameter(&self, parameter: u32) -> i32 {
+        let mut value = 0;
+        gl::GetProgramiv(self.get_inner(), parameter, ptr::addr_of_mut!(value));
+        value
+    }
+
+    unsafe fn active_attributes(&self) -> Vec<ActiveAttribute> {
+        let count = self.program_parameter(gl::ACTIVE_ATTRIBUTES);
+        let max_length = self.program_parameter(gl::ACTIVE_ATTRIBUTE_MAX_LENGTH);
+
+        let mut attributes = Vec::new();
+        for index in 0..count as u32 {
+            let (mut array_size, mut ty) = (0, 0);
+            let name = read_name(max_length, |length, written, buffer| {
+                gl::GetActiveAttrib(
+                    self.get_inner(),
+                    index,
+                    length,
+                    written,
+                    ptr::addr_of_mut!(array_size),
+                    ptr::addr_of_mut!(ty),
+                    buffer,
+                )
+            });
+            if name.starts_with("gl_") {
+                continue;
+            }
+
+            let cstr = CString::new(name.as_str()).unwrap_or_default();
+            let location = gl::GetAttribLocation(self.get_inner(), cstr.as_ptr());
+            attributes.push(ActiveAttribute {
+                name,
+                ty: GlslType::from_gl(ty),
+                array_size,
+                location,
+            });
+        }
+
+        attributes
+    }
+
+    unsafe fn active_uniforms(&self) -> Vec<ActiveUniform> {
+        let count = self.program_parameter(gl::ACTIVE_UNIFORMS);
+        let max_length = self.program_parameter(gl::ACTIVE_UNIFORM_MAX_LENGTH);
+
+        (0..count as u32)
+            .map(|index| self.active_uniform(index, max_length))
+            .filter(|uniform| !uniform.name.starts_with("gl_"))
+            .collect()
+    }
+
+    /// Look up a single uniform without reflecting the whole program. `name` can be
+    /// an array element like `lights[2]`, which shares the type of the whole array
+    /// but has a location of its own.
+    pub(crate) fn find_uniform(&self, name: &str) -> Option<ActiveUniform> {
+        let cstr = CString::new(name).ok()?;
+        let mut index = gl::INVALID_INDEX;
+        unsafe {
+            gl::GetUniformIndices(
+                self.get_inner(),
+                1,
+                &cstr.as_ptr(),
+                ptr::addr_of_mut!(index),
+            )
+        };
+        if index == gl::INVALID_INDEX {
+            return None;
+        }
+
+        unsafe {
+            let max_length = self.program_parameter(gl::ACTIVE_UNIFORM_MAX_LENGTH);
+            let mut uniform = self.active_uniform(index, max_length);
+            if uniform.location.is_some() {
+                let location = gl::GetUniformLocation(self.get_inner(), cstr.as_ptr());
+                if location == -1 {
+                    return None;
+                }
+                uniform.location = Some(location);
+            }
+
+            Some(uniform)
+        }
+    }
+
+    unsafe fn active_uniform(&self, index: u32, max_length: i32) -> ActiveUniform {
+        let (mut array_size, mut ty) = (0, 0);
+        let name = read_name(max_length, |length, written, buffer| {
+            gl::GetActiveUniform(
+                self.get_inner(),
+                index,
+                length,
+                written,
+                ptr::addr_of_mut!(array_size),
+                ptr::addr_of_mut!(ty),
+                buffer,
+            )
+        });
+
+        let (mut block_index, mut offset) = (-1, -1);
+        gl::GetActiveUniformsiv(
+            self.get_inner(),
+            1,
+            ptr::addr_of!(index),
+            gl::UNIFORM_BLOCK_INDEX,
+            ptr::addr_of_mut!(block_index),
+        );
+        gl::GetActiveUniformsiv(
+            self.get_inner(),
+            1,
+            ptr::addr_of!(index),
+            gl::UNIFORM_OFFSET,
+            ptr::addr_of_mut!(offset),
+        );
+
+        let (location, block) = if block_index == -1 {
+            let cstr = CString::new(name.as_str()).unwrap_or_default();
+            let location = gl::GetUniformLocation(self.get_inner(), cstr.as_ptr());
+            (Some(location), None)
+        } else {
+            (None, Some((block_index as usize, offset as usize)))
+        };
+
+        ActiveUniform {
+            name,
+            ty: GlslType::from_gl(ty),
+            array_size,
+            location,
+            block,
+        }
+    }
+
+    unsafe fn active_uniform_blocks(&self) -> Vec<ActiveBlock> {
+        let count = self.program_parameter(gl::ACTIVE_UNIFORM_BLOCKS);
+        let max_length = self.program_parameter(gl::ACTIVE_UNIFORM_BLOCK_MAX_NAME_LENGTH);
+
+        (0..count as u32)
+            .map(|index| {
+                let name = read_name(max_length, |length, written, buffer| {
+                    gl::GetActiveUniformBlockName(self.get_inner(), index, length, written, buffer)
+                });
+
+                let (mut binding, mut data_size) = (0, 0);
+                gl::GetActiveUniformBlockiv(
+                    self.get_inner(),
+                    index,
+                    gl::UNIFORM_BLOCK_BINDING,
+                    ptr::addr_of_mut!(binding),
+                );
+                gl::GetActiveUniformBlockiv(
+                    self.get_inner(),
+                    index,
+                    gl::UNIFORM_BLOCK_DATA_SIZE,
+                    ptr::addr_of_mut!(data_size),
+                );
+
+                ActiveBlock {
+                    name,
+                    binding: binding as u32,
+                    data_size: data_size as usize,
+                }
+            })
+            .collect()
+    }
+
+    unsafe fn active_storage_blocks(&self) -> Vec<ActiveBlock> {
+        // Program interface queries are core since 4.3, as are storage blocks. The entry point
+        // can be loaded on older contexts, where calling it raises an error.
+        let supported = gl_version() >= (4, 3)
+            || has_extension(c"GL_ARB_program_interface_query")
+                && has_extension(c"GL_ARB_shader_storage_buffer_object");
+        if !gl::GetProgramInterfaceiv::is_loaded() || !supported {
+            return Vec::new();
+        }
+
+        let (mut count, mut max_length) = (0, 0);
+        gl::GetProgramInterfaceiv(
+            self.get_inner(),
+            gl::SHADER_STORAGE_BLOCK,
+            gl::ACTIVE_RESOURCES,
+            ptr::addr_of_mut!(count),
+        );
+        gl::GetProgramInterfaceiv(
+            self.get_inner(),
+            gl::SHADER_STORAGE_BLOCK,
+            gl::MAX_NAME_LENGTH,
+            ptr::addr_of_mut!(max_length),
+        );
+
+        (0..count as u32)
+            .map(|index| {
+                let name = read_name(max_length, |length, written, buffer| {
+                    gl::GetProgramResourceName(
+                        self.get_inner(),
+                        gl::SHADER_STORAGE_BLOCK,
+                        index,
+                        length,
+                        written,
+                        buffer,
+                    )
+                });
+
+                let properties = [gl::BUFFER_BINDING, gl::BUFFER_DATA_SIZE];
+                let mut values = [0; 2];
+                gl::GetProgramResourceiv(
+                    self.get_inner(),
+                    gl::SHADER_STORAGE_BLOCK,
+                    index,
+                    properties.len() as i32,
+                    properties.as_ptr(),
+                    values.len() as i32,
+                    ptr::null_mut(),
+                    values.as_mut_ptr(),
+                );
+
+                ActiveBlock {
+                    name,
+                    binding: values[0] as u32,
+                    data_size: values[1] as usize,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Read a name of at most `max_length` bytes (including the terminator) with `query`,
+/// which receives the buffer length, a pointer to the written length and the buffer.
+unsafe fn read_name(max_length: i32, query: impl FnOnce(i32, *mut i32, *mut c_char)) -> String {
+    let mut buffer = vec![0_u8; max_length.max(1) as usize];
+    let mut written = 0;
+    query(
+        buffer.len() as i32,
+        ptr::addr_of_mut!(written),
+        buffer.as_mut_ptr() as *mut c_char,
+    );
+
+    buffer.truncate(written.max(0) as usize);
+    String::from_utf8_lossy(&buffer).into_owned()
+}
@@ -1,16 +1,62 @@
 use std::{
-    ffi::{c_char, CStr, CString},
+    ffi::{c_char, CString},
+    fmt,
     marker::PhantomData,
     ptr,
 };
 
-use super::ShaderError;
+use super::{specialize_stage, CompileError, ShaderError, SpecializationConstant};
 
 #[repr(u32)]
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ShaderType {
     Fragment = gl::FRAGMENT_SHADER,
     Vertex = gl::VERTEX_SHADER,
+    Compute = gl::COMPUTE_SHADER,
+    Geometry = gl::GEOMETRY_SHADER,
+    TessControl = gl::TESS_CONTROL_SHADER,
+    TessEvaluation = gl::TESS_EVALUATION_SHADER,
+}
+
+impl fmt::Display for ShaderType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Fragment => "fragment",
+            Self::Vertex => "vertex",
+            Self::Compute => "compute",
+            Self::Geometry => "geometry",
+            Self::TessControl => "tessellation control",
+            Self::TessEvaluation => "tessellation evaluation",
+        })
+    }
+}
+
+impl ShaderType {
+    /// The stage of a `GL_*_SHADER` value.
+    pub(crate) fn from_gl(value: u32) -> Option<Self> {
+        [
+            Self::Fragment,
+            Self::Vertex,
+            Self::Compute,
+            Self::Geometry,
+            Self::TessControl,
+            Self::TessEvaluation,
+        ]
+        .into_iter()
+        .find(|stage| *stage as u32 == value)
+    }
+
+    /// The `GL_*_SHADER_BIT` selecting this stage in `glUseProgramStages`.
+    pub(crate) fn stage_bit(self) -> u32 {
+        match self {
+            Self::Fragment => gl::FRAGMENT_SHADER_BIT,
+            Self::Vertex => gl::VERTEX_SHADER_BIT,
+            Self::Compute => gl::COMPUTE_SHADER_BIT,
+            Self::Geometry => gl::GEOMETRY_SHADER_BIT,
+            Self::TessControl => gl::TESS_CONTROL_SHADER_BIT,
+            Self::TessEvaluation => gl::TESS_EVALUATION_SHADER_BIT,
+        }
+    }
 }
 
 pub trait AsShaderType {
@@ -33,6 +79,40 @@ impl AsShaderType for Vertex {
     }
 }
 
+pub struct Compute;
+
+impl AsShaderType for Compute {
+    fn as_shader_type() -> ShaderType {
+        ShaderType::Compute
+    }
+}
+
+pub struct Geometry;
+
+impl AsShaderType for Geometry {
+    fn as_shader_type() -> ShaderType {
+        ShaderType::Geometry
+    }
+}
+
+/// Requires OpenGL 4.0.
+pub struct TessControl;
+
+impl AsShaderType for TessControl {
+    fn as_shader_type() -> ShaderType {
+        ShaderType::TessControl
+    }
+}
+
+/// Requires OpenGL 4.0.
+pub struct TessEvaluation;
+
+impl AsShaderType for TessEvaluation {
+    fn as_shader_type() -> ShaderType {
+        ShaderType::TessEvaluation
+    }
+}
+
 pub struct Shader<S: AsShaderType> {
     pub(crate) handle: u32,
     data: PhantomData<S>,
@@ -40,45 +120,87 @@ pub struct Shader<S: AsShaderType> {
 
 impl<S: AsShaderType> Shader<S> {
     pub fn compile(source: &str) -> Result<Self, ShaderError> {
-        let cstr = CString::new(source).map_err(ShaderError::CStringConversion)?;
-        let ptr = cstr.as_ptr();
+        let handle = compile_stage(S::as_shader_type(), source)?;
+        Ok(Self {
+            handle,
+            data: PhantomData,
+        })
+    }
 
-        let id = unsafe { Self::compile_internal(ptr) }?;
+    /// Create the shader from a SPIR-V module in native byte order, starting at `entry_point`
+    /// (usually `main`). `constants` override the defaults of the specialization constants.
+    ///
+    /// Requires OpenGL 4.6 or `GL_ARB_gl_spirv`, see [`super::spirv_supported`].
+    /// Every shader of a program must come from SPIR-V or none of them.
+    pub fn from_spirv(
+        module: &[u8],
+        entry_point: &str,
+        constants: &[SpecializationConstant],
+    ) -> Result<Self, ShaderError> {
+        let handle = specialize_stage(S::as_shader_type(), module, entry_point, constants)?;
         Ok(Self {
-            handle: id,
+            handle,
             data: PhantomData,
         })
     }
+}
 
-    unsafe fn compile_internal(source: *const c_char) -> Result<u32, ShaderError> {
-        let id = gl::CreateShader(S::as_shader_type() as u32);
-        gl::ShaderSource(id, 1, ptr::addr_of!(source), ptr::null());
-        gl::CompileShader(id);
+/// Compile `source` as a `stage` shader, the caller owns the returned handle.
+pub(crate) fn compile_stage(stage: ShaderType, source: &str) -> Result<u32, ShaderError> {
+    let cstr = CString::new(source).map_err(ShaderError::CStringConversion)?;
+    let ptr = cstr.as_ptr();
 
-        if !Self::check_compile_status(id) {
-            return Err(Self::get_error(id));
-        }
+    unsafe { compile_internal(stage, ptr) }.map_err(|log| {
+        let err = CompileError::new(stage, log, source.to_owned());
+        ShaderError::CompilationError(err)
+    })
+}
 
-        Ok(id)
-    }
+/// Returns the compile log on failure.
+unsafe fn compile_internal(stage: ShaderType, source: *const c_char) -> Result<u32, String> {
+    let id = gl::CreateShader(stage as u32);
+    gl::ShaderSource(id, 1, ptr::addr_of!(source), ptr::null());
+    gl::CompileShader(id);
 
-    unsafe fn get_error(id: u32) -> ShaderError {
-        let mut buffer = [c_char::MIN; 512];
-        gl::GetShaderInfoLog(id, 512, ptr::null_mut(), buffer.as_mut_ptr());
+    if let Err(log) = compile_log(id) {
+        gl::DeleteShader(id);
+        return Err(log);
+    }
 
-        let err = CStr::from_ptr(buffer.as_ptr())
-            .to_string_lossy()
-            .to_string();
+    Ok(id)
+}
 
-        ShaderError::CompilationError(err)
+/// Returns the log if compiling or specializing the shader `id` failed.
+pub(crate) unsafe fn compile_log(id: u32) -> Result<(), String> {
+    if check_compile_status(id) {
+        Ok(())
+    } else {
+        Err(get_log(id))
     }
+}
 
-    unsafe fn check_compile_status(id: u32) -> bool {
-        let mut success = 1;
-        gl::GetShaderiv(id, gl::COMPILE_STATUS, ptr::addr_of_mut!(success));
+unsafe fn get_log(id: u32) -> String {
+    let mut length = 0;
+    gl::GetShaderiv(id, gl::INFO_LOG_LENGTH, ptr::addr_of_mut!(length));
+
+    let mut buffer = vec![0_u8; length.max(1) as usize];
+    let mut written = 0;
+    gl::GetShaderInfoLog(
+        id,
+        buffer.len() as i32,
+        ptr::addr_of_mut!(written),
+        buffer.as_mut_ptr() as *mut c_char,
+    );
+    buffer.truncate(written.max(0) as usize);
+
+    String::from_utf8_lossy(&buffer).into_owned()
+}
 
-        success != 0
-    }
+unsafe fn check_compile_status(id: u32) -> bool {
+    let mut success = 1;
+    gl::GetShaderiv(id, gl::COMPILE_STATUS, ptr::addr_of_mut!(success));
+
+    success != 0
 }
 
 impl<S: AsShaderType> Drop for Shader<S> {
@@ -0,0 +1,150 @@
+use std::{
+    ffi::{c_char, c_void, CString},
+    mem, ptr,
+    sync::atomic::{AtomicPtr, Ordering},
+};
+
+use crate::capabilities::{gl_version, has_extension};
+
+use super::{compile_log, CompileError, ShaderError, ShaderType};
+
+/// `GL_SHADER_BINARY_FORMAT_SPIR_V`, the `gl` crate stops at OpenGL 4.5.
+const SHADER_BINARY_FORMAT_SPIR_V: u32 = 0x9551;
+/// The first word of every SPIR-V module.
+const SPIRV_MAGIC: u32 = 0x0723_0203;
+
+type SpecializeShaderFn = extern "system" fn(u32, *const c_char, u32, *const u32, *const u32);
+
+/// `glSpecializeShader`, or `glSpecializeShaderARB` on drivers that only have the extension.
+static SPECIALIZE_SHADER: AtomicPtr<c_void> = AtomicPtr::new(ptr::null_mut());
+
+/// Load the functions newer than the `gl` crate, alongside `gl::load_with`.
+pub(crate) fn load_with(mut loader: impl FnMut(&'static str) -> *const c_void) {
+    let mut specialize = loader("glSpecializeShader");
+    if specialize.is_null() {
+        specialize = loader("glSpecializeShaderARB");
+    }
+    SPECIALIZE_SHADER.store(specialize as *mut c_void, Ordering::Relaxed);
+}
+
+/// Whether [`super::Shader::from_spirv`] can be used,
+/// which requires OpenGL 4.6 or the `GL_ARB_gl_spirv` extension.
+pub fn spirv_supported() -> bool {
+    if SPECIALIZE_SHADER.load(Ordering::Relaxed).is_null() || !gl::ShaderBinary::is_loaded() {
+        return false;
+    }
+
+    gl_version() >= (4, 6) || has_extension(c"GL_ARB_gl_spirv")
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// The types a specialization constant can be set to: [`bool`], [`i32`], [`u32`] and [`f32`].
+pub trait SpecializationValue: sealed::Sealed {
+    /// The value as the 32 bits `glSpecializeShader` expects.
+    fn to_bits(self) -> u32;
+}
+
+impl sealed::Sealed for bool {}
+
+impl SpecializationValue for bool {
+    fn to_bits(self) -> u32 {
+        self as u32
+    }
+}
+
+impl sealed::Sealed for i32 {}
+
+impl SpecializationValue for i32 {
+    fn to_bits(self) -> u32 {
+        self as u32
+    }
+}
+
+impl sealed::Sealed for u32 {}
+
+impl SpecializationValue for u32 {
+    fn to_bits(self) -> u32 {
+        self
+    }
+}
+
+impl sealed::Sealed for f32 {}
+
+impl SpecializationValue for f32 {
+    fn to_bits(self) -> u32 {
+        f32::to_bits(self)
+    }
+}
+
+/// Overrides the default of the constant declared with `layout(constant_id = id)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SpecializationConstant {
+    pub id: u32,
+    pub value: u32,
+}
+
+impl SpecializationConstant {
+    pub fn new(id: u32, value: impl SpecializationValue) -> Self {
+        Self {
+            id,
+            value: value.to_bits(),
+        }
+    }
+}
+
+/// Create a `stage` shader from a SPIR-V module, the caller owns the returned handle.
+pub(crate) fn specialize_stage(
+    stage: ShaderType,
+    module: &[u8],
+    entry_point: &str,
+    constants: &[SpecializationConstant],
+) -> Result<u32, ShaderError> {
+    if !spirv_supported() {
+        return Err(ShaderError::SpirvUnsupported);
+    }
+    if module.is_empty() || !module.len().is_multiple_of(4) {
+        return Err(ShaderError::InvalidSpirv(
+            "the length is not a multiple of 4 bytes",
+        ));
+    }
+    if module[..4] != SPIRV_MAGIC.to_ne_bytes() {
+        return Err(ShaderError::InvalidSpirv(
+            "the magic number is missing or in the wrong byte order",
+        ));
+    }
+
+    let entry_point = CString::new(entry_point).map_err(ShaderError::CStringConversion)?;
+    let ids: Vec<u32> = constants.iter().map(|constant| constant.id).collect();
+    let values: Vec<u32> = constants.iter().map(|constant| constant.value).collect();
+
+    unsafe {
+        let specialize: SpecializeShaderFn =
+            mem::transmute(SPECIALIZE_SHADER.load(Ordering::Relaxed));
+
+        let id = gl::CreateShader(stage as u32);
+        gl::ShaderBinary(
+            1,
+            ptr::addr_of!(id),
+            SHADER_BINARY_FORMAT_SPIR_V,
+            module.as_ptr() as *const _,
+            module.len() as i32,
+        );
+        specialize(
+            id,
+            entry_point.as_ptr(),
+            constants.len() as u32,
+            ids.as_ptr(),
+            values.as_ptr(),
+        );
+
+        compile_log(id).map_err(|log| {
+            gl::DeleteShader(id);
+            ShaderError::CompilationError(CompileError::new(stage, log, String::new()))
+        })?;
+
+        Ok(id)
+    }
+}
@@ -0,0 +1,62 @@
+use std::{any, marker::PhantomData};
+
+use super::{Program, UniformError};
+use crate::UniformResource;
+
+/// The location of a uniform holding a `T`, resolved once by [`Program::uniform`]
+/// and set with [`crate::DrawLayer::set`].
+///
+/// ```ignore
+/// let time = program.uniform::<f32>("time")?;
+/// loop {
+///     draw_layer.use_program(&program);
+///     draw_layer.set(&time, &elapsed);
+/// }
+/// ```
+pub struct Uniform<T: ?Sized> {
+    location: i32,
+    data: PhantomData<fn(&T)>,
+}
+
+impl<T: ?Sized> Uniform<T> {
+    pub fn location(&self) -> i32 {
+        self.location
+    }
+}
+
+impl<T: ?Sized> Clone for Uniform<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for Uniform<T> {}
+
+impl Program {
+    /// Resolve the uniform `name`, checking that its declared type can hold a `T`.
+    /// Arrays can be named with or without the `[0]` suffix.
+    pub fn uniform<T>(&self, name: &str) -> Result<Uniform<T>, UniformError>
+    where
+        T: UniformResource + ?Sized,
+    {
+        let uniform = self
+            .find_uniform(name)
+            .ok_or_else(|| UniformError::NotFound(name.to_owned()))?;
+        let location = uniform
+            .location
+            .ok_or_else(|| UniformError::InUniformBlock(name.to_owned()))?;
+
+        if !T::accepts(uniform.ty) {
+            return Err(UniformError::TypeMismatch {
+                name: name.to_owned(),
+                declared: uniform.ty,
+                rust_type: any::type_name::<T>(),
+            });
+        }
+
+        Ok(Uniform {
+            location,
+            data: PhantomData,
+        })
+    }
+}
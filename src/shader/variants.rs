@@ -0,0 +1,149 @@
+use std::collections::{hash_map::Entry, HashMap};
+
+use super::{Preprocessor, Program, ShaderError, ShaderFiles};
+
+/// The files each stage of a program is preprocessed from.
+#[derive(Clone, Debug)]
+pub struct ProgramSources {
+    vertex: String,
+    tess_control: Option<String>,
+    tess_evaluation: Option<String>,
+    geometry: Option<String>,
+    fragment: String,
+}
+
+impl ProgramSources {
+    pub fn new(vertex: &str, fragment: &str) -> Self {
+        Self {
+            vertex: vertex.to_owned(),
+            tess_control: None,
+            tess_evaluation: None,
+            geometry: None,
+            fragment: fragment.to_owned(),
+        }
+    }
+
+    pub fn tess_control(mut self, path: &str) -> Self {
+        self.tess_control = Some(path.to_owned());
+        self
+    }
+
+    pub fn tess_evaluation(mut self, path: &str) -> Self {
+        self.tess_evaluation = Some(path.to_owned());
+        self
+    }
+
+    pub fn geometry(mut self, path: &str) -> Self {
+        self.geometry = Some(path.to_owned());
+        self
+    }
+
+    /// Compile and link every stage, `files` receives every file read on the way,
+    /// including the ones of stages that failed to compile.
+    pub(crate) fn build<F: ShaderFiles>(
+        &self,
+        preprocessor: &Preprocessor<F>,
+        features: &[&str],
+        files: &mut Vec<String>,
+    ) -> Result<Program, ShaderError> {
+        let mut process = |path: &String| {
+            let source = preprocessor
+                .process(path, features)
+                .map_err(ShaderError::Preprocessing)?;
+            for file in &source.files {
+                if !files.contains(file) {
+                    files.push(file.clone());
+                }
+            }
+
+            Ok::<_, ShaderError>(source)
+        };
+
+        let mut builder = Program::builder();
+        builder = builder.vertex(preprocessor.compile_processed(process(&self.vertex)?)?);
+        if let Some(path) = &self.tess_control {
+            builder = builder.tess_control(preprocessor.compile_processed(process(path)?)?);
+        }
+        if let Some(path) = &self.tess_evaluation {
+            builder = builder.tess_evaluation(preprocessor.compile_processed(process(path)?)?);
+        }
+        if let Some(path) = &self.geometry {
+            builder = builder.geometry(preprocessor.compile_processed(process(path)?)?);
+        }
+        builder = builder.fragment(preprocessor.compile_processed(process(&self.fragment)?)?);
+
+        builder.build()
+    }
+}
+
+/// Programs built from the same files with different sets of features,
+/// each feature becomes an empty `#define`. Every combination is compiled once.
+///
+/// ```ignore
+/// let mut variants = ProgramVariants::new(preprocessor, "mesh.vert", "mesh.frag");
+/// let skinned = variants.get(&["SKINNED", "HAS_NORMAL_MAP"])?;
+/// ```
+pub struct ProgramVariants<F: ShaderFiles> {
+    preprocessor: Preprocessor<F>,
+    sources: ProgramSources,
+    /// Keyed by the sorted features.
+    programs: HashMap<Vec<String>, Program>,
+}
+
+impl<F: ShaderFiles> ProgramVariants<F> {
+    pub fn new(preprocessor: Preprocessor<F>, vertex: &str, fragment: &str) -> Self {
+        Self::with_sources(preprocessor, ProgramSources::new(vertex, fragment))
+    }
+
+    pub fn with_sources(preprocessor: Preprocessor<F>, sources: ProgramSources) -> Self {
+        Self {
+            preprocessor,
+            sources,
+            programs: HashMap::new(),
+        }
+    }
+
+    pub fn tess_control(mut self, path: &str) -> Self {
+        self.sources = self.sources.tess_control(path);
+        self
+    }
+
+    pub fn tess_evaluation(mut self, path: &str) -> Self {
+        self.sources = self.sources.tess_evaluation(path);
+        self
+    }
+
+    pub fn geometry(mut self, path: &str) -> Self {
+        self.sources = self.sources.geometry(path);
+        self
+    }
+
+    /// The program with `features` defined, built on first use.
+    /// The order of the features doesn't matter.
+    pub fn get(&mut self, features: &[&str]) -> Result<&Program, ShaderError> {
+        let mut key: Vec<String> = features.iter().map(|&feature| feature.to_owned()).collect();
+        key.sort();
+        key.dedup();
+
+        match self.programs.entry(key) {
+            Entry::Occupied(entry) => Ok(entry.into_mut()),
+            Entry::Vacant(entry) => {
+                let features: Vec<&str> = entry.key().iter().map(String::as_str).collect();
+                let program = self
+                    .sources
+                    .build(&self.preprocessor, &features, &mut Vec::new())?;
+
+                Ok(entry.insert(program))
+            }
+        }
+    }
+
+    /// The number of variants built so far.
+    pub fn len(&self) -> usize {
+        self.programs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.programs.is_empty()
+    }
+}
@@ -0,0 +1,130 @@
+use std::time::{Duration, Instant, SystemTime};
+
+use super::{Preprocessor, Program, ProgramSources, ShaderError, ShaderFiles};
+
+/// A program rebuilt whenever one of the files it was preprocessed from changes,
+/// including the included ones. Requires the `hot-reload` feature.
+///
+/// Changes are found by polling the modification times of the files
+/// (see [`ShaderFiles::modified`]), so [`Self::poll`] has to be called regularly,
+/// for example once per frame.
+///
+/// ```ignore
+/// let preprocessor = Preprocessor::new(ShaderDirectory::new("shaders"));
+/// let mut watcher = ShaderWatcher::new(preprocessor, ProgramSources::new("mesh.vert", "mesh.frag"), &[])?;
+/// loop {
+///     if let Some(Err(err)) = watcher.poll() {
+///         eprintln!("{err}");
+///     }
+///     draw_layer.use_program(watcher.program());
+/// }
+/// ```
+pub struct ShaderWatcher<F: ShaderFiles> {
+    preprocessor: Preprocessor<F>,
+    sources: ProgramSources,
+    features: Vec<String>,
+    program: Program,
+    /// Every file read while building the program, with its modification time at that point.
+    files: Vec<(String, Option<SystemTime>)>,
+    poll_interval: Duration,
+    last_poll: Option<Instant>,
+}
+
+impl<F: ShaderFiles> ShaderWatcher<F> {
+    /// Build the program once, failing like [`super::ProgramVariants::get`] would.
+    pub fn new(
+        preprocessor: Preprocessor<F>,
+        sources: ProgramSources,
+        features: &[&str],
+    ) -> Result<Self, ShaderError> {
+        let mut files = Vec::new();
+        let program = sources.build(&preprocessor, features, &mut files)?;
+
+        let mut watcher = Self {
+            preprocessor,
+            sources,
+            features: features.iter().map(|&feature| feature.to_owned()).collect(),
+            program,
+            files: Vec::new(),
+            poll_interval: Duration::from_millis(250),
+            last_poll: None,
+        };
+        watcher.track(files);
+
+        Ok(watcher)
+    }
+
+    /// The latest program which linked successfully.
+    ///
+    /// Uniform locations and block bindings have to be set up again after a reload.
+    pub fn program(&self) -> &Program {
+        &self.program
+    }
+
+    /// The files the program is built from.
+    pub fn files(&self) -> impl Iterator<Item = &str> {
+        self.files.iter().map(|(file, _)| file.as_str())
+    }
+
+    /// Check the files at most once per `interval`, 250 milliseconds by default.
+    pub fn set_poll_interval(&mut self, interval: Duration) {
+        self.poll_interval = interval;
+    }
+
+    /// Rebuild the program if a file changed since the last build.
+    ///
+    /// Returns [`None`] if nothing changed. When the new sources fail to compile
+    /// or link the previous program stays in use and the error is returned,
+    /// the files aren't rebuilt again until they change another time.
+    pub fn poll(&mut self) -> Option<Result<(), ShaderError>> {
+        if self
+            .last_poll
+            .is_some_and(|last_poll| last_poll.elapsed() < self.poll_interval)
+        {
+            return None;
+        }
+        self.last_poll = Some(Instant::now());
+
+        let changed = self
+            .files
+            .iter()
+            .any(|(file, modified)| self.preprocessor.files().modified(file) != *modified);
+        if !changed {
+            return None;
+        }
+
+        Some(self.reload())
+    }
+
+    /// Rebuild the program now, whether files changed or not.
+    pub fn reload(&mut self) -> Result<(), ShaderError> {
+        let features: Vec<&str> = self.features.iter().map(String::as_str).collect();
+        let mut files = Vec::new();
+        let result = self
+            .sources
+            .build(&self.preprocessor, &features, &mut files);
+
+        // A failed build may stop before reading every file, keep watching the old ones
+        if result.is_err() {
+            for (file, _) in &self.files {
+                if !files.contains(file) {
+                    files.push(file.clone());
+                }
+            }
+        }
+        self.track(files);
+
+        self.program = result?;
+        Ok(())
+    }
+
+    fn track(&mut self, files: Vec<String>) {
+        self.files = files
+            .into_iter()
+            .map(|file| {
+                let modified = self.preprocessor.files().modified(&file);
+                (file, modified)
+            })
+            .collect();
+    }
+}
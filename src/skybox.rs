@@ -0,0 +1,97 @@
+use nalgebra_glm as glm;
+
+use crate::{
+    ActiveTexture, CubeMap, DrawLayer, DrawMode, Program, Shader, ShaderError, Uniform, Vao,
+};
+
+const VERTEX: &str = r#"#version 330 core
+uniform mat4 inverse_view_projection;
+out vec3 direction;
+
+void main() {
+    vec2 positions[3] = vec2[](vec2(-1.0, -1.0), vec2(3.0, -1.0), vec2(-1.0, 3.0));
+    // z = w puts every fragment on the far plane
+    gl_Position = vec4(positions[gl_VertexID], 1.0, 1.0);
+    vec4 far = inverse_view_projection * gl_Position;
+    direction = far.xyz / far.w;
+}
+"#;
+
+const FRAGMENT: &str = r#"#version 330 core
+uniform samplerCube environment;
+in vec3 direction;
+out vec4 color;
+
+void main() {
+    color = texture(environment, direction);
+}
+"#;
+
+/// Draws a [`CubeMap`] around the camera, behind everything else in the scene.
+///
+/// The sky is a single triangle covering the screen at the far plane, so it only shows where
+/// the depth buffer is still cleared. Draw it after the opaque geometry to skip the hidden
+/// fragments, or first with depth testing disabled.
+///
+/// ```ignore
+/// let skybox = Skybox::new()?;
+/// draw_layer.enable_depth_testing();
+/// draw_layer.enable_seamless_cube_maps();
+/// // draw the scene
+/// skybox.draw(&draw_layer, &sky, &view, &projection);
+/// ```
+pub struct Skybox {
+    program: Program,
+    vao: Vao,
+    unit: ActiveTexture,
+    environment: Option<Uniform<ActiveTexture>>,
+    inverse_view_projection: Option<Uniform<glm::Mat4>>,
+}
+
+impl Skybox {
+    pub fn new() -> Result<Self, ShaderError> {
+        let program = Program::new(Shader::compile(VERTEX)?, Shader::compile(FRAGMENT)?)?;
+
+        Ok(Self {
+            environment: program.uniform("environment").ok(),
+            inverse_view_projection: program.uniform("inverse_view_projection").ok(),
+            program,
+            vao: Vao::new(),
+            unit: ActiveTexture::new(0),
+        })
+    }
+
+    /// Draw `cube_map` as seen through `view` and `projection`. Only the rotation of `view`
+    /// is used, the sky stays infinitely far away however the camera moves.
+    ///
+    /// The cube map is bound to texture unit 0 and the skybox's program stays in use.
+    /// The depth function is relaxed to `GL_LEQUAL` for the draw and restored afterwards.
+    pub fn draw(
+        &self,
+        draw_layer: &DrawLayer,
+        cube_map: &CubeMap,
+        view: &glm::Mat4,
+        projection: &glm::Mat4,
+    ) {
+        let rotation = glm::mat3_to_mat4(&glm::mat4_to_mat3(view));
+        let inverse_view_projection = glm::inverse(&(projection * rotation));
+
+        draw_layer.use_program(&self.program);
+        self.unit.bind_cube_map(cube_map);
+        if let Some(environment) = &self.environment {
+            draw_layer.set(environment, &self.unit);
+        }
+        if let Some(uniform) = &self.inverse_view_projection {
+            draw_layer.set(uniform, &inverse_view_projection);
+        }
+
+        let mut depth_function = gl::LESS as i32;
+        unsafe {
+            gl::GetIntegerv(gl::DEPTH_FUNC, &mut depth_function);
+            // The far plane only passes a cleared depth buffer with GL_LEQUAL
+            gl::DepthFunc(gl::LEQUAL);
+        }
+        draw_layer.draw_arrays(&self.vao, DrawMode::Triangles, 0, 3);
+        unsafe { gl::DepthFunc(depth_function as u32) }
+    }
+}
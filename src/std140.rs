@@ -0,0 +1,119 @@
+use nalgebra_glm as glm;
+
+/// Types that can be written to a uniform block declared with the `std140` layout.
+///
+/// `#[derive(Std140)]` implements it for structs whose fields all implement it,
+/// inserting the padding the GLSL rules require between them.
+pub trait Std140 {
+    /// The base alignment in bytes.
+    const ALIGN: usize;
+    /// The size in bytes, not counting padding after the value.
+    const SIZE: usize;
+
+    /// Write the value to the start of `out`, which is at least [`Self::SIZE`] bytes long.
+    fn write_std140(&self, out: &mut [u8]);
+
+    /// The value laid out as a whole uniform block.
+    fn to_std140_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![0; Self::SIZE];
+        self.write_std140(&mut bytes);
+        bytes
+    }
+}
+
+/// Round `offset` up to a multiple of `alignment`.
+#[doc(hidden)]
+pub const fn align_to(offset: usize, alignment: usize) -> usize {
+    offset.next_multiple_of(alignment)
+}
+
+/// Arrays and structs are aligned like a `vec4`.
+pub(crate) const fn round_to_vec4(alignment: usize) -> usize {
+    align_to(alignment, 16)
+}
+
+macro_rules! std140_scalars {
+    ($($tp:ty),*) => {
+        $(impl Std140 for $tp {
+            const ALIGN: usize = 4;
+            const SIZE: usize = 4;
+
+            fn write_std140(&self, out: &mut [u8]) {
+                out[..4].copy_from_slice(&self.to_ne_bytes());
+            }
+        })*
+    };
+}
+
+std140_scalars!(f32, i32, u32);
+
+impl Std140 for bool {
+    const ALIGN: usize = 4;
+    const SIZE: usize = 4;
+
+    fn write_std140(&self, out: &mut [u8]) {
+        (*self as u32).write_std140(out)
+    }
+}
+
+impl Std140 for f64 {
+    const ALIGN: usize = 8;
+    const SIZE: usize = 8;
+
+    fn write_std140(&self, out: &mut [u8]) {
+        out[..8].copy_from_slice(&self.to_ne_bytes());
+    }
+}
+
+/// `vec2` is aligned to twice its component, `vec3` and `vec4` to four times.
+const fn vector_align(component: usize, n: usize) -> usize {
+    assert!(
+        n >= 1 && n <= 4,
+        "Vectors and matrix columns have 1 to 4 components"
+    );
+    match n {
+        1 => component,
+        2 => component * 2,
+        _ => component * 4,
+    }
+}
+
+/// Vectors are single column matrices, matrices are stored as arrays of their column vectors.
+impl<T, const R: usize, const C: usize> Std140 for glm::TMat<T, R, C>
+where
+    T: Std140 + glm::Scalar,
+{
+    const ALIGN: usize = if C == 1 {
+        vector_align(T::SIZE, R)
+    } else {
+        round_to_vec4(vector_align(T::SIZE, R))
+    };
+    const SIZE: usize = if C == 1 { T::SIZE * R } else { Self::ALIGN * C };
+
+    fn write_std140(&self, out: &mut [u8]) {
+        if C == 1 {
+            for (index, component) in self.iter().enumerate() {
+                component.write_std140(&mut out[index * T::SIZE..]);
+            }
+            return;
+        }
+
+        for (index, column) in self.column_iter().enumerate() {
+            let column: glm::TVec<T, R> = column.into_owned();
+            column.write_std140(&mut out[index * Self::ALIGN..]);
+        }
+    }
+}
+
+/// Every element of an array is aligned like a `vec4`.
+impl<T: Std140, const N: usize> Std140 for [T; N] {
+    const ALIGN: usize = round_to_vec4(T::ALIGN);
+    const SIZE: usize = align_to(T::SIZE, Self::ALIGN) * N;
+
+    fn write_std140(&self, out: &mut [u8]) {
+        let stride = align_to(T::SIZE, Self::ALIGN);
+        for (index, element) in self.iter().enumerate() {
+            element.write_std140(&mut out[index * stride..]);
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use super::{full_mip_chain, Filter, SamplerDesc, Texture, TextureError, TextureFormat};
+
+/// A texture with immutable storage, created with `glTexStorage2D`. Requires OpenGL 4.2.
+///
+/// Unlike [`Texture::new`] nothing is computed unless asked for: the number of mip levels
+/// is chosen up front, precomputed levels are uploaded as they are and mipmaps are only
+/// generated by [`Self::generate_mipmaps`] or later by [`Texture::regenerate_mipmaps`].
+///
+/// ```ignore
+/// let texture = TextureBuilder::new(256, 256)
+///     .format(TextureFormat::Srgb8Alpha8)
+///     .full_mip_chain()
+///     .level(0, &pixels)
+///     .generate_mipmaps()
+///     .build()?;
+/// ```
+pub struct TextureBuilder<'a> {
+    width: i32,
+    height: i32,
+    format: TextureFormat,
+    levels: i32,
+    uploads: Vec<(i32, &'a [u8])>,
+    generate_mipmaps: bool,
+    sampler: Option<SamplerDesc>,
+}
+
+impl<'a> TextureBuilder<'a> {
+    /// An RGBA8 texture with a single level.
+    pub fn new(width: i32, height: i32) -> Self {
+        Self {
+            width,
+            height,
+            format: TextureFormat::Rgba8,
+            levels: 1,
+            uploads: Vec::new(),
+            generate_mipmaps: false,
+            sampler: None,
+        }
+    }
+
+    pub fn format(mut self, format: TextureFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Allocate `levels` mip levels, clamped to the levels down to 1x1.
+    pub fn mip_levels(mut self, levels: i32) -> Self {
+        self.levels = levels.clamp(1, full_mip_chain(self.width, self.height));
+        self
+    }
+
+    /// Allocate every mip level down to 1x1.
+    pub fn full_mip_chain(mut self) -> Self {
+        self.levels = full_mip_chain(self.width, self.height);
+        self
+    }
+
+    /// Upload tightly packed pixels to mip level `level`, which is
+    /// [`Texture::level_size`] large. Levels without data are left undefined.
+    pub fn level(mut self, level: i32, data: &'a [u8]) -> Self {
+        self.uploads.push((level, data));
+        self
+    }
+
+    /// Compute the levels after the first from level 0 once everything is uploaded.
+    pub fn generate_mipmaps(mut self) -> Self {
+        self.generate_mipmaps = true;
+        self
+    }
+
+    /// Sample the texture with `desc`. By default the texture is sampled linearly,
+    /// blending between mip levels if it has more than one,
+    /// and integer formats are sampled with the nearest texel.
+    pub fn sampler(mut self, desc: SamplerDesc) -> Self {
+        self.sampler = Some(desc);
+        self
+    }
+
+    /// Fails if an upload has the wrong length or goes to a level the texture doesn't have,
+    /// or if mipmaps are generated for a format which doesn't support it.
+    pub fn build(self) -> Result<Texture, TextureError> {
+        if self.generate_mipmaps && (self.format.is_integer() || self.format.is_depth()) {
+            return Err(TextureError::MipmapsUnsupported(self.format));
+        }
+
+        let texture = unsafe {
+            Texture::allocate_immutable(self.format, self.width, self.height, self.levels)
+        };
+        for &(level, data) in &self.uploads {
+            texture.upload_level(level, data)?;
+        }
+        if self.generate_mipmaps {
+            texture.regenerate_mipmaps()?;
+        }
+
+        let sampler = self.sampler.unwrap_or_else(|| {
+            let mipmap_filter = (self.levels > 1).then_some(self.default_filter());
+            SamplerDesc {
+                min_filter: self.default_filter(),
+                mag_filter: self.default_filter(),
+                mipmap_filter,
+                ..SamplerDesc::default()
+            }
+        });
+        texture.set_sampler(&sampler);
+
+        Ok(texture)
+    }
+
+    fn default_filter(&self) -> Filter {
+        if self.format.is_integer() {
+            Filter::Nearest
+        } else {
+            Filter::Linear
+        }
+    }
+}
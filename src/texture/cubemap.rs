@@ -0,0 +1,320 @@
+use std::{cell::Cell, ffi::c_void, ptr};
+
+use super::{
+    full_mip_chain, read_through_framebuffer, EquirectangularError, SamplerDesc, Texture,
+    TextureError, TextureFormat,
+};
+use crate::{ActiveTexture, Program, Shader, UniformResource, Vao};
+
+/// The faces of a [`CubeMap`] in the order OpenGL numbers them.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CubeFace {
+    PositiveX = gl::TEXTURE_CUBE_MAP_POSITIVE_X,
+    NegativeX = gl::TEXTURE_CUBE_MAP_NEGATIVE_X,
+    PositiveY = gl::TEXTURE_CUBE_MAP_POSITIVE_Y,
+    NegativeY = gl::TEXTURE_CUBE_MAP_NEGATIVE_Y,
+    PositiveZ = gl::TEXTURE_CUBE_MAP_POSITIVE_Z,
+    NegativeZ = gl::TEXTURE_CUBE_MAP_NEGATIVE_Z,
+}
+
+impl CubeFace {
+    pub const ALL: [Self; 6] = [
+        Self::PositiveX,
+        Self::NegativeX,
+        Self::PositiveY,
+        Self::NegativeY,
+        Self::PositiveZ,
+        Self::NegativeZ,
+    ];
+}
+
+const EQUIRECTANGULAR_VERTEX: &str = r#"#version 330 core
+void main() {
+    vec2 positions[3] = vec2[](vec2(-1.0, -1.0), vec2(3.0, -1.0), vec2(-1.0, 3.0));
+    gl_Position = vec4(positions[gl_VertexID], 0.0, 1.0);
+}
+"#;
+
+/// Inverts the face selection of the OpenGL specification for the texel being drawn,
+/// then looks the direction up in the panorama.
+const EQUIRECTANGULAR_FRAGMENT: &str = r#"#version 330 core
+uniform sampler2D panorama;
+uniform int face;
+uniform float size;
+out vec4 color;
+
+const float PI = 3.14159265358979;
+
+void main() {
+    vec2 st = gl_FragCoord.xy / size * 2.0 - 1.0;
+    vec3 directions[6] = vec3[](
+        vec3(1.0, -st.y, -st.x),
+        vec3(-1.0, -st.y, st.x),
+        vec3(st.x, 1.0, st.y),
+        vec3(st.x, -1.0, -st.y),
+        vec3(st.x, -st.y, 1.0),
+        vec3(-st.x, -st.y, -1.0)
+    );
+    vec3 direction = normalize(directions[face]);
+    vec2 uv = vec2(atan(direction.z, direction.x) / (2.0 * PI) + 0.5, asin(direction.y) / PI + 0.5);
+    color = textureLod(panorama, uv, 0.0);
+}
+"#;
+
+/// Six square textures sampled by direction with a `samplerCube`,
+/// used for skyboxes and environment lighting.
+pub struct CubeMap {
+    id: u32,
+    format: TextureFormat,
+    size: i32,
+    /// The number of mip levels with storage.
+    levels: Cell<i32>,
+}
+
+impl CubeMap {
+    /// Create a cube map from six `size` x `size` images, given in the order of [`CubeFace::ALL`],
+    /// with a single mip level, sampled linearly and clamped to the edges.
+    pub fn new(faces: [&[u8]; 6], size: i32, format: TextureFormat) -> Result<Self, TextureError> {
+        let expected = format.image_size(size, size);
+        if let Some(face) = faces.iter().find(|face| face.len() != expected) {
+            return Err(TextureError::DataLength {
+                expected,
+                actual: face.len(),
+            });
+        }
+
+        let cube_map = unsafe { Self::allocate(size, format) };
+        for (face, data) in CubeFace::ALL.into_iter().zip(faces) {
+            unsafe { cube_map.upload_face(face, data.as_ptr() as *const c_void) };
+        }
+
+        Ok(cube_map)
+    }
+
+    /// A cube map without any data, e.g. to render an environment into.
+    pub fn empty(size: i32, format: TextureFormat) -> Self {
+        let cube_map = unsafe { Self::allocate(size, format) };
+        for face in CubeFace::ALL {
+            unsafe { cube_map.upload_face(face, ptr::null()) };
+        }
+
+        cube_map
+    }
+
+    /// Project an equirectangular panorama onto the faces of a new cube map, on the GPU.
+    /// The panorama's columns go once around the horizon starting behind -X,
+    /// its rows from straight down to straight up.
+    ///
+    /// Leaves the program, vertex array and the texture of unit 0 changed. Blending and the
+    /// scissor test are disabled and every color channel is written for the projection,
+    /// then they are restored along with the framebuffer and viewport.
+    ///
+    /// Fails with [`TextureError::NotRenderable`] for integer and depth formats.
+    pub fn from_equirectangular(
+        panorama: &Texture,
+        size: i32,
+        format: TextureFormat,
+    ) -> Result<Self, EquirectangularError> {
+        if format.is_integer() || format.is_depth() {
+            return Err(TextureError::NotRenderable(format).into());
+        }
+
+        let program = Program::new(
+            Shader::compile(EQUIRECTANGULAR_VERTEX)?,
+            Shader::compile(EQUIRECTANGULAR_FRAGMENT)?,
+        )?;
+        let sampler = program.uniform::<ActiveTexture>("panorama").ok();
+        let face_index = program.uniform::<i32>("face").ok();
+        let face_size = program.uniform::<f32>("size").ok();
+
+        let cube_map = Self::empty(size, format);
+        let unit = ActiveTexture::new(0);
+        unit.bind_texture(panorama);
+        let vao = Vao::new();
+
+        unsafe {
+            let mut viewport = [0; 4];
+            gl::GetIntegerv(gl::VIEWPORT, viewport.as_mut_ptr());
+            let mut previous = 0;
+            gl::GetIntegerv(gl::DRAW_FRAMEBUFFER_BINDING, ptr::addr_of_mut!(previous));
+            let mut framebuffer = 0;
+            gl::GenFramebuffers(1, ptr::addr_of_mut!(framebuffer));
+            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, framebuffer);
+            gl::Viewport(0, 0, size, size);
+            let blend = gl::IsEnabled(gl::BLEND);
+            let scissor = gl::IsEnabled(gl::SCISSOR_TEST);
+            let mut color_mask = [gl::TRUE; 4];
+            gl::GetBooleanv(gl::COLOR_WRITEMASK, color_mask.as_mut_ptr());
+            gl::Disable(gl::BLEND);
+            gl::Disable(gl::SCISSOR_TEST);
+            gl::ColorMask(gl::TRUE, gl::TRUE, gl::TRUE, gl::TRUE);
+
+            program.use_internal();
+            vao.bind();
+            if let Some(sampler) = sampler {
+                unit.uniform(sampler.location());
+            }
+            if let Some(face_size) = face_size {
+                (size as f32).uniform(face_size.location());
+            }
+            for (index, face) in CubeFace::ALL.into_iter().enumerate() {
+                gl::FramebufferTexture2D(
+                    gl::DRAW_FRAMEBUFFER,
+                    gl::COLOR_ATTACHMENT0,
+                    face as u32,
+                    cube_map.id,
+                    0,
+                );
+                if let Some(face_index) = face_index {
+                    (index as i32).uniform(face_index.location());
+                }
+                gl::DrawArrays(gl::TRIANGLES, 0, 3);
+            }
+
+            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, previous as u32);
+            gl::DeleteFramebuffers(1, ptr::addr_of!(framebuffer));
+            let [x, y, width, height] = viewport;
+            gl::Viewport(x, y, width, height);
+            if blend == gl::TRUE {
+                gl::Enable(gl::BLEND);
+            }
+            if scissor == gl::TRUE {
+                gl::Enable(gl::SCISSOR_TEST);
+            }
+            let [red, green, blue, alpha] = color_mask;
+            gl::ColorMask(red, green, blue, alpha);
+        }
+
+        Ok(cube_map)
+    }
+
+    /// Leaves the cube map bound to `GL_TEXTURE_CUBE_MAP`.
+    unsafe fn allocate(size: i32, format: TextureFormat) -> Self {
+        let mut id = 0_u32;
+        gl::GenTextures(1, ptr::addr_of_mut!(id));
+        gl::BindTexture(gl::TEXTURE_CUBE_MAP, id);
+
+        let cube_map = Self {
+            id,
+            format,
+            size,
+            levels: Cell::new(1),
+        };
+        let filter = if format.is_integer() {
+            super::Filter::Nearest
+        } else {
+            super::Filter::Linear
+        };
+        SamplerDesc {
+            min_filter: filter,
+            mag_filter: filter,
+            mipmap_filter: None,
+            ..SamplerDesc::default().wrap(super::Wrap::ClampToEdge)
+        }
+        .apply_to_texture(gl::TEXTURE_CUBE_MAP);
+
+        cube_map
+    }
+
+    /// Expects the cube map to be bound.
+    unsafe fn upload_face(&self, face: CubeFace, data: *const c_void) {
+        gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+        gl::TexImage2D(
+            face as u32,
+            0,
+            self.format as i32,
+            self.size,
+            self.size,
+            0,
+            self.format.pixel_format(),
+            self.format.pixel_type(),
+            data,
+        );
+    }
+
+    /// Change how the cube map is sampled, unless a [`super::Sampler`] is bound to its unit.
+    /// Only the edges of the faces are ever sampled outside of `[0, 1]`, so
+    /// [`super::Wrap::ClampToEdge`] is the only sensible wrap mode.
+    pub fn set_sampler(&self, desc: &SamplerDesc) {
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_CUBE_MAP, self.id);
+            desc.apply_to_texture(gl::TEXTURE_CUBE_MAP);
+        }
+    }
+
+    /// Compute every mip level of every face from the base levels,
+    /// see [`Texture::regenerate_mipmaps`].
+    pub fn regenerate_mipmaps(&self) -> Result<(), TextureError> {
+        if self.format.is_integer() || self.format.is_depth() {
+            return Err(TextureError::MipmapsUnsupported(self.format));
+        }
+
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_CUBE_MAP, self.id);
+            gl::GenerateMipmap(gl::TEXTURE_CUBE_MAP);
+        }
+        self.levels.set(full_mip_chain(self.size, self.size));
+
+        Ok(())
+    }
+
+    /// Read mip level `level` of `face` back, like [`Texture::read`] and with the same
+    /// fallback where `glGetTexImage` isn't available.
+    pub fn read_face(&self, face: CubeFace, level: i32) -> Result<Vec<u8>, TextureError> {
+        let levels = self.levels.get();
+        if !(0..levels).contains(&level) {
+            return Err(TextureError::LevelOutOfRange { level, levels });
+        }
+
+        let size = (self.size >> level).max(1);
+        let mut data = vec![0_u8; self.format.image_size(size, size)];
+        unsafe {
+            gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+            if gl::GetTexImage::is_loaded() {
+                gl::BindTexture(gl::TEXTURE_CUBE_MAP, self.id);
+                gl::GetTexImage(
+                    face as u32,
+                    level,
+                    self.format.pixel_format(),
+                    self.format.pixel_type(),
+                    data.as_mut_ptr() as *mut c_void,
+                );
+            } else {
+                read_through_framebuffer(
+                    face as u32,
+                    self.id,
+                    level,
+                    self.format,
+                    (size, size),
+                    &mut data,
+                )?;
+            }
+        }
+
+        Ok(data)
+    }
+
+    pub fn format(&self) -> TextureFormat {
+        self.format
+    }
+
+    /// The width and height of every face.
+    pub fn size(&self) -> i32 {
+        self.size
+    }
+
+    pub fn levels(&self) -> i32 {
+        self.levels.get()
+    }
+
+    pub(crate) fn get_inner(&self) -> u32 {
+        self.id
+    }
+}
+
+impl Drop for CubeMap {
+    fn drop(&mut self) {
+        unsafe { gl::DeleteTextures(1, ptr::addr_of!(self.id)) }
+    }
+}
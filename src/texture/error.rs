@@ -0,0 +1,111 @@
+use std::{error, fmt};
+
+use super::TextureFormat;
+use crate::{FramebufferError, ShaderError};
+
+/// The reason a [`crate::Texture`] can't be created or updated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextureError {
+    /// The data doesn't hold exactly one tightly packed image of the given size and format.
+    DataLength { expected: usize, actual: usize },
+    /// The region `[x, y, width, height]` doesn't fit into its mip level.
+    RegionOutOfBounds {
+        region: [i32; 4],
+        level_size: (i32, i32),
+    },
+    /// Rows of `row` bytes don't fit into a stride of `stride` bytes.
+    RowStride { stride: usize, row: usize },
+    /// `height` rows `stride` bytes apart span more bytes than a `usize` can count.
+    StrideOverflow { stride: usize, height: i32 },
+    /// The texture has only `levels` mip levels.
+    LevelOutOfRange { level: i32, levels: i32 },
+    /// `glGenerateMipmap` only works for formats which are filterable and can be rendered to.
+    MipmapsUnsupported(TextureFormat),
+    /// Integer and depth formats can't be drawn into by a color shader.
+    NotRenderable(TextureFormat),
+    /// The format can't be used for image load and store, see [`TextureFormat::is_image_format`].
+    UnsupportedImageFormat(TextureFormat),
+    /// The driver can't read textures of this format back, see [`crate::Texture::read`].
+    Unreadable(TextureFormat),
+    /// The texture can't be attached to a framebuffer to be read back.
+    Framebuffer(FramebufferError),
+}
+
+impl fmt::Display for TextureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DataLength { expected, actual } => {
+                write!(f, "Expected {expected} bytes of pixel data, got {actual}")
+            }
+            Self::RegionOutOfBounds {
+                region: [x, y, width, height],
+                level_size: (level_width, level_height),
+            } => {
+                write!(f, "The {width}x{height} region at {x}, {y} doesn't fit ")?;
+                write!(f, "into a {level_width}x{level_height} mip level")
+            }
+            Self::RowStride { stride, row } => {
+                write!(
+                    f,
+                    "Rows of {row} bytes don't fit into a stride of {stride} bytes"
+                )
+            }
+            Self::StrideOverflow { stride, height } => {
+                write!(
+                    f,
+                    "{height} rows with a stride of {stride} bytes don't fit into memory"
+                )
+            }
+            Self::LevelOutOfRange { level, levels } => {
+                write!(
+                    f,
+                    "Mip level {level} is out of range, the texture has {levels}"
+                )
+            }
+            Self::MipmapsUnsupported(format) => {
+                write!(f, "Cannot generate mipmaps for {format:?} textures")
+            }
+            Self::NotRenderable(format) => write!(f, "Cannot render into {format:?} textures"),
+            Self::UnsupportedImageFormat(format) => {
+                write!(f, "{format:?} textures cannot be bound to an image unit")
+            }
+            Self::Unreadable(format) => {
+                write!(f, "The driver cannot read {format:?} textures back")
+            }
+            Self::Framebuffer(err) => write!(f, "Cannot read the texture: {err}"),
+        }
+    }
+}
+
+impl error::Error for TextureError {}
+
+/// The reason [`crate::CubeMap::from_equirectangular`] failed.
+#[derive(Debug)]
+pub enum EquirectangularError {
+    Texture(TextureError),
+    /// The projection shaders didn't compile or link.
+    Shader(ShaderError),
+}
+
+impl fmt::Display for EquirectangularError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Texture(err) => write!(f, "Cannot project the panorama: {err}"),
+            Self::Shader(err) => write!(f, "Cannot build the projection program: {err}"),
+        }
+    }
+}
+
+impl error::Error for EquirectangularError {}
+
+impl From<TextureError> for EquirectangularError {
+    fn from(value: TextureError) -> Self {
+        Self::Texture(value)
+    }
+}
+
+impl From<ShaderError> for EquirectangularError {
+    fn from(value: ShaderError) -> Self {
+        Self::Shader(value)
+    }
+}
@@ -0,0 +1,92 @@
+/// Storage formats a [`crate::Texture`] can be created with.
+///
+/// Pixel data is uploaded in the matching client format: bytes for the 8 bit formats,
+/// `f16` for the half float ones, `f32` for `Rgba32F` and `Depth32F`, `u32` for `R32Ui`
+/// and `u32`s with 24 bits of depth above 8 bits of stencil for `Depth24Stencil8`.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextureFormat {
+    R8 = gl::R8,
+    Rg8 = gl::RG8,
+    Rgb8 = gl::RGB8,
+    Rgba8 = gl::RGBA8,
+    /// Sampled colors are converted from sRGB to linear.
+    Srgb8Alpha8 = gl::SRGB8_ALPHA8,
+    R16F = gl::R16F,
+    Rgba16F = gl::RGBA16F,
+    Rgba32F = gl::RGBA32F,
+    /// Sampled with a `usampler`, can't be filtered.
+    R32Ui = gl::R32UI,
+    Depth24Stencil8 = gl::DEPTH24_STENCIL8,
+    Depth32F = gl::DEPTH_COMPONENT32F,
+}
+
+impl TextureFormat {
+    /// The size of a single pixel in the uploaded data.
+    pub fn bytes_per_pixel(self) -> usize {
+        match self {
+            Self::R8 => 1,
+            Self::Rg8 | Self::R16F => 2,
+            Self::Rgb8 => 3,
+            Self::Rgba8 | Self::Srgb8Alpha8 | Self::R32Ui => 4,
+            Self::Depth24Stencil8 | Self::Depth32F => 4,
+            Self::Rgba16F => 8,
+            Self::Rgba32F => 16,
+        }
+    }
+
+    /// Integer formats are only sampled with `GL_NEAREST` filtering.
+    pub fn is_integer(self) -> bool {
+        self == Self::R32Ui
+    }
+
+    pub fn is_depth(self) -> bool {
+        matches!(self, Self::Depth24Stencil8 | Self::Depth32F)
+    }
+
+    /// Whether textures of this format can be bound to an image unit, which
+    /// excludes three component, sRGB and depth formats.
+    pub fn is_image_format(self) -> bool {
+        matches!(
+            self,
+            Self::R8
+                | Self::Rg8
+                | Self::Rgba8
+                | Self::R16F
+                | Self::Rgba16F
+                | Self::Rgba32F
+                | Self::R32Ui
+        )
+    }
+
+    /// The `format` argument of `glTexImage2D`.
+    pub(crate) fn pixel_format(self) -> u32 {
+        match self {
+            Self::R8 | Self::R16F => gl::RED,
+            Self::Rg8 => gl::RG,
+            Self::Rgb8 => gl::RGB,
+            Self::Rgba8 | Self::Srgb8Alpha8 | Self::Rgba16F | Self::Rgba32F => gl::RGBA,
+            Self::R32Ui => gl::RED_INTEGER,
+            Self::Depth24Stencil8 => gl::DEPTH_STENCIL,
+            Self::Depth32F => gl::DEPTH_COMPONENT,
+        }
+    }
+
+    /// The `type` argument of `glTexImage2D`.
+    pub(crate) fn pixel_type(self) -> u32 {
+        match self {
+            Self::R8 | Self::Rg8 | Self::Rgb8 | Self::Rgba8 | Self::Srgb8Alpha8 => {
+                gl::UNSIGNED_BYTE
+            }
+            Self::R16F | Self::Rgba16F => gl::HALF_FLOAT,
+            Self::Rgba32F | Self::Depth32F => gl::FLOAT,
+            Self::R32Ui => gl::UNSIGNED_INT,
+            Self::Depth24Stencil8 => gl::UNSIGNED_INT_24_8,
+        }
+    }
+
+    /// The length of a tightly packed `width` x `height` image.
+    pub(crate) fn image_size(self, width: i32, height: i32) -> usize {
+        width.max(0) as usize * height.max(0) as usize * self.bytes_per_pixel()
+    }
+}
@@ -0,0 +1,9 @@
+mod builder;
+mod cubemap;
+mod error;
+mod format;
+mod sampler;
+#[allow(clippy::module_inception)]
+mod texture;
+
+pub use {builder::*, cubemap::*, error::*, format::*, sampler::*, texture::*};
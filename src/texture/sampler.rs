@@ -0,0 +1,252 @@
+use std::{cell::Cell, ptr};
+
+use crate::{
+    capabilities::{gl_version, has_extension},
+    Color,
+};
+
+/// `GL_TEXTURE_MAX_ANISOTROPY`, core in OpenGL 4.6 and the same value as the extension's.
+const TEXTURE_MAX_ANISOTROPY: u32 = 0x84fe;
+/// `GL_MAX_TEXTURE_MAX_ANISOTROPY`
+const MAX_TEXTURE_MAX_ANISOTROPY: u32 = 0x84ff;
+
+thread_local! {
+    /// The largest anisotropy of the context current on this thread,
+    /// 0 without anisotropic filtering and [`None`] until the driver has been asked.
+    static MAX_ANISOTROPY: Cell<Option<f32>> = const { Cell::new(None) };
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Filter {
+    Nearest = gl::NEAREST,
+    Linear = gl::LINEAR,
+}
+
+/// What happens to texture coordinates outside of `[0, 1]`.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Wrap {
+    Repeat = gl::REPEAT,
+    MirroredRepeat = gl::MIRRORED_REPEAT,
+    ClampToEdge = gl::CLAMP_TO_EDGE,
+    /// Coordinates outside of the texture read [`SamplerDesc::border_color`].
+    ClampToBorder = gl::CLAMP_TO_BORDER,
+    /// Requires OpenGL 4.4.
+    MirrorClampToEdge = gl::MIRROR_CLAMP_TO_EDGE,
+}
+
+/// How a reference value is compared against a stored one.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompareFunction {
+    Never = gl::NEVER,
+    Less = gl::LESS,
+    Equal = gl::EQUAL,
+    LessEqual = gl::LEQUAL,
+    Greater = gl::GREATER,
+    NotEqual = gl::NOTEQUAL,
+    GreaterEqual = gl::GEQUAL,
+    Always = gl::ALWAYS,
+}
+
+/// How a texture is sampled, applied with [`crate::Texture::set_sampler`]
+/// or through a [`Sampler`] object.
+///
+/// The default is OpenGL's initial sampling state. The texture constructors of this crate
+/// don't keep it but pick filters fitting the texture, e.g. linear filtering without
+/// mipmaps for [`crate::Texture::with_format`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SamplerDesc {
+    pub min_filter: Filter,
+    pub mag_filter: Filter,
+    /// How mip levels are blended, [`None`] only samples the base level.
+    pub mipmap_filter: Option<Filter>,
+    pub wrap_s: Wrap,
+    pub wrap_t: Wrap,
+    pub wrap_r: Wrap,
+    pub border_color: Color,
+    pub lod_bias: f32,
+    pub min_lod: f32,
+    pub max_lod: f32,
+    /// Values above 1 enable anisotropic filtering, clamped to the driver's maximum.
+    /// Ignored unless the driver has OpenGL 4.6 or `GL_EXT_texture_filter_anisotropic`.
+    pub max_anisotropy: f32,
+    /// Sampling a depth texture with a `sampler*Shadow` compares the texture coordinate
+    /// against the stored depth with this function.
+    pub compare: Option<CompareFunction>,
+}
+
+impl SamplerDesc {
+    /// Blocky magnification without mipmaps, for pixel art.
+    pub fn nearest() -> Self {
+        Self {
+            min_filter: Filter::Nearest,
+            mag_filter: Filter::Nearest,
+            mipmap_filter: None,
+            ..Self::default()
+        }
+    }
+
+    /// Linear filtering without mipmaps.
+    pub fn linear() -> Self {
+        Self {
+            min_filter: Filter::Linear,
+            mag_filter: Filter::Linear,
+            mipmap_filter: None,
+            ..Self::default()
+        }
+    }
+
+    /// Set every wrap mode to `wrap`.
+    pub fn wrap(self, wrap: Wrap) -> Self {
+        Self {
+            wrap_s: wrap,
+            wrap_t: wrap,
+            wrap_r: wrap,
+            ..self
+        }
+    }
+
+    fn min_filter_enum(&self) -> u32 {
+        match (self.min_filter, self.mipmap_filter) {
+            (filter, None) => filter as u32,
+            (Filter::Nearest, Some(Filter::Nearest)) => gl::NEAREST_MIPMAP_NEAREST,
+            (Filter::Nearest, Some(Filter::Linear)) => gl::NEAREST_MIPMAP_LINEAR,
+            (Filter::Linear, Some(Filter::Nearest)) => gl::LINEAR_MIPMAP_NEAREST,
+            (Filter::Linear, Some(Filter::Linear)) => gl::LINEAR_MIPMAP_LINEAR,
+        }
+    }
+
+    /// Set every parameter of the texture bound to `target`.
+    pub(crate) unsafe fn apply_to_texture(&self, target: u32) {
+        self.apply(
+            |name, value| gl::TexParameteri(target, name, value),
+            |name, value| gl::TexParameterf(target, name, value),
+            |name, value| gl::TexParameterfv(target, name, value),
+        )
+    }
+
+    unsafe fn apply(
+        &self,
+        int: impl Fn(u32, i32),
+        float: impl Fn(u32, f32),
+        floats: impl Fn(u32, *const f32),
+    ) {
+        int(gl::TEXTURE_MIN_FILTER, self.min_filter_enum() as i32);
+        int(gl::TEXTURE_MAG_FILTER, self.mag_filter as i32);
+        int(gl::TEXTURE_WRAP_S, self.wrap_s as i32);
+        int(gl::TEXTURE_WRAP_T, self.wrap_t as i32);
+        int(gl::TEXTURE_WRAP_R, self.wrap_r as i32);
+
+        let border = self.border_color;
+        let border = [border.r, border.g, border.b, border.a];
+        floats(gl::TEXTURE_BORDER_COLOR, border.as_ptr());
+
+        float(gl::TEXTURE_LOD_BIAS, self.lod_bias);
+        float(gl::TEXTURE_MIN_LOD, self.min_lod);
+        float(gl::TEXTURE_MAX_LOD, self.max_lod);
+
+        match self.compare {
+            Some(function) => {
+                int(gl::TEXTURE_COMPARE_MODE, gl::COMPARE_REF_TO_TEXTURE as i32);
+                int(gl::TEXTURE_COMPARE_FUNC, function as i32);
+            }
+            None => int(gl::TEXTURE_COMPARE_MODE, gl::NONE as i32),
+        }
+
+        if let Some(maximum) = max_anisotropy() {
+            float(
+                TEXTURE_MAX_ANISOTROPY,
+                self.max_anisotropy.min(maximum).max(1.0),
+            );
+        }
+    }
+}
+
+impl Default for SamplerDesc {
+    fn default() -> Self {
+        Self {
+            min_filter: Filter::Nearest,
+            mag_filter: Filter::Linear,
+            mipmap_filter: Some(Filter::Linear),
+            wrap_s: Wrap::Repeat,
+            wrap_t: Wrap::Repeat,
+            wrap_r: Wrap::Repeat,
+            border_color: Color::new(0.0, 0.0, 0.0, 0.0),
+            lod_bias: 0.0,
+            min_lod: -1000.0,
+            max_lod: 1000.0,
+            max_anisotropy: 1.0,
+            compare: None,
+        }
+    }
+}
+
+/// The largest anisotropy the driver supports, [`None`] without anisotropic filtering.
+/// Only asked once per [`crate::DrawLayer`], as looking for the extensions takes a call per extension.
+fn max_anisotropy() -> Option<f32> {
+    let maximum = MAX_ANISOTROPY.get().unwrap_or_else(|| {
+        let maximum = query_max_anisotropy();
+        MAX_ANISOTROPY.set(Some(maximum));
+        maximum
+    });
+
+    (maximum >= 1.0).then_some(maximum)
+}
+
+fn query_max_anisotropy() -> f32 {
+    let supported = gl_version() >= (4, 6)
+        || has_extension(c"GL_EXT_texture_filter_anisotropic")
+        || has_extension(c"GL_ARB_texture_filter_anisotropic");
+    if !supported {
+        return 0.0;
+    }
+
+    let mut maximum = 0.0;
+    unsafe { gl::GetFloatv(MAX_TEXTURE_MAX_ANISOTROPY, ptr::addr_of_mut!(maximum)) };
+    maximum
+}
+
+/// Forget what the previous context supported, called whenever the functions are loaded.
+pub(crate) fn reset_capabilities() {
+    MAX_ANISOTROPY.set(None);
+}
+
+/// Sampling state kept apart from any texture. While bound to a texture unit with
+/// [`crate::ActiveTexture::bind_sampler`] it replaces the state of the texture bound there.
+/// Requires OpenGL 3.3.
+pub struct Sampler(u32);
+
+impl Sampler {
+    pub fn new(desc: &SamplerDesc) -> Self {
+        let mut id = 0;
+        unsafe { gl::GenSamplers(1, ptr::addr_of_mut!(id)) };
+
+        let sampler = Self(id);
+        sampler.set(desc);
+        sampler
+    }
+
+    /// Replace every parameter of the sampler.
+    pub fn set(&self, desc: &SamplerDesc) {
+        let id = self.0;
+        unsafe {
+            desc.apply(
+                |name, value| gl::SamplerParameteri(id, name, value),
+                |name, value| gl::SamplerParameterf(id, name, value),
+                |name, value| gl::SamplerParameterfv(id, name, value),
+            )
+        }
+    }
+
+    pub(crate) fn get_inner(&self) -> u32 {
+        self.0
+    }
+}
+
+impl Drop for Sampler {
+    fn drop(&mut self) {
+        unsafe { gl::DeleteSamplers(1, ptr::addr_of!(self.0)) }
+    }
+}
@@ -0,0 +1,520 @@
+use std::{cell::Cell, ffi::c_void, ptr};
+
+use super::{CubeMap, Sampler, SamplerDesc, TextureError, TextureFormat};
+use crate::FramebufferError;
+use crate::{GlslType, UniformResource};
+
+pub struct Texture {
+    id: u32,
+    format: TextureFormat,
+    width: i32,
+    height: i32,
+    /// The number of mip levels with storage.
+    levels: Cell<i32>,
+    /// Created with `glTexStorage2D`, the number of levels never changes.
+    immutable: bool,
+}
+
+impl Texture {
+    /// Create a new texture with the given bytes and generate its mipmaps.
+    /// Uses the RGBA8 format internally.
+    ///
+    /// # Panics
+    /// If `data` isn't exactly `width * height` RGBA pixels.
+    pub fn new(data: &[u8], width: i32, height: i32) -> Self {
+        let texture = Self::with_format(data, width, height, TextureFormat::Rgba8)
+            .unwrap_or_else(|err| panic!("Cannot create the texture: {err}"));
+        texture
+            .regenerate_mipmaps()
+            .expect("RGBA8 textures have mipmaps");
+        unsafe {
+            // The driver default, which samples the mipmaps.
+            gl::TexParameteri(
+                gl::TEXTURE_2D,
+                gl::TEXTURE_MIN_FILTER,
+                gl::NEAREST_MIPMAP_LINEAR as i32,
+            );
+        }
+
+        texture
+    }
+
+    /// Create a texture with a single mip level from tightly packed rows of pixels in `format`,
+    /// see [`TextureFormat`] for the layout of each pixel.
+    pub fn with_format(
+        data: &[u8],
+        width: i32,
+        height: i32,
+        format: TextureFormat,
+    ) -> Result<Self, TextureError> {
+        let expected = format.image_size(width, height);
+        if data.len() != expected {
+            return Err(TextureError::DataLength {
+                expected,
+                actual: data.len(),
+            });
+        }
+
+        Ok(unsafe { Self::allocate(format, width, height, data.as_ptr() as *const c_void) })
+    }
+
+    /// Create an RGBA8 texture without any data, used as a render target
+    /// or as an image written by shaders.
+    /// The texture has a single mip level and is sampled linearly.
+    pub fn empty(width: i32, height: i32) -> Self {
+        Self::empty_with_format(width, height, TextureFormat::Rgba8)
+    }
+
+    /// Like [`Self::empty`], e.g. `Rgba16F` for HDR render targets
+    /// or `Depth32F` for shadow maps.
+    pub fn empty_with_format(width: i32, height: i32, format: TextureFormat) -> Self {
+        unsafe { Self::allocate(format, width, height, ptr::null()) }
+    }
+
+    /// Leaves the texture bound to `GL_TEXTURE_2D`.
+    unsafe fn allocate(
+        format: TextureFormat,
+        width: i32,
+        height: i32,
+        data: *const c_void,
+    ) -> Self {
+        let mut id = 0_u32;
+        let filter = if format.is_integer() {
+            gl::NEAREST
+        } else {
+            gl::LINEAR
+        };
+
+        gl::GenTextures(1, ptr::addr_of_mut!(id));
+        gl::BindTexture(gl::TEXTURE_2D, id);
+        // Rows of RGB8 images with an odd width don't start at a multiple of 4.
+        gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+        gl::TexImage2D(
+            gl::TEXTURE_2D,
+            0,
+            format as i32,
+            width,
+            height,
+            0,
+            format.pixel_format(),
+            format.pixel_type(),
+            data,
+        );
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, filter as i32);
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, filter as i32);
+
+        Self {
+            id,
+            format,
+            width,
+            height,
+            levels: Cell::new(1),
+            immutable: false,
+        }
+    }
+
+    /// Allocate `levels` mip levels which can't be resized, leaves the texture bound.
+    /// Requires OpenGL 4.2.
+    pub(crate) unsafe fn allocate_immutable(
+        format: TextureFormat,
+        width: i32,
+        height: i32,
+        levels: i32,
+    ) -> Self {
+        let mut id = 0_u32;
+
+        gl::GenTextures(1, ptr::addr_of_mut!(id));
+        gl::BindTexture(gl::TEXTURE_2D, id);
+        gl::TexStorage2D(gl::TEXTURE_2D, levels, format as u32, width, height);
+
+        Self {
+            id,
+            format,
+            width,
+            height,
+            levels: Cell::new(levels),
+            immutable: true,
+        }
+    }
+
+    /// Replace all of mip level `level` with tightly packed pixels.
+    pub(crate) fn upload_level(&self, level: i32, data: &[u8]) -> Result<(), TextureError> {
+        self.check_level(level)?;
+        let (width, height) = self.level_size(level);
+        self.update_region(0, 0, width, height, level, data)
+    }
+
+    /// Replace the `width` x `height` pixels at `x`, `y` of mip level `level`
+    /// with tightly packed rows of pixels, e.g. a glyph in an atlas or a new video frame.
+    pub fn update_region(
+        &self,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        level: i32,
+        data: &[u8],
+    ) -> Result<(), TextureError> {
+        let expected = self.format.image_size(width, height);
+        if data.len() != expected {
+            return Err(TextureError::DataLength {
+                expected,
+                actual: data.len(),
+            });
+        }
+
+        let row = width.max(0) as usize * self.format.bytes_per_pixel();
+        self.update_region_with_stride(x, y, width, height, level, data, row)
+    }
+
+    /// Like [`Self::update_region`], but the rows of `data` start `stride` bytes apart,
+    /// e.g. to upload part of a larger image.
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_region_with_stride(
+        &self,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        level: i32,
+        data: &[u8],
+        stride: usize,
+    ) -> Result<(), TextureError> {
+        self.check_level(level)?;
+        let level_size = self.level_size(level);
+        let (level_width, level_height) = level_size;
+        if x < 0
+            || y < 0
+            || width < 0
+            || height < 0
+            || width > level_width - x
+            || height > level_height - y
+        {
+            return Err(TextureError::RegionOutOfBounds {
+                region: [x, y, width, height],
+                level_size,
+            });
+        }
+
+        let pixel_size = self.format.bytes_per_pixel();
+        let row = width as usize * pixel_size;
+        if stride < row {
+            return Err(TextureError::RowStride { stride, row });
+        }
+        let expected = match height {
+            0 => Some(0),
+            height => stride
+                .checked_mul(height as usize - 1)
+                .and_then(|rows| rows.checked_add(row)),
+        };
+        let expected = expected.ok_or(TextureError::StrideOverflow { stride, height })?;
+        if data.len() < expected {
+            return Err(TextureError::DataLength {
+                expected,
+                actual: data.len(),
+            });
+        }
+
+        // `GL_UNPACK_ROW_LENGTH` counts pixels, other strides are packed here.
+        let packed;
+        let (data, row_length) = if stride == row {
+            (data, 0)
+        } else if stride.is_multiple_of(pixel_size) {
+            (data, (stride / pixel_size) as i32)
+        } else {
+            packed = data
+                .chunks(stride)
+                .take(height as usize)
+                .flat_map(|line| &line[..row])
+                .copied()
+                .collect::<Vec<_>>();
+            (packed.as_slice(), 0)
+        };
+
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_2D, self.id);
+            // Rows of RGB8 images with an odd width don't start at a multiple of 4.
+            gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+            gl::PixelStorei(gl::UNPACK_ROW_LENGTH, row_length);
+            gl::TexSubImage2D(
+                gl::TEXTURE_2D,
+                level,
+                x,
+                y,
+                width,
+                height,
+                self.format.pixel_format(),
+                self.format.pixel_type(),
+                data.as_ptr() as *const c_void,
+            );
+            gl::PixelStorei(gl::UNPACK_ROW_LENGTH, 0);
+        }
+
+        Ok(())
+    }
+
+    /// Read mip level `level` back as tightly packed pixels in the texture's format,
+    /// starting with the first row, which is the bottom one when the texture is drawn.
+    ///
+    /// Uses `glGetTexImage` where available, and reads through a framebuffer otherwise.
+    /// That fallback, which is what OpenGL ES has, can't read depth textures and only reads
+    /// the formats `glReadPixels` is guaranteed to support or the driver reports as readable.
+    pub fn read(&self, level: i32) -> Result<Vec<u8>, TextureError> {
+        self.check_level(level)?;
+        let (width, height) = self.level_size(level);
+        let mut data = vec![0_u8; self.format.image_size(width, height)];
+
+        unsafe {
+            gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+            if gl::GetTexImage::is_loaded() {
+                gl::BindTexture(gl::TEXTURE_2D, self.id);
+                gl::GetTexImage(
+                    gl::TEXTURE_2D,
+                    level,
+                    self.format.pixel_format(),
+                    self.format.pixel_type(),
+                    data.as_mut_ptr() as *mut c_void,
+                );
+            } else {
+                read_through_framebuffer(
+                    gl::TEXTURE_2D,
+                    self.id,
+                    level,
+                    self.format,
+                    (width, height),
+                    &mut data,
+                )?;
+            }
+        }
+
+        Ok(data)
+    }
+
+    pub(crate) fn check_level(&self, level: i32) -> Result<(), TextureError> {
+        let levels = self.levels.get();
+        if !(0..levels).contains(&level) {
+            return Err(TextureError::LevelOutOfRange { level, levels });
+        }
+
+        Ok(())
+    }
+
+    /// Compute every mip level from the base level with `glGenerateMipmap`.
+    ///
+    /// Textures created with [`super::TextureBuilder`] keep their number of levels,
+    /// the others grow a full chain of levels down to 1x1.
+    /// Integer and depth formats can't have their mipmaps generated.
+    pub fn regenerate_mipmaps(&self) -> Result<(), TextureError> {
+        if self.format.is_integer() || self.format.is_depth() {
+            return Err(TextureError::MipmapsUnsupported(self.format));
+        }
+
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_2D, self.id);
+            gl::GenerateMipmap(gl::TEXTURE_2D);
+        }
+        if !self.immutable {
+            self.levels.set(full_mip_chain(self.width, self.height));
+        }
+
+        Ok(())
+    }
+
+    /// Change how the texture is sampled, unless a [`Sampler`] is bound to its texture unit.
+    pub fn set_sampler(&self, desc: &SamplerDesc) {
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_2D, self.id);
+            desc.apply_to_texture(gl::TEXTURE_2D);
+        }
+    }
+
+    pub fn format(&self) -> TextureFormat {
+        self.format
+    }
+
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    /// The number of mip levels which have storage.
+    pub fn levels(&self) -> i32 {
+        self.levels.get()
+    }
+
+    /// The width and height of mip level `level`.
+    ///
+    /// # Panics
+    /// If the texture has no level `level`.
+    pub fn level_size(&self, level: i32) -> (i32, i32) {
+        self.check_level(level)
+            .unwrap_or_else(|err| panic!("Cannot get the level size: {err}"));
+        ((self.width >> level).max(1), (self.height >> level).max(1))
+    }
+
+    pub(crate) fn get_inner(&self) -> u32 {
+        self.id
+    }
+
+    /// Bind a texture to an index
+    unsafe fn bind(&self, index: u32) {
+        assert!(
+            index < 32,
+            "The texture index goes outside of the maximum texture range"
+        );
+        gl::ActiveTexture(gl::TEXTURE0 + index);
+        gl::BindTexture(gl::TEXTURE_2D, self.id)
+    }
+}
+
+/// The number of mip levels down to 1x1 of a `width` x `height` image.
+pub(crate) fn full_mip_chain(width: i32, height: i32) -> i32 {
+    let largest = width.max(height).max(1) as u32;
+    largest.ilog2() as i32 + 1
+}
+
+impl Drop for Texture {
+    fn drop(&mut self) {
+        unsafe { gl::DeleteTextures(1, ptr::addr_of!(self.id)) }
+    }
+}
+
+pub struct ActiveTexture {
+    id: u32,
+    texture_bound: Cell<bool>,
+}
+
+impl ActiveTexture {
+    pub fn new(index: u32) -> Self {
+        assert!(
+            index < 32,
+            "The texture index goes outside of the maximum texture range"
+        );
+        Self {
+            id: index,
+            texture_bound: Cell::new(false),
+        }
+    }
+
+    pub fn bind_texture(&self, texture: &Texture) {
+        unsafe { texture.bind(self.id) };
+        self.texture_bound.set(true)
+    }
+
+    /// Bind `cube_map` for a `samplerCube`. A unit can hold a [`Texture`] and a [`CubeMap`]
+    /// at the same time, but a program may only sample one of them through it.
+    pub fn bind_cube_map(&self, cube_map: &CubeMap) {
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE0 + self.id);
+            gl::BindTexture(gl::TEXTURE_CUBE_MAP, cube_map.get_inner());
+        }
+        self.texture_bound.set(true)
+    }
+
+    /// Sample the texture bound to this unit with `sampler` instead of the texture's own state.
+    pub fn bind_sampler(&self, sampler: &Sampler) {
+        unsafe { gl::BindSampler(self.id, sampler.get_inner()) }
+    }
+
+    /// Go back to the state of the texture bound to this unit.
+    pub fn unbind_sampler(&self) {
+        unsafe { gl::BindSampler(self.id, 0) }
+    }
+}
+
+impl UniformResource for ActiveTexture {
+    unsafe fn uniform(&self, location: i32) {
+        assert!(
+            self.texture_bound.get(),
+            "No texture has been bound to this ActiveTexture"
+        );
+
+        gl::Uniform1i(location, self.id as i32)
+    }
+
+    /// Images take the index of an image unit, which the unit's index doubles as.
+    fn accepts(ty: GlslType) -> bool {
+        ty.is_sampler() || ty.is_image()
+    }
+}
+
+/// OpenGL ES has no `glGetTexImage`, attach `level` of the texture `id` to a temporary
+/// framebuffer instead. `target` is `GL_TEXTURE_2D` or the face of a cube map.
+pub(crate) unsafe fn read_through_framebuffer(
+    target: u32,
+    id: u32,
+    level: i32,
+    format: TextureFormat,
+    (width, height): (i32, i32),
+    data: &mut [u8],
+) -> Result<(), TextureError> {
+    if format.is_depth() {
+        return Err(TextureError::Unreadable(format));
+    }
+
+    let mut previous = 0;
+    gl::GetIntegerv(gl::READ_FRAMEBUFFER_BINDING, ptr::addr_of_mut!(previous));
+    let mut framebuffer = 0;
+    gl::GenFramebuffers(1, ptr::addr_of_mut!(framebuffer));
+    gl::BindFramebuffer(gl::READ_FRAMEBUFFER, framebuffer);
+    gl::FramebufferTexture2D(
+        gl::READ_FRAMEBUFFER,
+        gl::COLOR_ATTACHMENT0,
+        target,
+        id,
+        level,
+    );
+
+    let status = gl::CheckFramebufferStatus(gl::READ_FRAMEBUFFER);
+    let result = match FramebufferError::from_status(status) {
+        Some(err) => Err(TextureError::Framebuffer(err)),
+        None if !readable_format(format) => Err(TextureError::Unreadable(format)),
+        None => {
+            gl::ReadPixels(
+                0,
+                0,
+                width,
+                height,
+                format.pixel_format(),
+                format.pixel_type(),
+                data.as_mut_ptr() as *mut c_void,
+            );
+            Ok(())
+        }
+    };
+
+    gl::BindFramebuffer(gl::READ_FRAMEBUFFER, previous as u32);
+    gl::DeleteFramebuffers(1, ptr::addr_of!(framebuffer));
+    result
+}
+
+/// Whether `glReadPixels` accepts `format`, which OpenGL ES only guarantees for
+/// four pairs of format and type and one more the driver chooses per framebuffer.
+/// Expects the texture to be attached to the bound read framebuffer.
+unsafe fn readable_format(format: TextureFormat) -> bool {
+    let pair = (format.pixel_format(), format.pixel_type());
+    let guaranteed = matches!(
+        pair,
+        (gl::RGBA, gl::UNSIGNED_BYTE)
+            | (gl::RGBA, gl::FLOAT)
+            | (gl::RGBA_INTEGER, gl::UNSIGNED_INT)
+            | (gl::RGBA_INTEGER, gl::INT)
+    );
+    if guaranteed {
+        return true;
+    }
+
+    let (mut read_format, mut read_type) = (0, 0);
+    gl::GetIntegerv(
+        gl::IMPLEMENTATION_COLOR_READ_FORMAT,
+        ptr::addr_of_mut!(read_format),
+    );
+    gl::GetIntegerv(
+        gl::IMPLEMENTATION_COLOR_READ_TYPE,
+        ptr::addr_of_mut!(read_type),
+    );
+    pair == (read_format as u32, read_type as u32)
+}
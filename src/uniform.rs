@@ -0,0 +1,212 @@
+use nalgebra_glm as glm;
+
+use crate::GlslType;
+
+pub trait UniformResource {
+    /// Upload the value to the uniform at `location` of the program in use.
+    ///
+    /// # Safety
+    /// A program must be in use and `location` must belong to it.
+    unsafe fn uniform(&self, location: i32);
+
+    /// Whether a uniform declared as `ty` can hold this value, checked by [`crate::Program::uniform`].
+    fn accepts(ty: GlslType) -> bool;
+}
+
+impl UniformResource for bool {
+    #[inline]
+    unsafe fn uniform(&self, location: i32) {
+        gl::Uniform1i(location, *self as i32)
+    }
+
+    fn accepts(ty: GlslType) -> bool {
+        ty == GlslType::Bool
+    }
+}
+
+impl UniformResource for i32 {
+    #[inline]
+    unsafe fn uniform(&self, location: i32) {
+        gl::Uniform1i(location, *self)
+    }
+
+    /// Samplers and images are set to the index of a texture or image unit,
+    /// like [`crate::ActiveTexture`] does.
+    fn accepts(ty: GlslType) -> bool {
+        matches!(ty, GlslType::Int | GlslType::Bool) || ty.is_sampler() || ty.is_image()
+    }
+}
+
+impl UniformResource for u32 {
+    #[inline]
+    unsafe fn uniform(&self, location: i32) {
+        gl::Uniform1ui(location, *self)
+    }
+
+    fn accepts(ty: GlslType) -> bool {
+        matches!(ty, GlslType::UInt | GlslType::Bool)
+    }
+}
+
+impl UniformResource for f32 {
+    #[inline]
+    unsafe fn uniform(&self, location: i32) {
+        gl::Uniform1f(location, *self)
+    }
+
+    fn accepts(ty: GlslType) -> bool {
+        ty == GlslType::Float
+    }
+}
+
+impl UniformResource for f64 {
+    #[inline]
+    unsafe fn uniform(&self, location: i32) {
+        gl::Uniform1d(location, *self)
+    }
+
+    fn accepts(ty: GlslType) -> bool {
+        ty == GlslType::Double
+    }
+}
+
+// mat4x4
+impl UniformResource for [f32; 16] {
+    unsafe fn uniform(&self, location: i32) {
+        gl::UniformMatrix4fv(location, 1, gl::FALSE, self.as_ptr())
+    }
+
+    fn accepts(ty: GlslType) -> bool {
+        ty == GlslType::Mat4
+    }
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// The components of vector and matrix uniforms: [`f32`], [`f64`], [`i32`] and [`u32`].
+pub trait UniformScalar: sealed::Sealed + glm::Scalar {
+    /// The GLSL type of a matrix with `rows` rows and `columns` columns,
+    /// vectors have a single column.
+    fn glsl_type(rows: usize, columns: usize) -> Option<GlslType>;
+
+    /// Upload `count` consecutive matrices of that shape.
+    ///
+    /// # Safety
+    /// Same as [`UniformResource::uniform`],
+    /// `data` must point to `count * rows * columns` values stored column by column.
+    unsafe fn upload(location: i32, count: i32, rows: usize, columns: usize, data: *const Self);
+}
+
+macro_rules! uniform_scalars {
+    ($($tp:ident {
+        $(($columns:literal, $rows:literal) => $glsl:ident, $function:ident $(($transpose:ident))?;)*
+    })*) => {
+        $(impl sealed::Sealed for $tp {}
+
+        impl UniformScalar for $tp {
+            fn glsl_type(rows: usize, columns: usize) -> Option<GlslType> {
+                match (columns, rows) {
+                    $(($columns, $rows) => Some(GlslType::$glsl),)*
+                    _ => None,
+                }
+            }
+
+            unsafe fn upload(location: i32, count: i32, rows: usize, columns: usize, data: *const Self) {
+                match (columns, rows) {
+                    $(($columns, $rows) => gl::$function(location, count, $(gl::$transpose,)? data),)*
+                    _ => panic!(
+                        "A matrix of {} with {rows} rows and {columns} columns can't be a uniform",
+                        stringify!($tp)
+                    ),
+                }
+            }
+        })*
+    };
+}
+
+uniform_scalars! {
+    f32 {
+        (1, 1) => Float, Uniform1fv;
+        (1, 2) => Vec2, Uniform2fv;
+        (1, 3) => Vec3, Uniform3fv;
+        (1, 4) => Vec4, Uniform4fv;
+        (2, 2) => Mat2, UniformMatrix2fv(FALSE);
+        (2, 3) => Mat2x3, UniformMatrix2x3fv(FALSE);
+        (2, 4) => Mat2x4, UniformMatrix2x4fv(FALSE);
+        (3, 2) => Mat3x2, UniformMatrix3x2fv(FALSE);
+        (3, 3) => Mat3, UniformMatrix3fv(FALSE);
+        (3, 4) => Mat3x4, UniformMatrix3x4fv(FALSE);
+        (4, 2) => Mat4x2, UniformMatrix4x2fv(FALSE);
+        (4, 3) => Mat4x3, UniformMatrix4x3fv(FALSE);
+        (4, 4) => Mat4, UniformMatrix4fv(FALSE);
+    }
+    f64 {
+        (1, 1) => Double, Uniform1dv;
+        (1, 2) => DVec2, Uniform2dv;
+        (1, 3) => DVec3, Uniform3dv;
+        (1, 4) => DVec4, Uniform4dv;
+        (2, 2) => DMat2, UniformMatrix2dv(FALSE);
+        (2, 3) => DMat2x3, UniformMatrix2x3dv(FALSE);
+        (2, 4) => DMat2x4, UniformMatrix2x4dv(FALSE);
+        (3, 2) => DMat3x2, UniformMatrix3x2dv(FALSE);
+        (3, 3) => DMat3, UniformMatrix3dv(FALSE);
+        (3, 4) => DMat3x4, UniformMatrix3x4dv(FALSE);
+        (4, 2) => DMat4x2, UniformMatrix4x2dv(FALSE);
+        (4, 3) => DMat4x3, UniformMatrix4x3dv(FALSE);
+        (4, 4) => DMat4, UniformMatrix4dv(FALSE);
+    }
+    i32 {
+        (1, 1) => Int, Uniform1iv;
+        (1, 2) => IVec2, Uniform2iv;
+        (1, 3) => IVec3, Uniform3iv;
+        (1, 4) => IVec4, Uniform4iv;
+    }
+    u32 {
+        (1, 1) => UInt, Uniform1uiv;
+        (1, 2) => UVec2, Uniform2uiv;
+        (1, 3) => UVec3, Uniform3uiv;
+        (1, 4) => UVec4, Uniform4uiv;
+    }
+}
+
+/// Vectors like [`glm::Vec3`] and matrices like [`glm::Mat4`] or [`glm::Mat2x3`].
+///
+/// `glm` names matrices by rows and columns while GLSL uses columns and rows,
+/// a [`glm::Mat2x3`] is a `mat3x2` uniform.
+impl<T: UniformScalar, const R: usize, const C: usize> UniformResource for glm::TMat<T, R, C> {
+    #[inline]
+    unsafe fn uniform(&self, location: i32) {
+        T::upload(location, 1, R, C, self.as_ptr())
+    }
+
+    fn accepts(ty: GlslType) -> bool {
+        T::glsl_type(R, C) == Some(ty)
+    }
+}
+
+/// Arrays of scalars, set with `glUniform*v`.
+impl<T: UniformScalar + UniformResource> UniformResource for [T] {
+    #[inline]
+    unsafe fn uniform(&self, location: i32) {
+        T::upload(location, self.len() as i32, 1, 1, self.as_ptr())
+    }
+
+    fn accepts(ty: GlslType) -> bool {
+        T::accepts(ty)
+    }
+}
+
+/// Arrays of vectors or matrices.
+impl<T: UniformScalar, const R: usize, const C: usize> UniformResource for [glm::TMat<T, R, C>] {
+    #[inline]
+    unsafe fn uniform(&self, location: i32) {
+        // Matrices are stored column by column without padding
+        T::upload(location, self.len() as i32, R, C, self.as_ptr() as *const T)
+    }
+
+    fn accepts(ty: GlslType) -> bool {
+        T::glsl_type(R, C) == Some(ty)
+    }
+}
@@ -0,0 +1,205 @@
+mod common;
+
+use common::{draw_fullscreen, with_context, FULLSCREEN_VERTEX};
+use graphics::{DrawUsage, Ebo, MapFlags, Program, Shader, Std140, UniformBuffer, Vao, Vbo};
+use nalgebra_glm as glm;
+
+/// Outputs green if every member of the block has the expected value, red otherwise.
+const CHECK_BLOCK_FRAGMENT: &str = r#"
+#version 330 core
+struct Light {
+    vec3 position;
+    float intensity;
+};
+
+layout (std140) uniform Scene {
+    float ambient;
+    Light light;
+    bool enabled;
+    float weights[3];
+    vec2 offset;
+    mat3 normal;
+    uint index;
+};
+
+out vec4 color;
+void main() {
+    bool ok = ambient == 0.5
+        && light.position == vec3(1.0, 2.0, 3.0)
+        && light.intensity == 4.0
+        && enabled
+        && weights[0] == 5.0 && weights[1] == 6.0 && weights[2] == 7.0
+        && offset == vec2(8.0, 9.0)
+        && normal[0] == vec3(1.0, 2.0, 3.0) && normal[2] == vec3(7.0, 8.0, 9.0)
+        && index == 10u;
+    color = ok ? vec4(0.0, 1.0, 0.0, 1.0) : vec4(1.0, 0.0, 0.0, 1.0);
+}
+"#;
+
+#[derive(Std140)]
+#[repr(C)]
+struct Light {
+    position: glm::Vec3,
+    intensity: f32,
+}
+
+#[derive(Std140)]
+#[repr(C)]
+struct Scene {
+    ambient: f32,
+    light: Light,
+    enabled: bool,
+    weights: [f32; 3],
+    offset: glm::Vec2,
+    normal: glm::Mat3,
+    index: u32,
+}
+
+fn contents<V: bytemuck::Pod>(vbo: &Vbo<V>) -> Vec<V> {
+    vbo.map_range(0, vbo.len(), MapFlags::READ).to_vec()
+}
+
+#[test]
+fn update_range() {
+    with_context(1, 1, |_| {
+        let vao = Vao::new();
+        let vbo = Vbo::new(&vao);
+        vbo.bind_data(&[1.0_f32, 2.0, 3.0, 4.0]);
+        vbo.update_range(1, &[20.0, 30.0]);
+
+        assert_eq!(vbo.len(), 4);
+        assert_eq!(contents(&vbo), [1.0, 20.0, 30.0, 4.0]);
+    });
+}
+
+#[test]
+fn reserve_keeps_contents() {
+    with_context(1, 1, |_| {
+        let vao = Vao::new();
+        let vbo = Vbo::new(&vao);
+        vbo.bind_data(&[1_u32, 2, 3]);
+        vbo.reserve(64);
+
+        assert_eq!(vbo.capacity(), 64);
+        assert_eq!(vbo.len(), 3);
+        assert_eq!(contents(&vbo), [1, 2, 3]);
+
+        vbo.update_range(3, &[4, 5]);
+        assert_eq!(contents(&vbo), [1, 2, 3, 4, 5]);
+    });
+}
+
+#[test]
+fn map_with_usage_defaults() {
+    with_context(1, 1, |_| {
+        let vao = Vao::new();
+        let ebo = Ebo::<u16>::new(&vao);
+        ebo.allocate(6, DrawUsage::DynamicDraw);
+        assert!(ebo.is_empty());
+
+        {
+            let mut indices = ebo.map(0, 6);
+            indices.copy_from_slice(&[0, 1, 2, 2, 3, 0]);
+        }
+        assert_eq!(ebo.len(), 6);
+
+        ebo.orphan();
+        assert!(ebo.is_empty());
+        assert_eq!(ebo.capacity(), 6);
+
+        ebo.map(3, 3).copy_from_slice(&[7, 8, 9]);
+        let indices = ebo.map_range(3, 3, MapFlags::READ).to_vec();
+        assert_eq!(indices, [7, 8, 9]);
+    });
+}
+
+#[test]
+#[should_panic(expected = "capacity")]
+fn update_past_capacity() {
+    with_context(1, 1, |_| {
+        let vao = Vao::new();
+        let vbo = Vbo::new(&vao);
+        vbo.allocate(2, DrawUsage::StreamDraw);
+        vbo.update_range(1, &[0_u8, 1]);
+    });
+}
+
+#[test]
+#[should_panic(expected = "outside of the buffer's capacity")]
+fn update_overflowing_offset() {
+    with_context(1, 1, |_| {
+        let vao = Vao::new();
+        let vbo = Vbo::new(&vao);
+        vbo.allocate(2, DrawUsage::StreamDraw);
+        vbo.update_range(usize::MAX / 2, &[0_u32]);
+    });
+}
+
+#[test]
+#[should_panic(expected = "outside of the buffer's capacity")]
+fn map_overflowing_range() {
+    with_context(1, 1, |_| {
+        let vao = Vao::new();
+        let vbo = Vbo::<u32>::new(&vao);
+        vbo.allocate(2, DrawUsage::StreamDraw);
+        let _mapping = vbo.map_range(1, usize::MAX / 4, MapFlags::WRITE);
+    });
+}
+
+#[test]
+#[should_panic(expected = "currently mapped")]
+fn reserve_while_mapped() {
+    with_context(1, 1, |_| {
+        let vao = Vao::new();
+        let vbo = Vbo::new(&vao);
+        vbo.bind_data(&[1_u32, 2, 3]);
+        let _mapping = vbo.map_range(0, 3, MapFlags::READ);
+        vbo.reserve(64);
+    });
+}
+
+#[test]
+#[should_panic(expected = "not mapped for writing")]
+fn write_read_only_mapping() {
+    with_context(1, 1, |_| {
+        let vao = Vao::new();
+        let vbo = Vbo::new(&vao);
+        vbo.bind_data(&[1_u32, 2, 3]);
+        vbo.map_range(0, 3, MapFlags::READ)[0] = 4;
+    });
+}
+
+#[test]
+fn uniform_buffer() {
+    let pixel = with_context(4, 4, |draw_layer| {
+        let vertex = Shader::compile(FULLSCREEN_VERTEX).unwrap();
+        let fragment = Shader::compile(CHECK_BLOCK_FRAGMENT).unwrap();
+        let program = Program::new(vertex, fragment).unwrap();
+
+        let mut scene = Scene {
+            ambient: 0.0,
+            light: Light {
+                position: glm::vec3(1.0, 2.0, 3.0),
+                intensity: 4.0,
+            },
+            enabled: true,
+            weights: [5.0, 6.0, 7.0],
+            offset: glm::vec2(8.0, 9.0),
+            normal: glm::mat3(1.0, 4.0, 7.0, 2.0, 5.0, 8.0, 3.0, 6.0, 9.0),
+            index: 10,
+        };
+        let buffer = UniformBuffer::new(&scene);
+        scene.ambient = 0.5;
+        buffer.update(&scene);
+
+        buffer.bind_base(3);
+        program.bind_uniform_block("Scene", 3).unwrap();
+        assert!(program.bind_uniform_block("Missing", 3).is_none());
+
+        draw_layer.use_program(&program);
+        draw_fullscreen(draw_layer)
+    });
+
+    assert_eq!(pixel, [0, 255, 0, 255]);
+    assert_eq!(Scene::SIZE, 176);
+}
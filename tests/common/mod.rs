@@ -0,0 +1,167 @@
+//! Golden image testing.
+//!
+//! Scenes are rendered with a [`HeadlessContext`] and compared against the PNG
+//! files in `tests/golden`. On a mismatch the rendered image and a diff are
+//! written to `golden` inside cargo's temporary target directory.
+//! Run with `GRAPHICS_BLESS=1` to replace the reference images with the current output.
+#![allow(dead_code)]
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use graphics::{ClearFlags, DrawLayer, DrawMode, HeadlessContext, PixelBuffer, Vao};
+
+macro_rules! fullscreen_main {
+    () => {
+        r#"void main() {
+    vec2 positions[3] = vec2[](vec2(-1.0, -1.0), vec2(3.0, -1.0), vec2(-1.0, 3.0));
+    gl_Position = vec4(positions[gl_VertexID], 0.0, 1.0);
+}
+"#
+    };
+}
+
+/// A vertex shader drawing one triangle over the whole viewport, without any attributes.
+pub const FULLSCREEN_VERTEX: &str = concat!("#version 330 core\n", fullscreen_main!());
+
+/// [`FULLSCREEN_VERTEX`] without the `#version` line, for sources run through a preprocessor.
+pub const FULLSCREEN_MAIN: &str = fullscreen_main!();
+
+/// `gl` keeps its function pointers in globals, so only one test loads them at a time.
+static GL: Mutex<()> = Mutex::new(());
+
+/// How far the rendered image may be from the reference.
+#[derive(Clone, Copy, Debug)]
+pub struct Tolerance {
+    /// The largest difference allowed for a single channel.
+    pub per_channel: u8,
+    /// The number of pixels allowed to exceed `per_channel`.
+    pub pixels: usize,
+}
+
+impl Tolerance {
+    pub const EXACT: Self = Self {
+        per_channel: 0,
+        pixels: 0,
+    };
+}
+
+impl Default for Tolerance {
+    /// Absorbs rounding differences between rasterizer versions.
+    fn default() -> Self {
+        Self {
+            per_channel: 2,
+            pixels: 0,
+        }
+    }
+}
+
+/// Run `f` with a fresh context whose default framebuffer is `width` x `height`,
+/// failing if it leaves a GL error behind.
+pub fn with_context<R>(width: u32, height: u32, f: impl FnOnce(&DrawLayer) -> R) -> R {
+    let _guard = GL.lock().unwrap_or_else(|err| err.into_inner());
+
+    let context = HeadlessContext::new(width as i32, height as i32)
+        .expect("Cannot create a headless OpenGL context");
+    let draw_layer = context.draw_layer();
+    draw_layer.set_viewport(0, 0, width as i32, height as i32);
+
+    let result = f(&draw_layer);
+
+    assert_eq!(
+        draw_layer.get_gl_error(),
+        gl::NO_ERROR,
+        "A GL error was raised"
+    );
+    result
+}
+
+/// Run `scene` with a fresh `width` x `height` context and read back the default framebuffer.
+pub fn render(width: u32, height: u32, scene: impl FnOnce(&DrawLayer)) -> PixelBuffer {
+    with_context(width, height, |draw_layer| {
+        scene(draw_layer);
+        draw_layer.read_pixels(0, 0, width, height)
+    })
+}
+
+/// Clear the color buffer, draw a fullscreen triangle with the program or pipeline in use
+/// and read back one pixel. The context has to be at least 2x2.
+pub fn draw_fullscreen(draw_layer: &DrawLayer) -> [u8; 4] {
+    let vao = Vao::new();
+    draw_layer.clear(ClearFlags::COLOR);
+    draw_layer.draw_arrays(&vao, DrawMode::Triangles, 0, 3);
+    draw_layer.read_pixels(0, 0, 2, 2).pixel(1, 1)
+}
+
+/// A fresh directory for each test, as they run in parallel.
+/// `group` is usually the name of the test file.
+pub fn test_directory(group: &str, test: &str) -> PathBuf {
+    let directory = Path::new(env!("CARGO_TARGET_TMPDIR"))
+        .join(group)
+        .join(test);
+    let _ = fs::remove_dir_all(&directory);
+    fs::create_dir_all(&directory).expect("Cannot create the test directory");
+
+    directory
+}
+
+/// Compare `actual` against `tests/golden/{name}.png`.
+pub fn assert_golden(name: &str, actual: &PixelBuffer, tolerance: Tolerance) {
+    let reference_path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(format!("{name}.png"));
+
+    if env::var_os("GRAPHICS_BLESS").is_some() {
+        actual
+            .save_png(&reference_path)
+            .expect("Cannot write the reference image");
+        return;
+    }
+
+    let reference = PixelBuffer::load_png(&reference_path).unwrap_or_else(|err| {
+        panic!(
+            "Cannot load {}: {err}, run with GRAPHICS_BLESS=1 to create it",
+            reference_path.display()
+        )
+    });
+
+    if (reference.width, reference.height) != (actual.width, actual.height) {
+        let actual_path = write_output(name, "actual", actual);
+        panic!(
+            "{name}: expected a {}x{} image, got {}x{} (written to {})",
+            reference.width,
+            reference.height,
+            actual.width,
+            actual.height,
+            actual_path.display()
+        );
+    }
+
+    let diff = actual.diff(&reference, tolerance.per_channel);
+    if diff.differing_pixels > tolerance.pixels {
+        let actual_path = write_output(name, "actual", actual);
+        let diff_path = write_output(name, "diff", &diff.image);
+        panic!(
+            "{name}: {} pixels differ by up to {} (tolerance: {tolerance:?})\n  actual: {}\n  diff: {}",
+            diff.differing_pixels,
+            diff.max_difference,
+            actual_path.display(),
+            diff_path.display()
+        );
+    }
+}
+
+fn write_output(name: &str, kind: &str, image: &PixelBuffer) -> PathBuf {
+    let directory = Path::new(env!("CARGO_TARGET_TMPDIR")).join("golden");
+    std::fs::create_dir_all(&directory).expect("Cannot create the output directory");
+
+    let path = directory.join(format!("{name}.{kind}.png"));
+    image
+        .save_png(&path)
+        .expect("Cannot write the output image");
+
+    path
+}
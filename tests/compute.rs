@@ -0,0 +1,149 @@
+mod common;
+
+use common::with_context;
+use graphics::{
+    Attachment, BarrierFlags, ComputeProgram, DispatchIndirectCommand, DrawUsage, Framebuffer,
+    ImageAccess, MapFlags, Shader, StorageBuffer, Texture, TextureError, TextureFormat,
+};
+
+const DOUBLE_COMPUTE: &str = r#"
+#version 430 core
+layout (local_size_x = 4) in;
+
+layout (std430) buffer Values {
+    uint values[];
+};
+
+void main() {
+    values[gl_GlobalInvocationID.x] *= 2u;
+}
+"#;
+
+const GRADIENT_COMPUTE: &str = r#"
+#version 430 core
+layout (local_size_x = 2, local_size_y = 2) in;
+layout (rgba8) uniform writeonly image2D target;
+
+void main() {
+    ivec2 texel = ivec2(gl_GlobalInvocationID.xy);
+    imageStore(target, texel, vec4(float(texel.x) / 3.0, float(texel.y) / 3.0, 0.0, 1.0));
+}
+"#;
+
+fn compute_program(source: &str) -> ComputeProgram {
+    ComputeProgram::new(Shader::compile(source).unwrap()).unwrap()
+}
+
+fn contents<T: bytemuck::Pod>(buffer: &StorageBuffer<T>) -> Vec<T> {
+    buffer.map_range(0, buffer.len(), MapFlags::READ).to_vec()
+}
+
+#[test]
+fn dispatch() {
+    with_context(1, 1, |draw_layer| {
+        let program = compute_program(DOUBLE_COMPUTE);
+        assert_eq!(program.work_group_size(), [4, 1, 1]);
+
+        let values = StorageBuffer::new();
+        values.bind_data_ex(&[1_u32, 2, 3, 4, 5, 6, 7, 8], DrawUsage::DynamicCopy);
+        values.bind_base(0);
+        assert!(program
+            .as_program()
+            .bind_storage_block("Values", 0)
+            .is_some());
+        assert!(program
+            .as_program()
+            .bind_storage_block("Missing", 1)
+            .is_none());
+
+        draw_layer.use_compute_program(&program);
+        draw_layer.dispatch(2, 1, 1);
+        draw_layer.memory_barrier(BarrierFlags::BUFFER_UPDATE);
+
+        assert_eq!(contents(&values), [2, 4, 6, 8, 10, 12, 14, 16]);
+    });
+}
+
+#[test]
+fn dispatch_indirect() {
+    with_context(1, 1, |draw_layer| {
+        let program = compute_program(DOUBLE_COMPUTE);
+        let values = StorageBuffer::new();
+        values.bind_data_ex(&[1_u32; 8], DrawUsage::DynamicCopy);
+        values.bind_base(0);
+        program.as_program().bind_storage_block("Values", 0);
+
+        // Only the second command is used, so only the first work group runs.
+        let commands = StorageBuffer::new();
+        commands.bind_data(&[
+            DispatchIndirectCommand::default(),
+            DispatchIndirectCommand {
+                num_groups_x: 1,
+                num_groups_y: 1,
+                num_groups_z: 1,
+            },
+        ]);
+
+        draw_layer.use_compute_program(&program);
+        draw_layer.dispatch_indirect(&commands, size_of::<DispatchIndirectCommand>());
+        draw_layer.memory_barrier(BarrierFlags::BUFFER_UPDATE | BarrierFlags::SHADER_STORAGE);
+
+        assert_eq!(contents(&values), [2, 2, 2, 2, 1, 1, 1, 1]);
+    });
+}
+
+#[test]
+fn image_store() {
+    let pixels = with_context(1, 1, |draw_layer| {
+        let program = compute_program(GRADIENT_COMPUTE);
+        let texture = Texture::empty(4, 4);
+
+        draw_layer.use_compute_program(&program);
+        draw_layer
+            .bind_image_texture(0, &texture, 0, ImageAccess::WriteOnly)
+            .unwrap();
+        draw_layer.dispatch(2, 2, 1);
+        draw_layer.memory_barrier(BarrierFlags::FRAMEBUFFER);
+
+        let framebuffer = Framebuffer::new();
+        framebuffer.attach_texture(Attachment::Color(0), &texture, 0);
+        draw_layer.bind_framebuffer(&framebuffer);
+        let pixels = draw_layer.read_pixels(0, 0, 4, 4);
+        draw_layer.unbind_framebuffer();
+
+        pixels
+    });
+
+    // The rows are read back from top to bottom
+    assert_eq!(pixels.pixel(0, 3), [0, 0, 0, 255]);
+    assert_eq!(pixels.pixel(3, 3), [255, 0, 0, 255]);
+    assert_eq!(pixels.pixel(3, 0), [255, 255, 0, 255]);
+    assert_eq!(pixels.pixel(1, 1), [85, 170, 0, 255]);
+}
+
+#[test]
+fn image_format_errors() {
+    with_context(1, 1, |draw_layer| {
+        for format in [
+            TextureFormat::Rgb8,
+            TextureFormat::Srgb8Alpha8,
+            TextureFormat::Depth32F,
+        ] {
+            let texture =
+                Texture::with_format(&vec![0; 4 * format.bytes_per_pixel()], 2, 2, format).unwrap();
+            assert_eq!(
+                draw_layer.bind_image_texture(0, &texture, 0, ImageAccess::ReadOnly),
+                Err(TextureError::UnsupportedImageFormat(format))
+            );
+        }
+
+        let texture = Texture::empty(2, 2);
+        assert_eq!(
+            draw_layer.bind_image_texture(0, &texture, 1, ImageAccess::ReadOnly),
+            Err(TextureError::LevelOutOfRange {
+                level: 1,
+                levels: 1
+            })
+        );
+    });
+}
@@ -0,0 +1,252 @@
+mod common;
+
+use common::{with_context, FULLSCREEN_VERTEX};
+use graphics::{
+    ActiveTexture, ClearFlags, CubeFace, CubeMap, DrawLayer, DrawMode, EquirectangularError,
+    PixelBuffer, Program, SamplerDesc, Shader, Skybox, Texture, TextureError, TextureFormat, Vao,
+};
+use nalgebra_glm as glm;
+
+/// One color per face, in the order of [`CubeFace::ALL`].
+const FACE_COLORS: [[u8; 4]; 6] = [
+    [255, 0, 0, 255],
+    [0, 255, 0, 255],
+    [0, 0, 255, 255],
+    [255, 255, 0, 255],
+    [0, 255, 255, 255],
+    [255, 0, 255, 255],
+];
+
+fn solid_faces(size: i32) -> CubeMap {
+    let faces = FACE_COLORS.map(|color| color.repeat((size * size) as usize));
+    CubeMap::new(
+        faces.each_ref().map(Vec::as_slice),
+        size,
+        TextureFormat::Rgba8,
+    )
+    .unwrap()
+}
+
+/// Sample `cube_map` along the axis of each face, one pixel per face.
+fn sample_axes(draw_layer: &DrawLayer, cube_map: &CubeMap) -> PixelBuffer {
+    let fragment = r#"#version 330 core
+    uniform samplerCube environment;
+    out vec4 color;
+    void main() {
+        vec3 directions[6] = vec3[](
+            vec3(1.0, 0.0, 0.0), vec3(-1.0, 0.0, 0.0),
+            vec3(0.0, 1.0, 0.0), vec3(0.0, -1.0, 0.0),
+            vec3(0.0, 0.0, 1.0), vec3(0.0, 0.0, -1.0)
+        );
+        color = texture(environment, directions[int(gl_FragCoord.x)]);
+    }"#;
+    let program = Program::new(
+        Shader::compile(FULLSCREEN_VERTEX).unwrap(),
+        Shader::compile(fragment).unwrap(),
+    )
+    .unwrap();
+
+    let unit = ActiveTexture::new(0);
+    unit.bind_cube_map(cube_map);
+    draw_layer.use_program(&program);
+    draw_layer
+        .put_uniform(&program, "environment", &unit)
+        .unwrap();
+
+    let vao = Vao::new();
+    draw_layer.clear(ClearFlags::COLOR);
+    draw_layer.draw_arrays(&vao, DrawMode::Triangles, 0, 3);
+    draw_layer.read_pixels(0, 0, 6, 1)
+}
+
+fn center_texel(cube_map: &CubeMap, face: CubeFace) -> [u8; 4] {
+    let size = cube_map.size() as usize;
+    let data = cube_map.read_face(face, 0).unwrap();
+    let start = (size / 2 * size + size / 2) * 4;
+
+    data[start..start + 4].try_into().unwrap()
+}
+
+#[test]
+fn faces_by_direction() {
+    with_context(6, 1, |draw_layer| {
+        let cube_map = solid_faces(4);
+        assert_eq!(cube_map.size(), 4);
+        assert_eq!(cube_map.levels(), 1);
+
+        let pixels = sample_axes(draw_layer, &cube_map);
+        for (x, color) in FACE_COLORS.into_iter().enumerate() {
+            assert_eq!(pixels.pixel(x as u32, 0), color, "face {x}");
+        }
+
+        for (face, color) in CubeFace::ALL.into_iter().zip(FACE_COLORS) {
+            assert_eq!(cube_map.read_face(face, 0).unwrap(), color.repeat(16));
+        }
+    })
+}
+
+#[test]
+fn mipmaps_and_errors() {
+    with_context(6, 1, |draw_layer| {
+        let cube_map = solid_faces(4);
+        cube_map.regenerate_mipmaps().unwrap();
+        assert_eq!(cube_map.levels(), 3);
+        assert_eq!(
+            cube_map.read_face(CubeFace::NegativeY, 2).unwrap(),
+            FACE_COLORS[3].to_vec()
+        );
+
+        draw_layer.enable_seamless_cube_maps();
+        cube_map.set_sampler(&SamplerDesc {
+            mipmap_filter: Some(graphics::Filter::Linear),
+            ..SamplerDesc::linear().wrap(graphics::Wrap::ClampToEdge)
+        });
+        let pixels = sample_axes(draw_layer, &cube_map);
+        assert_eq!(pixels.pixel(4, 0), FACE_COLORS[4]);
+
+        let short = [0_u8; 4];
+        let full = [0_u8; 16];
+        let faces = [&full[..], &full, &full, &short, &full, &full];
+        assert!(matches!(
+            CubeMap::new(faces, 2, TextureFormat::Rgba8),
+            Err(TextureError::DataLength {
+                expected: 16,
+                actual: 4
+            })
+        ));
+
+        assert_eq!(
+            cube_map.read_face(CubeFace::PositiveX, 3),
+            Err(TextureError::LevelOutOfRange {
+                level: 3,
+                levels: 3
+            })
+        );
+
+        let integer = CubeMap::empty(2, TextureFormat::R32Ui);
+        assert!(matches!(
+            integer.regenerate_mipmaps(),
+            Err(TextureError::MipmapsUnsupported(TextureFormat::R32Ui))
+        ));
+    })
+}
+
+#[test]
+fn equirectangular_projection() {
+    with_context(4, 4, |_| {
+        // Bottom row red, top row green and the horizon split into four pairs of columns
+        // centered on -Z, +X, +Z and -X.
+        const RED: [u8; 4] = [255, 0, 0, 255];
+        const GREEN: [u8; 4] = [0, 255, 0, 255];
+        let horizon = |column: usize| match column {
+            1 | 2 => [0, 0, 255, 255],
+            3 | 4 => [255, 255, 0, 255],
+            5 | 6 => [0, 255, 255, 255],
+            _ => [255, 0, 255, 255],
+        };
+        let mut pixels = Vec::new();
+        for row in 0..4 {
+            for column in 0..8 {
+                pixels.extend(match row {
+                    0 => RED,
+                    3 => GREEN,
+                    _ => horizon(column),
+                });
+            }
+        }
+        let panorama = Texture::with_format(&pixels, 8, 4, TextureFormat::Rgba8).unwrap();
+        panorama.set_sampler(&SamplerDesc::nearest());
+
+        // State a scene might leave behind doesn't leak into the faces
+        unsafe {
+            gl::Enable(gl::BLEND);
+            gl::BlendFunc(gl::ZERO, gl::ONE);
+            gl::Enable(gl::SCISSOR_TEST);
+            gl::Scissor(0, 0, 1, 1);
+            gl::ColorMask(gl::FALSE, gl::TRUE, gl::TRUE, gl::TRUE);
+        }
+
+        let cube_map = CubeMap::from_equirectangular(&panorama, 16, TextureFormat::Rgba8).unwrap();
+        assert_eq!(cube_map.format(), TextureFormat::Rgba8);
+        assert_eq!(center_texel(&cube_map, CubeFace::PositiveY), GREEN);
+        assert_eq!(center_texel(&cube_map, CubeFace::NegativeY), RED);
+        assert_eq!(center_texel(&cube_map, CubeFace::NegativeZ), horizon(1));
+        assert_eq!(center_texel(&cube_map, CubeFace::PositiveX), horizon(3));
+        assert_eq!(center_texel(&cube_map, CubeFace::PositiveZ), horizon(5));
+        assert_eq!(center_texel(&cube_map, CubeFace::NegativeX), horizon(7));
+
+        // The framebuffer, viewport and the state above are left as they were
+        let mut viewport = [0; 4];
+        let mut framebuffer = -1;
+        let mut color_mask = [gl::TRUE; 4];
+        unsafe {
+            gl::GetIntegerv(gl::VIEWPORT, viewport.as_mut_ptr());
+            gl::GetIntegerv(gl::DRAW_FRAMEBUFFER_BINDING, &mut framebuffer);
+            gl::GetBooleanv(gl::COLOR_WRITEMASK, color_mask.as_mut_ptr());
+            assert_eq!(gl::IsEnabled(gl::BLEND), gl::TRUE);
+            assert_eq!(gl::IsEnabled(gl::SCISSOR_TEST), gl::TRUE);
+        }
+        assert_eq!(viewport, [0, 0, 4, 4]);
+        assert_eq!(framebuffer, 0);
+        assert_eq!(color_mask, [gl::FALSE, gl::TRUE, gl::TRUE, gl::TRUE]);
+
+        assert!(matches!(
+            CubeMap::from_equirectangular(&panorama, 4, TextureFormat::R32Ui),
+            Err(EquirectangularError::Texture(TextureError::NotRenderable(
+                TextureFormat::R32Ui
+            )))
+        ));
+    })
+}
+
+#[test]
+fn skybox_behind_scene() {
+    with_context(4, 4, |draw_layer| {
+        let sky = solid_faces(2);
+        let skybox = Skybox::new().unwrap();
+
+        // A quad over the left half, halfway between the near and far plane
+        let scene = Program::new(
+            Shader::compile(
+                r#"#version 330 core
+                void main() {
+                    vec2 positions[4] = vec2[](vec2(-1.0, -1.0), vec2(0.0, -1.0), vec2(-1.0, 1.0), vec2(0.0, 1.0));
+                    gl_Position = vec4(positions[gl_VertexID], 0.5, 1.0);
+                }"#,
+            )
+            .unwrap(),
+            Shader::compile(
+                r#"#version 330 core
+                out vec4 color;
+                void main() { color = vec4(1.0); }"#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        draw_layer.enable_depth_testing();
+        draw_layer.clear(ClearFlags::COLOR | ClearFlags::DEPTH);
+        draw_layer.use_program(&scene);
+        draw_layer.draw_arrays(&Vao::new(), DrawMode::TriangleStrip, 0, 4);
+
+        let projection = glm::perspective(1.0, std::f32::consts::FRAC_PI_2, 0.1, 10.0);
+        // Moving the camera doesn't move the sky
+        let view = glm::translation(&glm::vec3(3.0, -2.0, 5.0));
+        skybox.draw(draw_layer, &sky, &view, &projection);
+
+        let pixels = draw_layer.read_pixels(0, 0, 4, 4);
+        assert_eq!(pixels.pixel(0, 2), [255, 255, 255, 255]);
+        assert_eq!(pixels.pixel(3, 2), FACE_COLORS[5]);
+
+        // Turning around shows +Z
+        let view = glm::rotation(std::f32::consts::PI, &glm::vec3(0.0, 1.0, 0.0));
+        draw_layer.clear(ClearFlags::COLOR | ClearFlags::DEPTH);
+        skybox.draw(draw_layer, &sky, &view, &projection);
+        let pixels = draw_layer.read_pixels(0, 0, 4, 4);
+        assert_eq!(pixels.pixel(1, 1), FACE_COLORS[4]);
+
+        let mut depth_function = 0;
+        unsafe { gl::GetIntegerv(gl::DEPTH_FUNC, &mut depth_function) };
+        assert_eq!(depth_function as u32, gl::LESS);
+    })
+}
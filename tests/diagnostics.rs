@@ -0,0 +1,95 @@
+use graphics::{CompileError, Diagnostic, Severity, ShaderType};
+
+const SOURCE: &str = "#version 330 core
+#line 1 0
+out vec4 color;
+#line 1 1
+float light() {
+    return undefined;
+}
+#line 2 0
+void main() {
+    color = vec4(light());
+}
+";
+
+fn error(log: &str) -> CompileError {
+    CompileError {
+        stage: ShaderType::Fragment,
+        log: log.to_owned(),
+        diagnostics: Diagnostic::parse_log(ShaderType::Fragment, log),
+        source: SOURCE.to_owned(),
+        files: vec!["main.frag".to_owned(), "light.glsl".to_owned()],
+    }
+}
+
+#[test]
+fn driver_formats() {
+    let logs = [
+        "1:2(12): error: `undefined' undeclared\n",
+        "1(2) : error C1008: undefined variable \"undefined\"\n",
+        "ERROR: 1:2: 'undefined' : undeclared identifier\n",
+    ];
+
+    for log in logs {
+        let diagnostics = Diagnostic::parse_log(ShaderType::Fragment, log);
+        assert_eq!(diagnostics.len(), 1, "{log}");
+        assert_eq!(diagnostics[0].stage, ShaderType::Fragment);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!((diagnostics[0].file, diagnostics[0].line), (1, Some(2)));
+    }
+
+    let mesa = Diagnostic::parse_log(ShaderType::Vertex, "0:4(7): warning: unused\n");
+    assert_eq!(
+        mesa,
+        [Diagnostic {
+            stage: ShaderType::Vertex,
+            file: 0,
+            line: Some(4),
+            column: Some(7),
+            severity: Severity::Warning,
+            message: "unused".to_owned(),
+        }]
+    );
+
+    let nvidia = Diagnostic::parse_log(ShaderType::Vertex, "0(4) : warning C7050: unused\n");
+    assert_eq!(nvidia[0].message, "unused");
+
+    let amd = Diagnostic::parse_log(ShaderType::Vertex, "WARNING: 0:4: unused\nnot a message");
+    assert_eq!(amd.len(), 1);
+    assert_eq!(amd[0].severity, Severity::Warning);
+}
+
+#[test]
+fn source_lines() {
+    let err =
+        error("1:2(12): error: `undefined' undeclared\n0:3(5): warning: implicit conversion\n");
+
+    assert_eq!(
+        err.source_line(&err.diagnostics[0]),
+        Some("    return undefined;")
+    );
+    assert_eq!(
+        err.source_line(&err.diagnostics[1]),
+        Some("    color = vec4(light());")
+    );
+    assert_eq!(
+        err.to_string(),
+        "error: `undefined' undeclared
+  --> light.glsl:2:12 (fragment shader)
+  |
+2 |     return undefined;
+warning: implicit conversion
+  --> main.frag:3:5 (fragment shader)
+  |
+3 |     color = vec4(light());"
+    );
+}
+
+#[test]
+fn unknown_format() {
+    let err = error("something went wrong\n");
+
+    assert!(err.diagnostics.is_empty());
+    assert_eq!(err.to_string(), "fragment shader: something went wrong");
+}
@@ -0,0 +1,147 @@
+mod common;
+
+use common::{assert_golden, render, Tolerance};
+use graphics::{
+    attributes, ActiveTexture, ClearFlags, Color, DrawMode, Program, Shader, Texture, Vao, Vbo,
+};
+use image::{EncodableLayout, ImageReader};
+
+const TRIANGLE_VERTEX: &str = r#"
+#version 330 core
+layout (location = 0) in vec2 position;
+layout (location = 1) in vec3 color;
+out vec3 fragment_color;
+void main() {
+    gl_Position = vec4(position, 1.0, 1.0);
+    fragment_color = color;
+}
+"#;
+
+const TRIANGLE_FRAGMENT: &str = r#"
+#version 330 core
+in vec3 fragment_color;
+out vec4 color;
+void main() {
+    color = vec4(fragment_color, 1.0);
+}
+"#;
+
+const TRIANGLE_DATA: [f32; 15] = [
+    0.0, 0.5, // first pos
+    1.0, 0.819, 0.729, // first color
+    0.5, -0.5, // second pos
+    0.807, 0.490, 0.647, // second color
+    -0.5, -0.5, // third pos
+    0.745, 0.8980, 0.749, // third color
+];
+
+const TEXTURED_VERTEX: &str = r#"
+#version 330 core
+layout (location = 0) in vec2 position;
+layout (location = 1) in vec3 color;
+layout (location = 2) in vec2 uv;
+out vec3 fragment_color;
+out vec2 texture_coords;
+void main() {
+    gl_Position = vec4(position, 1.0, 1.0);
+    fragment_color = color;
+    texture_coords = uv;
+}
+"#;
+
+const TEXTURED_FRAGMENT: &str = r#"
+#version 330 core
+in vec3 fragment_color;
+in vec2 texture_coords;
+uniform sampler2D tex;
+uniform float time;
+
+out vec4 color;
+void main() {
+    color = vec4(fragment_color, 1.0) * texture(tex, texture_coords) * sin(time);
+}
+"#;
+
+const TEXTURED_DATA: [f32; 21] = [
+    0.0, 0.5, // first pos
+    1.0, 0.819, 0.729, // first color
+    0.5, 1.0, // first uv
+    0.5, -0.5, // second pos
+    0.807, 0.490, 0.647, // second color
+    1.0, 0.0, // second uv
+    -0.5, -0.5, // third pos
+    0.745, 0.8980, 0.749, // third color
+    0.0, 0.0, // thid uv
+];
+
+#[test]
+fn triangle() {
+    let pixels = render(128, 128, |draw_layer| {
+        let vao = Vao::new();
+        let vbo = Vbo::new(&vao);
+        vbo.bind_data(&TRIANGLE_DATA);
+
+        let vertex = Shader::compile(TRIANGLE_VERTEX).unwrap();
+        let fragment = Shader::compile(TRIANGLE_FRAGMENT).unwrap();
+        let program = Program::new(vertex, fragment).unwrap();
+
+        let attrs = attributes! {
+            position: vec<f32, 2>,
+            color: vec<f32, 3>
+        };
+        attrs.calculate_for(&program).unwrap();
+
+        draw_layer.set_clear_color(Color::WHITE);
+        draw_layer.use_program(&program);
+        draw_layer.clear(ClearFlags::COLOR);
+        draw_layer.draw_arrays(&vao, DrawMode::Triangles, 0, 3);
+    });
+
+    assert_golden("triangle", &pixels, Tolerance::default());
+}
+
+#[test]
+fn textured_triangle() {
+    let image = ImageReader::open(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/triangle.jpeg"))
+        .unwrap()
+        .decode()
+        .unwrap()
+        .flipv()
+        .to_rgba8();
+
+    let pixels = render(128, 128, |draw_layer| {
+        let vao = Vao::new();
+        let vbo = Vbo::new(&vao);
+        vbo.bind_data(&TEXTURED_DATA);
+
+        let vertex = Shader::compile(TEXTURED_VERTEX).unwrap();
+        let fragment = Shader::compile(TEXTURED_FRAGMENT).unwrap();
+        let program = Program::new(vertex, fragment).unwrap();
+
+        let attrs = attributes! {
+            position: vec<f32, 2>,
+            color: vec<f32, 3>,
+            uv: vec<f32, 2>
+        };
+        attrs.calculate_for(&program).unwrap();
+
+        draw_layer.use_program(&program);
+
+        let texture = Texture::new(
+            image.as_bytes(),
+            image.width() as i32,
+            image.height() as i32,
+        );
+        let active_texture = ActiveTexture::new(0);
+        active_texture.bind_texture(&texture);
+        draw_layer.put_uniform(&program, "tex", &active_texture);
+        // sin(time) == 1, the example's animation at full brightness
+        draw_layer.put_uniform(&program, "time", &std::f32::consts::FRAC_PI_2);
+
+        draw_layer.set_clear_color(Color::BLACK);
+        draw_layer.clear(ClearFlags::COLOR);
+        draw_layer.draw_arrays(&vao, DrawMode::Triangles, 0, 3);
+    });
+
+    assert_golden("textured_triangle", &pixels, Tolerance::default());
+}
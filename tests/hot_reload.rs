@@ -0,0 +1,108 @@
+mod common;
+
+use std::{
+    fs::{self, File},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use common::{draw_fullscreen, test_directory, with_context, FULLSCREEN_MAIN};
+use graphics::{Preprocessor, ProgramSources, ShaderDirectory, ShaderError, ShaderWatcher};
+
+const FRAGMENT: &str = r#"
+#include "color.glsl"
+out vec4 color;
+void main() {
+    color = COLOR;
+}
+"#;
+
+/// Write `text` to `path` and move its modification time forward,
+/// the file system may not notice writes in quick succession otherwise.
+fn write(path: &Path, text: &str, age: u64) {
+    fs::write(path, text).unwrap();
+    let modified = SystemTime::now() + Duration::from_secs(age);
+    File::options()
+        .write(true)
+        .open(path)
+        .unwrap()
+        .set_modified(modified)
+        .unwrap();
+}
+
+/// A fresh directory with the shaders of the tests.
+fn shader_directory(name: &str) -> PathBuf {
+    let root = test_directory("hot_reload", name);
+    write(&root.join("fullscreen.vert"), FULLSCREEN_MAIN, 0);
+    write(&root.join("color.frag"), FRAGMENT, 0);
+    write(
+        &root.join("color.glsl"),
+        "#define COLOR vec4(1.0, 0.0, 0.0, 1.0)\n",
+        0,
+    );
+
+    root
+}
+
+#[test]
+fn reload_on_change() {
+    let root = shader_directory("reload_on_change");
+
+    with_context(4, 4, |draw_layer| {
+        let preprocessor = Preprocessor::new(ShaderDirectory::new(&root)).version("330 core");
+        let sources = ProgramSources::new("fullscreen.vert", "color.frag");
+        let mut watcher = ShaderWatcher::new(preprocessor, sources, &[]).unwrap();
+        watcher.set_poll_interval(Duration::ZERO);
+
+        let mut files: Vec<_> = watcher.files().collect();
+        files.sort();
+        assert_eq!(files, ["color.frag", "color.glsl", "fullscreen.vert"]);
+
+        assert!(watcher.poll().is_none());
+        draw_layer.use_program(watcher.program());
+        assert_eq!(draw_fullscreen(draw_layer), [255, 0, 0, 255]);
+
+        // Included files are watched too
+        write(
+            &root.join("color.glsl"),
+            "#define COLOR vec4(0.0, 1.0, 0.0, 1.0)\n",
+            10,
+        );
+        assert!(matches!(watcher.poll(), Some(Ok(()))));
+        assert!(watcher.poll().is_none());
+        draw_layer.use_program(watcher.program());
+        assert_eq!(draw_fullscreen(draw_layer), [0, 255, 0, 255]);
+
+        // A broken shader keeps the previous program
+        write(&root.join("color.frag"), "void main() { broken }", 20);
+        assert!(matches!(
+            watcher.poll(),
+            Some(Err(ShaderError::CompilationError(_)))
+        ));
+        assert!(watcher.poll().is_none());
+        draw_layer.use_program(watcher.program());
+        assert_eq!(draw_fullscreen(draw_layer), [0, 255, 0, 255]);
+
+        write(&root.join("color.frag"), FRAGMENT, 30);
+        assert!(matches!(watcher.poll(), Some(Ok(()))));
+        draw_layer.use_program(watcher.program());
+        assert_eq!(draw_fullscreen(draw_layer), [0, 255, 0, 255]);
+    });
+}
+
+#[test]
+fn poll_interval() {
+    let root = shader_directory("poll_interval");
+
+    with_context(1, 1, |_| {
+        let preprocessor = Preprocessor::new(ShaderDirectory::new(&root)).version("330 core");
+        let sources = ProgramSources::new("fullscreen.vert", "color.frag");
+        let mut watcher = ShaderWatcher::new(preprocessor, sources, &[]).unwrap();
+        watcher.set_poll_interval(Duration::from_secs(3600));
+
+        assert!(watcher.poll().is_none());
+        write(&root.join("color.frag"), FRAGMENT, 40);
+        assert!(watcher.poll().is_none());
+        assert!(watcher.reload().is_ok());
+    });
+}
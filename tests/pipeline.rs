@@ -0,0 +1,99 @@
+mod common;
+
+use common::{draw_fullscreen, with_context};
+use graphics::{
+    Color, Fragment, Program, ProgramPipeline, SeparableProgram, Shader, ShaderType, Vertex,
+};
+
+const VERTEX: &str = r#"#version 410 core
+out gl_PerVertex {
+    vec4 gl_Position;
+};
+void main() {
+    vec2 positions[3] = vec2[](vec2(-1.0, -1.0), vec2(3.0, -1.0), vec2(-1.0, 3.0));
+    gl_Position = vec4(positions[gl_VertexID], 0.0, 1.0);
+}
+"#;
+
+const RED: &str = r#"#version 410 core
+out vec4 color;
+void main() {
+    color = vec4(1.0, 0.0, 0.0, 1.0);
+}
+"#;
+
+const TINTED: &str = r#"#version 410 core
+uniform vec4 tint;
+out vec4 color;
+void main() {
+    color = tint;
+}
+"#;
+
+fn separable<S: graphics::AsShaderType>(source: &str) -> SeparableProgram<S> {
+    SeparableProgram::new(Shader::compile(source).unwrap()).unwrap()
+}
+
+#[test]
+fn swap_fragment_stage() {
+    with_context(4, 4, |draw_layer| {
+        let vertex = separable::<Vertex>(VERTEX);
+        let red = separable::<Fragment>(RED);
+        let tinted = separable::<Fragment>(TINTED);
+        let tint = tinted.as_program().uniform::<Color>("tint").unwrap();
+
+        let pipeline = ProgramPipeline::new();
+        pipeline.use_stage(&vertex);
+        pipeline.use_stage(&red);
+        pipeline.validate().unwrap();
+        draw_layer.use_pipeline(&pipeline);
+        assert_eq!(draw_fullscreen(draw_layer), [255, 0, 0, 255]);
+
+        pipeline.use_stage(&tinted);
+        pipeline.set_active_program(&tinted);
+        draw_layer.set(&tint, &Color::new(0.0, 0.0, 1.0, 1.0));
+        assert_eq!(draw_fullscreen(draw_layer), [0, 0, 255, 255]);
+    });
+}
+
+/// [`DrawLayer::use_program`] and [`DrawLayer::use_pipeline`] replace each other.
+#[test]
+fn switch_with_programs() {
+    with_context(4, 4, |draw_layer| {
+        let vertex = separable::<Vertex>(VERTEX);
+        let red = separable::<Fragment>(RED);
+        let pipeline = ProgramPipeline::new();
+        pipeline.use_stage(&vertex);
+        pipeline.use_stage(&red);
+
+        let green = Program::new(
+            Shader::compile(VERTEX).unwrap(),
+            Shader::compile(&RED.replace("1.0, 0.0, 0.0", "0.0, 1.0, 0.0")).unwrap(),
+        )
+        .unwrap();
+
+        draw_layer.use_pipeline(&pipeline);
+        assert_eq!(draw_fullscreen(draw_layer), [255, 0, 0, 255]);
+        draw_layer.use_program(&green);
+        assert_eq!(draw_fullscreen(draw_layer), [0, 255, 0, 255]);
+        draw_layer.use_pipeline(&pipeline);
+        assert_eq!(draw_fullscreen(draw_layer), [255, 0, 0, 255]);
+    });
+}
+
+/// Stages removed from a pipeline are skipped.
+#[test]
+fn removed_stage() {
+    with_context(4, 4, |draw_layer| {
+        let vertex = separable::<Vertex>(VERTEX);
+        let red = separable::<Fragment>(RED);
+        let pipeline = ProgramPipeline::new();
+        pipeline.use_stage(&vertex);
+        pipeline.use_stage(&red);
+        pipeline.remove_stage(ShaderType::Fragment);
+
+        draw_layer.use_pipeline(&pipeline);
+        pipeline.validate().unwrap();
+        draw_fullscreen(draw_layer);
+    });
+}
@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+
+use graphics::{PreprocessError, Preprocessor, ShaderDirectory};
+
+fn files(files: &[(&str, &str)]) -> HashMap<String, String> {
+    files
+        .iter()
+        .map(|(path, text)| (path.to_string(), text.to_string()))
+        .collect()
+}
+
+#[test]
+fn includes() {
+    let preprocessor = Preprocessor::new(files(&[
+        (
+            "shaders/main.frag",
+            "#version 330 core\n#include \"lib/light.glsl\"\nvoid main() {}\n",
+        ),
+        (
+            "shaders/lib/light.glsl",
+            "#pragma once\n#include \"../common.glsl\"\nfloat light() { return 1.0; }\n",
+        ),
+        ("shaders/common.glsl", "const float PI = 3.14;\n"),
+    ]));
+
+    let source = preprocessor.process("shaders/main.frag", &[]).unwrap();
+    assert_eq!(
+        source.files,
+        [
+            "shaders/main.frag",
+            "shaders/lib/light.glsl",
+            "shaders/common.glsl"
+        ]
+    );
+    assert_eq!(
+        source.code,
+        "#version 330 core\n\
+         #line 1 0\n\
+         \n\
+         #line 1 1\n\
+         \n\
+         #line 1 2\n\
+         const float PI = 3.14;\n\
+         #line 3 1\n\
+         float light() { return 1.0; }\n\
+         #line 3 0\n\
+         void main() {}\n"
+    );
+}
+
+#[test]
+fn version_and_defines() {
+    let preprocessor = Preprocessor::new(files(&[("main.vert", "#version 110\nvoid main() {}\n")]))
+        .version("330 core")
+        .define("MAX_LIGHTS", "4");
+
+    let source = preprocessor.process("main.vert", &["SKINNED"]).unwrap();
+    assert_eq!(
+        source.code,
+        "#version 330 core\n\
+         #define MAX_LIGHTS 4\n\
+         #define SKINNED\n\
+         #line 1 0\n\
+         \n\
+         void main() {}\n"
+    );
+}
+
+#[test]
+fn pragma_once() {
+    let preprocessor = Preprocessor::new(files(&[
+        (
+            "main.frag",
+            "#include \"once.glsl\"\n#include \"once.glsl\"\n",
+        ),
+        ("once.glsl", "#pragma once\nint x;\n"),
+    ]));
+
+    let source = preprocessor.process("main.frag", &[]).unwrap();
+    assert_eq!(source.code.matches("int x;").count(), 1);
+}
+
+#[test]
+fn errors() {
+    let preprocessor = Preprocessor::new(files(&[
+        ("a.glsl", "#include \"b.glsl\"\n"),
+        ("b.glsl", "\n#include \"a.glsl\"\n"),
+        ("missing.glsl", "\n\n#include \"nothing.glsl\"\n"),
+        ("invalid.glsl", "#include <system.glsl>\n"),
+    ]));
+
+    let cycle = preprocessor.process("a.glsl", &[]).unwrap_err();
+    assert_eq!(
+        cycle.to_string(),
+        "Include cycle: a.glsl -> b.glsl -> a.glsl"
+    );
+
+    match preprocessor.process("missing.glsl", &[]).unwrap_err() {
+        PreprocessError::Read {
+            path,
+            included_from,
+            ..
+        } => {
+            assert_eq!(path, "nothing.glsl");
+            assert_eq!(included_from, Some(("missing.glsl".to_owned(), 3)));
+        }
+        err => panic!("unexpected error: {err}"),
+    }
+
+    assert!(matches!(
+        preprocessor.process("invalid.glsl", &[]),
+        Err(PreprocessError::InvalidInclude { line: 1, .. })
+    ));
+}
+
+#[test]
+fn directory() {
+    let root = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("preprocessor");
+    std::fs::create_dir_all(root.join("lib")).unwrap();
+    std::fs::write(root.join("main.frag"), "#include \"lib/color.glsl\"\n").unwrap();
+    std::fs::write(root.join("lib/color.glsl"), "vec4 color;\n").unwrap();
+
+    let preprocessor = Preprocessor::new(ShaderDirectory::new(&root));
+    let source = preprocessor.process("main.frag", &[]).unwrap();
+    assert_eq!(source.files, ["main.frag", "lib/color.glsl"]);
+    assert!(source.code.contains("vec4 color;\n"));
+}
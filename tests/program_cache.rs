@@ -0,0 +1,125 @@
+mod common;
+
+use std::fs;
+
+use common::{draw_fullscreen, test_directory, with_context, FULLSCREEN_VERTEX};
+use graphics::{Program, ProgramCache, ShaderType};
+
+const FRAGMENT: &str = r#"#version 330 core
+out vec4 color;
+void main() {
+    color = vec4(0.0, 1.0, 0.0, 1.0);
+}
+"#;
+
+const STAGES: &[(ShaderType, &str)] = &[
+    (ShaderType::Vertex, FULLSCREEN_VERTEX),
+    (ShaderType::Fragment, FRAGMENT),
+];
+
+#[test]
+fn binary_round_trip() {
+    with_context(4, 4, |draw_layer| {
+        let cache =
+            ProgramCache::new(test_directory("program_cache", "binary_round_trip")).unwrap();
+        let program = cache.link(STAGES).unwrap();
+        let binary = program.binary().expect("The driver has no binary formats");
+        assert!(Program::binary_formats().contains(&binary.format));
+
+        let loaded = Program::from_binary(&binary).unwrap();
+        draw_layer.use_program(&loaded);
+        assert_eq!(draw_fullscreen(draw_layer), [0, 255, 0, 255]);
+    });
+}
+
+#[test]
+fn link_stores_the_program() {
+    with_context(4, 4, |draw_layer| {
+        let cache =
+            ProgramCache::new(test_directory("program_cache", "link_stores_the_program")).unwrap();
+        assert!(cache.load(STAGES).is_none());
+
+        let program = cache.link(STAGES).unwrap();
+        assert!(cache.path(STAGES).is_file());
+        draw_layer.use_program(&program);
+        assert_eq!(draw_fullscreen(draw_layer), [0, 255, 0, 255]);
+
+        let loaded = cache.load(STAGES).expect("The program wasn't cached");
+        draw_layer.use_program(&loaded);
+        assert_eq!(draw_fullscreen(draw_layer), [0, 255, 0, 255]);
+    });
+}
+
+#[test]
+fn key_covers_every_stage() {
+    with_context(4, 4, |_| {
+        let cache =
+            ProgramCache::new(test_directory("program_cache", "key_covers_every_stage")).unwrap();
+        let other_fragment = FRAGMENT.replace("0.0, 1.0, 0.0", "1.0, 0.0, 0.0");
+        let other = [
+            (ShaderType::Vertex, FULLSCREEN_VERTEX),
+            (ShaderType::Fragment, other_fragment.as_str()),
+        ];
+        assert_ne!(cache.path(STAGES), cache.path(&other));
+
+        cache.link(STAGES).unwrap();
+        assert!(cache.load(&other).is_none());
+    });
+}
+
+/// Damaged files and files of another version are rebuilt and overwritten.
+#[test]
+fn invalid_files_are_rebuilt() {
+    with_context(4, 4, |draw_layer| {
+        let cache = ProgramCache::new(test_directory("program_cache", "invalid_files_are_rebuilt"))
+            .unwrap();
+        cache.link(STAGES).unwrap();
+        let path = cache.path(STAGES);
+        let valid = fs::read(&path).unwrap();
+
+        let mut corrupted = valid.clone();
+        let last = corrupted.len() - 1;
+        corrupted[last] ^= 0xff;
+        let mut truncated = valid.clone();
+        truncated.truncate(valid.len() / 2);
+        let mut newer_version = valid.clone();
+        newer_version[4..8].copy_from_slice(&u32::MAX.to_le_bytes());
+        // The header isn't covered by the checksum, the driver rejects the format instead.
+        let mut unknown_format = valid.clone();
+        unknown_format[16..20].copy_from_slice(&0xdead_u32.to_le_bytes());
+
+        for file in [
+            corrupted,
+            truncated,
+            newer_version,
+            unknown_format,
+            Vec::new(),
+        ] {
+            fs::write(&path, file).unwrap();
+            assert!(cache.load(STAGES).is_none());
+
+            let program = cache.link(STAGES).unwrap();
+            draw_layer.use_program(&program);
+            assert_eq!(draw_fullscreen(draw_layer), [0, 255, 0, 255]);
+            assert_eq!(fs::read(&path).unwrap(), valid);
+        }
+    });
+}
+
+#[test]
+fn compile_errors_are_reported() {
+    with_context(4, 4, |_| {
+        let cache = ProgramCache::new(test_directory(
+            "program_cache",
+            "compile_errors_are_reported",
+        ))
+        .unwrap();
+        let stages = [
+            (ShaderType::Vertex, FULLSCREEN_VERTEX),
+            (ShaderType::Fragment, "#version 330 core\nvoid main() {"),
+        ];
+
+        assert!(cache.link(&stages).is_err());
+        assert!(!cache.path(&stages).exists());
+    });
+}
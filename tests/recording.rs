@@ -0,0 +1,471 @@
+use graphics::{
+    attributes, ActiveTexture, Arg, Attachment, Call, Color, CubeFace, CubeMap, DrawLayer,
+    DrawMode, Ebo, Fragment, Framebuffer, GlslType, Program, RecordingBackend, RingBuffer,
+    RingBufferError, SamplerDesc, Shader, ShaderError, SpecializationConstant, Texture,
+    TextureBuilder, TextureError, TextureFormat, UniformResource, Vao, Vbo,
+};
+use nalgebra_glm as glm;
+
+fn formatted(calls: Vec<Call>) -> Vec<String> {
+    calls.iter().map(ToString::to_string).collect()
+}
+
+fn program() -> Program {
+    let vertex = Shader::compile("").unwrap();
+    let fragment = Shader::compile("").unwrap();
+
+    Program::new(vertex, fragment).unwrap()
+}
+
+#[test]
+fn attribute_layout() {
+    let backend = RecordingBackend::new();
+    DrawLayer::with_backend(&backend);
+    backend.set_attribute_location("position", 0);
+    backend.set_attribute_location("color", 1);
+    backend.set_attribute_location("uv", 2);
+
+    let program = program();
+    backend.clear();
+
+    let attrs = attributes! {
+        position: vec<f32, 2>,
+        color: vec<f32, 3>,
+        uv: vec<f32, 2>
+    };
+    attrs.calculate_for(&program).unwrap();
+
+    let float = gl::FLOAT;
+    assert_eq!(
+        formatted(backend.calls_to("VertexAttribPointer")),
+        [
+            format!("VertexAttribPointer(0, 2, {float}, 0, 28, 0)"),
+            format!("VertexAttribPointer(1, 3, {float}, 0, 28, 8)"),
+            format!("VertexAttribPointer(2, 2, {float}, 0, 28, 20)"),
+        ]
+    );
+    assert_eq!(
+        formatted(backend.calls_to("EnableVertexAttribArray")),
+        [
+            "EnableVertexAttribArray(0)",
+            "EnableVertexAttribArray(1)",
+            "EnableVertexAttribArray(2)"
+        ]
+    );
+}
+
+#[test]
+fn missing_attribute() {
+    let backend = RecordingBackend::new();
+    DrawLayer::with_backend(&backend);
+    backend.set_attribute_location("normal", -1);
+
+    let program = program();
+    let attrs = attributes! {
+        position: vec<f32, 3>,
+        normal: vec<f32, 3>
+    };
+
+    assert!(attrs.calculate_for(&program).is_none());
+}
+
+#[test]
+fn object_lifetimes() {
+    let backend = RecordingBackend::new();
+    let draw_layer = DrawLayer::with_backend(&backend);
+
+    {
+        let vao = Vao::new();
+        let vbo = Vbo::new(&vao);
+        vbo.bind_data(&[0.0_f32; 6]);
+        draw_layer.draw_arrays(&vao, DrawMode::Triangles, 0, 3);
+    }
+
+    let array_buffer = gl::ARRAY_BUFFER;
+    let static_draw = gl::STATIC_DRAW;
+    let triangles = gl::TRIANGLES;
+    let calls = formatted(backend.calls());
+    assert_eq!(calls[0], "GenVertexArrays(1)");
+    assert_eq!(calls[1], "BindVertexArray(1)");
+    assert_eq!(calls[2], "GenBuffers(1)");
+    assert_eq!(calls[3], format!("BindBuffer({array_buffer}, 2)"));
+    assert!(calls[4].starts_with(&format!("BufferData({array_buffer}, 24, ")));
+    assert!(calls[4].ends_with(&format!(", {static_draw})")));
+    assert_eq!(calls[5], "BindVertexArray(1)");
+    assert_eq!(calls[6], format!("DrawArrays({triangles}, 0, 3)"));
+    assert_eq!(calls[7..].len(), 2);
+    assert!(calls[7].starts_with("DeleteBuffers(1, "));
+    assert!(calls[8].starts_with("DeleteVertexArrays(1, "));
+}
+
+#[test]
+fn framebuffer_draw_buffers() {
+    let backend = RecordingBackend::new();
+    let draw_layer = DrawLayer::with_backend(&backend);
+
+    let first = Texture::empty(4, 4);
+    let third = Texture::empty(4, 4);
+    let framebuffer = Framebuffer::new();
+    framebuffer
+        .attach_texture(Attachment::Color(0), &first, 0)
+        .attach_texture(Attachment::Color(2), &third, 0);
+    framebuffer.check_status().unwrap();
+    draw_layer.bind_framebuffer(&framebuffer);
+
+    let draw_buffers = backend.calls_to("DrawBuffers");
+    assert_eq!(draw_buffers.len(), 2);
+    assert_eq!(draw_buffers[1].args[0], graphics::Arg::Int(3));
+
+    let attachments: Vec<_> = backend
+        .calls_to("FramebufferTexture2D")
+        .into_iter()
+        .map(|call| call.args[1].clone())
+        .collect();
+    assert_eq!(
+        attachments,
+        [
+            graphics::Arg::Int(gl::COLOR_ATTACHMENT0 as i64),
+            graphics::Arg::Int(gl::COLOR_ATTACHMENT2 as i64)
+        ]
+    );
+}
+
+/// Editing a framebuffer keeps draws going to the framebuffer bound before.
+#[test]
+fn framebuffer_binding_restored() {
+    let backend = RecordingBackend::new();
+    DrawLayer::with_backend(&backend);
+    backend.set_integer(gl::DRAW_FRAMEBUFFER_BINDING, 5);
+
+    let texture = Texture::empty(4, 4);
+    let framebuffer = Framebuffer::new();
+    framebuffer.attach_texture(Attachment::Color(0), &texture, 0);
+    framebuffer.check_status().unwrap();
+
+    let bindings = formatted(backend.calls_to("BindFramebuffer"));
+    assert_eq!(bindings.len(), 4);
+    assert_eq!(
+        bindings[3],
+        format!("BindFramebuffer({}, 5)", gl::DRAW_FRAMEBUFFER)
+    );
+}
+
+#[test]
+fn indexed_draw() {
+    let backend = RecordingBackend::new();
+    let draw_layer = DrawLayer::with_backend(&backend);
+
+    let vao = Vao::new();
+    let ebo = Ebo::new(&vao);
+    ebo.bind_data(&[0_u16, 1, 2, 2, 3, 0]);
+    assert_eq!(ebo.len(), 6);
+
+    backend.clear();
+    draw_layer.draw_indexed(&vao, &ebo, DrawMode::Triangles);
+
+    assert_eq!(
+        formatted(backend.calls()),
+        [
+            "BindVertexArray(1)".to_owned(),
+            format!("BindBuffer({}, 2)", gl::ELEMENT_ARRAY_BUFFER),
+            format!(
+                "DrawElements({}, 6, {}, 0)",
+                gl::TRIANGLES,
+                gl::UNSIGNED_SHORT
+            ),
+        ]
+    );
+}
+
+#[test]
+fn uniform_uploads() {
+    let backend = RecordingBackend::new();
+    let draw_layer = DrawLayer::with_backend(&backend);
+    let program = program();
+    backend.set_uniform_location("value", 7);
+
+    backend.clear();
+    draw_layer.put_uniform(&program, "value", &true).unwrap();
+    draw_layer.put_uniform(&program, "value", &2.5_f64).unwrap();
+    draw_layer
+        .put_uniform(&program, "value", &glm::vec3(1.0_f32, 2.0, 3.0))
+        .unwrap();
+    draw_layer
+        .put_uniform(&program, "value", &glm::IVec2::zeros())
+        .unwrap();
+    draw_layer
+        .put_uniform(&program, "value", &glm::UVec4::zeros())
+        .unwrap();
+    draw_layer
+        .put_uniform(&program, "value", &glm::Mat3::identity())
+        .unwrap();
+    draw_layer
+        .put_uniform(&program, "value", &glm::Mat2x3::zeros())
+        .unwrap();
+    draw_layer
+        .put_uniform(&program, "value", &glm::DMat4::identity())
+        .unwrap();
+    draw_layer
+        .put_uniform(&program, "value", &[1_i32, 2, 3][..])
+        .unwrap();
+    draw_layer
+        .put_uniform(&program, "value", &[glm::Vec2::zeros(); 4][..])
+        .unwrap();
+    draw_layer
+        .put_uniform(&program, "value", &Color::BLACK)
+        .unwrap();
+
+    let uploads: Vec<_> = backend
+        .calls()
+        .into_iter()
+        .filter(|call| call.name != "GetUniformLocation")
+        .map(|call| {
+            let count = match call.name {
+                "Uniform1i" | "Uniform1d" | "Uniform4f" => String::new(),
+                _ => call.args[1].to_string(),
+            };
+            format!("{} {count}", call.name)
+        })
+        .collect();
+
+    assert_eq!(
+        uploads,
+        [
+            "Uniform1i ",
+            "Uniform1d ",
+            "Uniform3fv 1",
+            "Uniform2iv 1",
+            "Uniform4uiv 1",
+            "UniformMatrix3fv 1",
+            "UniformMatrix3x2fv 1",
+            "UniformMatrix4dv 1",
+            "Uniform1iv 3",
+            "Uniform2fv 4",
+            "Uniform4f ",
+        ]
+    );
+}
+
+#[test]
+fn uniform_types() {
+    assert!(glm::Mat2x3::accepts(GlslType::Mat3x2));
+    assert!(!glm::Mat2x3::accepts(GlslType::Mat2x3));
+    assert!(glm::DVec3::accepts(GlslType::DVec3));
+    assert!(<[glm::Vec4]>::accepts(GlslType::Vec4));
+    assert!(<[i32]>::accepts(GlslType::Sampler2D));
+    assert!(Color::accepts(GlslType::Vec4));
+    assert!(!glm::IVec3::accepts(GlslType::Vec3));
+}
+
+#[test]
+fn spirv_specialization() {
+    let backend = RecordingBackend::new();
+    DrawLayer::with_backend(&backend);
+    let module = 0x0723_0203_u32.to_ne_bytes();
+
+    let err = Shader::<Fragment>::from_spirv(&module, "main", &[]).err();
+    assert!(
+        matches!(err, Some(ShaderError::SpirvUnsupported)),
+        "{err:?}"
+    );
+
+    backend.set_integer(gl::MAJOR_VERSION, 4);
+    backend.set_integer(gl::MINOR_VERSION, 6);
+    let constants = [
+        SpecializationConstant::new(3, 0.5_f32),
+        SpecializationConstant::new(7, -1),
+    ];
+    backend.clear();
+    Shader::<Fragment>::from_spirv(&module, "main", &constants).unwrap();
+
+    let binary = &backend.calls_to("ShaderBinary")[0];
+    assert_eq!(binary.args[2], Arg::Int(0x9551));
+    let specialize = &backend.calls_to("SpecializeShader")[0];
+    assert_eq!(
+        specialize.args[1..3],
+        [Arg::Str("main".to_owned()), Arg::Int(2)]
+    );
+}
+
+/// A driver reporting a maximum anisotropy below 1 gets none, and is only asked once.
+#[test]
+fn anisotropy_support() {
+    let backend = RecordingBackend::new();
+    DrawLayer::with_backend(&backend);
+    backend.set_integer(gl::MAJOR_VERSION, 4);
+    backend.set_integer(gl::MINOR_VERSION, 6);
+
+    let texture = Texture::empty(2, 2);
+    let desc = SamplerDesc {
+        max_anisotropy: 16.0,
+        ..SamplerDesc::linear()
+    };
+    texture.set_sampler(&desc);
+    texture.set_sampler(&desc);
+
+    assert_eq!(backend.calls_to("GetFloatv").len(), 1);
+    let anisotropy = backend
+        .calls_to("TexParameterf")
+        .into_iter()
+        .filter(|call| call.args[1] == Arg::Int(0x84fe))
+        .count();
+    assert_eq!(anisotropy, 0);
+}
+
+/// Only textures which ask for mipmaps generate them.
+#[test]
+fn texture_storage() {
+    let backend = RecordingBackend::new();
+    DrawLayer::with_backend(&backend);
+
+    let pixels = [0_u8; 8 * 4 * 4];
+    let texture = TextureBuilder::new(8, 4)
+        .full_mip_chain()
+        .level(0, &pixels)
+        .build()
+        .unwrap();
+    assert!(backend.calls_to("GenerateMipmap").is_empty());
+    assert_eq!(
+        formatted(backend.calls_to("TexStorage2D")),
+        [format!(
+            "TexStorage2D({}, 4, {}, 8, 4)",
+            gl::TEXTURE_2D,
+            gl::RGBA8
+        )]
+    );
+
+    texture.regenerate_mipmaps().unwrap();
+    assert_eq!(backend.calls_to("GenerateMipmap").len(), 1);
+}
+
+/// Without `glGetTexImage`, as on OpenGL ES, textures are read through a framebuffer.
+#[test]
+fn texture_read_fallback() {
+    let backend = RecordingBackend::new();
+    DrawLayer::with_backend(&backend);
+    backend.set_integer(gl::READ_FRAMEBUFFER_BINDING, 7);
+    backend.set_integer(gl::IMPLEMENTATION_COLOR_READ_FORMAT, gl::RGB as i32);
+    backend.set_integer(gl::IMPLEMENTATION_COLOR_READ_TYPE, gl::UNSIGNED_BYTE as i32);
+
+    let texture = Texture::with_format(&[0; 9], 3, 1, TextureFormat::Rgb8).unwrap();
+    backend.clear();
+    assert_eq!(texture.read(0).unwrap().len(), 9);
+
+    let names: Vec<_> = backend.calls().iter().map(|call| call.name).collect();
+    assert_eq!(
+        names,
+        [
+            "PixelStorei",
+            "GetIntegerv",
+            "GenFramebuffers",
+            "BindFramebuffer",
+            "FramebufferTexture2D",
+            "CheckFramebufferStatus",
+            "GetIntegerv",
+            "GetIntegerv",
+            "ReadPixels",
+            "BindFramebuffer",
+            "DeleteFramebuffers"
+        ]
+    );
+    assert_eq!(
+        formatted(backend.calls_to("BindFramebuffer"))[1],
+        format!("BindFramebuffer({}, 7)", gl::READ_FRAMEBUFFER)
+    );
+}
+
+#[test]
+fn texture_read_fallback_unreadable() {
+    let backend = RecordingBackend::new();
+    DrawLayer::with_backend(&backend);
+
+    // The driver's extra pair doesn't match, and GLES doesn't guarantee RED_INTEGER
+    let texture = Texture::with_format(&[0; 4], 1, 1, TextureFormat::R32Ui).unwrap();
+    backend.clear();
+    assert_eq!(
+        texture.read(0),
+        Err(TextureError::Unreadable(TextureFormat::R32Ui))
+    );
+    assert!(backend.calls_to("ReadPixels").is_empty());
+    assert_eq!(backend.calls_to("DeleteFramebuffers").len(), 1);
+
+    let depth = Texture::with_format(&[0; 4], 1, 1, TextureFormat::Depth32F).unwrap();
+    backend.clear();
+    assert_eq!(
+        depth.read(0),
+        Err(TextureError::Unreadable(TextureFormat::Depth32F))
+    );
+    assert!(backend.calls_to("GenFramebuffers").is_empty());
+}
+
+#[test]
+fn cube_map_read_fallback() {
+    let backend = RecordingBackend::new();
+    DrawLayer::with_backend(&backend);
+
+    let face = [0_u8; 2 * 2 * 4];
+    let cube_map = CubeMap::new([&face; 6], 2, TextureFormat::Rgba8).unwrap();
+    backend.clear();
+    assert_eq!(
+        cube_map.read_face(CubeFace::NegativeZ, 0).unwrap().len(),
+        16
+    );
+    let attachments = backend.calls_to("FramebufferTexture2D");
+    assert_eq!(attachments.len(), 1);
+    assert_eq!(
+        attachments[0].args[2].to_string(),
+        gl::TEXTURE_CUBE_MAP_NEGATIVE_Z.to_string()
+    );
+    assert_eq!(backend.calls_to("ReadPixels").len(), 1);
+
+    let integer = CubeMap::new([&face; 6], 2, TextureFormat::R32Ui).unwrap();
+    assert_eq!(
+        integer.read_face(CubeFace::PositiveX, 0),
+        Err(TextureError::Unreadable(TextureFormat::R32Ui))
+    );
+}
+
+#[test]
+fn cube_map_faces() {
+    let backend = RecordingBackend::new();
+    DrawLayer::with_backend(&backend);
+
+    let face = [0_u8; 2 * 2 * 3];
+    let cube_map = CubeMap::new([&face; 6], 2, TextureFormat::Rgb8).unwrap();
+    let targets: Vec<_> = backend
+        .calls_to("TexImage2D")
+        .into_iter()
+        .map(|call| call.args[0].clone())
+        .collect();
+    let expected: Vec<_> = CubeFace::ALL
+        .into_iter()
+        .map(|face| Arg::Int(face as i64))
+        .collect();
+    assert_eq!(targets, expected);
+
+    backend.clear();
+    ActiveTexture::new(3).bind_cube_map(&cube_map);
+    assert_eq!(
+        formatted(backend.calls()),
+        [
+            format!("ActiveTexture({})", gl::TEXTURE3),
+            format!("BindTexture({}, 1)", gl::TEXTURE_CUBE_MAP)
+        ]
+    );
+}
+
+#[test]
+fn ring_buffer_requires_buffer_storage() {
+    let backend = RecordingBackend::new();
+    DrawLayer::with_backend(&backend);
+
+    assert_eq!(
+        RingBuffer::new(64, 2).err(),
+        Some(RingBufferError::Unsupported)
+    );
+    assert!(backend.calls_to("BufferStorage").is_empty());
+
+    backend.set_integer(gl::MAJOR_VERSION, 4);
+    backend.set_integer(gl::MINOR_VERSION, 4);
+    assert!(RingBuffer::new(64, 2).is_ok());
+}
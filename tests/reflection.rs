@@ -0,0 +1,131 @@
+mod common;
+
+use common::with_context;
+use graphics::{
+    ActiveAttribute, ActiveBlock, ComputeProgram, GlslType, Program, Shader, ShaderType,
+};
+
+const VERTEX: &str = r#"
+#version 330 core
+layout (location = 0) in vec3 position;
+layout (location = 2) in vec2 uv[2];
+uniform mat4 transform;
+out vec2 texture_coords;
+void main() {
+    gl_Position = transform * vec4(position, 1.0) + float(gl_VertexID);
+    texture_coords = uv[0] + uv[1];
+}
+"#;
+
+const FRAGMENT: &str = r#"
+#version 330 core
+in vec2 texture_coords;
+uniform sampler2D tex;
+uniform float weights[3];
+
+layout (std140) uniform Material {
+    vec4 tint;
+    float shininess;
+};
+
+out vec4 color;
+void main() {
+    color = texture(tex, texture_coords) * tint * shininess
+        * (weights[0] + weights[1] + weights[2]);
+}
+"#;
+
+const COMPUTE: &str = r#"
+#version 430 core
+layout (local_size_x = 1) in;
+layout (std430, binding = 2) buffer Values {
+    uint values[4];
+};
+void main() {
+    values[0] = 1u;
+}
+"#;
+
+#[test]
+fn render_program() {
+    with_context(1, 1, |_| {
+        let program = Program::new(
+            Shader::compile(VERTEX).unwrap(),
+            Shader::compile(FRAGMENT).unwrap(),
+        )
+        .unwrap();
+        program.bind_uniform_block("Material", 4).unwrap();
+
+        let interface = program.reflect();
+
+        assert_eq!(interface.attributes.len(), 2);
+        assert_eq!(
+            interface.attribute("position"),
+            Some(&ActiveAttribute {
+                name: "position".to_owned(),
+                ty: GlslType::Vec3,
+                array_size: 1,
+                location: 0,
+            })
+        );
+        // Drivers differ in whether attribute arrays get the `[0]` suffix
+        let uv = interface.attribute("uv").unwrap();
+        assert_eq!((uv.ty, uv.array_size, uv.location), (GlslType::Vec2, 2, 2));
+
+        let transform = interface.uniform("transform").unwrap();
+        assert_eq!(transform.ty, GlslType::Mat4);
+        assert!(transform.location.is_some_and(|location| location >= 0));
+        assert_eq!(transform.block, None);
+
+        assert_eq!(interface.uniform("tex").unwrap().ty, GlslType::Sampler2D);
+
+        let weights = interface.uniform("weights").unwrap();
+        assert_eq!((weights.ty, weights.array_size), (GlslType::Float, 3));
+        assert_eq!(weights.name, "weights[0]");
+
+        assert_eq!(
+            interface.uniform_blocks,
+            [ActiveBlock {
+                name: "Material".to_owned(),
+                binding: 4,
+                data_size: 32,
+            }]
+        );
+        let shininess = interface.uniform("shininess").unwrap();
+        assert_eq!(shininess.location, None);
+        assert_eq!(shininess.block, Some((0, 16)));
+
+        assert!(interface.storage_blocks.is_empty());
+        assert!(interface.uniform("missing").is_none());
+    });
+}
+
+#[test]
+fn storage_blocks() {
+    with_context(1, 1, |_| {
+        let program = ComputeProgram::new(Shader::compile(COMPUTE).unwrap()).unwrap();
+        let interface = program.as_program().reflect();
+
+        assert_eq!(
+            interface.storage_blocks,
+            [ActiveBlock {
+                name: "Values".to_owned(),
+                binding: 2,
+                data_size: 16,
+            }]
+        );
+        assert!(interface.attributes.is_empty());
+    });
+}
+
+#[test]
+fn type_names() {
+    assert_eq!(GlslType::from_gl(gl::FLOAT_MAT2x3), GlslType::Mat2x3);
+    assert_eq!(GlslType::Mat2x3.to_string(), "mat2x3");
+    assert_eq!(
+        GlslType::from_gl(gl::SAMPLER_CUBE).as_gl(),
+        gl::SAMPLER_CUBE
+    );
+    assert_eq!(GlslType::from_gl(gl::NONE), GlslType::Other(gl::NONE));
+    assert_eq!(ShaderType::TessControl.to_string(), "tessellation control");
+}
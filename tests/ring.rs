@@ -0,0 +1,119 @@
+mod common;
+
+use common::with_context;
+use graphics::{
+    AttributeType, ClearFlags, DrawMode, DrawTarget, Program, RingBuffer, RingBufferError, Shader,
+    Vao, Vertex, VertexLayout,
+};
+
+const VERTEX_SOURCE: &str = r#"
+#version 330 core
+layout (location = 0) in vec2 position;
+layout (location = 1) in vec4 color;
+out vec4 fragment_color;
+void main() {
+    gl_Position = vec4(position, 0.0, 1.0);
+    fragment_color = color;
+}
+"#;
+
+const FRAGMENT_SOURCE: &str = r#"
+#version 330 core
+in vec4 fragment_color;
+out vec4 color;
+void main() {
+    color = fragment_color;
+}
+"#;
+
+#[derive(Vertex, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+#[repr(C)]
+struct ColoredVertex {
+    position: [f32; 2],
+    color: [u8; 4],
+}
+
+fn screen_quad(color: [u8; 4]) -> [ColoredVertex; 4] {
+    [[-1.0, -1.0], [1.0, -1.0], [1.0, 1.0], [-1.0, 1.0]]
+        .map(|position| ColoredVertex { position, color })
+}
+
+#[test]
+fn streams_across_frames() {
+    with_context(8, 8, |draw_layer| {
+        let vertex = Shader::compile(VERTEX_SOURCE).unwrap();
+        let fragment = Shader::compile(FRAGMENT_SOURCE).unwrap();
+        let program = Program::new(vertex, fragment).unwrap();
+        draw_layer.use_program(&program);
+
+        let mut ring = RingBuffer::new(256, 3).unwrap();
+        let vao = Vao::new();
+        unsafe { vao.bind() };
+        ring.bind(DrawTarget::ArrayBuffer);
+        ring.bind(DrawTarget::ElementArrayBuffer);
+        ColoredVertex::attributes().calculate_for(&program).unwrap();
+
+        let colors = [[255, 0, 0, 255], [0, 255, 0, 255], [0, 0, 255, 255]];
+        for frame in 0..7 {
+            let color = colors[frame % colors.len()];
+
+            // Unaligned filler makes the slices start in the middle of the segment
+            ring.push(&[0_u8; 3], 1).unwrap();
+            let vertices = ring.push_vertices(&screen_quad(color)).unwrap();
+            assert_eq!(vertices.first() as usize * 12, vertices.offset);
+
+            // Indices are relative to the start of the buffer, not to the pushed quad
+            let base = vertices.first() as u16;
+            let indices = ring
+                .push_indices(&[0_u16, 1, 2, 2, 3, 0].map(|index| index + base))
+                .unwrap();
+            assert_eq!(indices.offset % 2, 0);
+
+            draw_layer.clear(ClearFlags::COLOR);
+            draw_layer.draw_elements(
+                &vao,
+                DrawMode::Triangles,
+                indices.len as i32,
+                AttributeType::u16,
+                indices.offset,
+            );
+            assert_eq!(draw_layer.read_pixels(0, 0, 8, 8).pixel(4, 4), color);
+
+            ring.next_frame();
+        }
+    });
+}
+
+#[test]
+fn full_segment() {
+    with_context(1, 1, |_| {
+        let mut ring = RingBuffer::new(64, 2).unwrap();
+        assert!(ring.push(&[0_u32; 16], 4).is_some());
+        assert_eq!(ring.remaining(), 0);
+        assert!(ring.push(&[0_u8], 1).is_none());
+
+        ring.next_frame();
+        let slice = ring.push(&[0_u32; 4], 4).unwrap();
+        assert_eq!(slice.offset, 64);
+        assert_eq!(slice.size(), 16);
+    });
+}
+
+#[test]
+fn invalid_sizes() {
+    with_context(1, 1, |_| {
+        assert!(RingBuffer::supported());
+        assert_eq!(
+            RingBuffer::new(0, 3).err(),
+            Some(RingBufferError::EmptySegment)
+        );
+        assert_eq!(
+            RingBuffer::new(64, 0).err(),
+            Some(RingBufferError::NoFrames)
+        );
+        assert_eq!(
+            RingBuffer::new(usize::MAX / 2, 3).err(),
+            Some(RingBufferError::TooLarge)
+        );
+    });
+}
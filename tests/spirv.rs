@@ -0,0 +1,160 @@
+mod common;
+
+use common::{draw_fullscreen, with_context};
+use graphics::{
+    spirv_supported, Fragment, Program, Shader, ShaderError, SpecializationConstant, Vertex,
+};
+
+/// Appends SPIR-V instructions, there is no SPIR-V compiler among the dev-dependencies.
+struct Module(Vec<u32>);
+
+impl Module {
+    /// `bound` is one more than the largest id used.
+    fn new(bound: u32) -> Self {
+        let mut module = Self(vec![0x0723_0203, 0x0001_0000, 0, bound, 0]);
+        module.op(17, &[1]); // OpCapability Shader
+        module.op(14, &[0, 1]); // OpMemoryModel Logical GLSL450
+        module
+    }
+
+    fn op(&mut self, opcode: u32, operands: &[u32]) -> &mut Self {
+        self.0.push(((operands.len() as u32 + 1) << 16) | opcode);
+        self.0.extend_from_slice(operands);
+        self
+    }
+
+    /// OpEntryPoint `model` %main "main" `interface`
+    fn entry_point(&mut self, model: u32, main: u32, interface: &[u32]) -> &mut Self {
+        let name = u32::from_le_bytes(*b"main");
+        let operands = [&[model, main, name, 0], interface].concat();
+        self.op(15, &operands)
+    }
+
+    fn bytes(&self) -> Vec<u8> {
+        self.0.iter().flat_map(|word| word.to_ne_bytes()).collect()
+    }
+}
+
+/// A triangle covering the viewport, positioned from `gl_VertexID`.
+fn vertex_module() -> Vec<u8> {
+    let [void, function, int, float, vec4, int_input, vec4_output] = [1, 2, 3, 4, 5, 6, 7];
+    let [vertex_id, position, one, four, zero_f, one_f, main, label] =
+        [8, 9, 10, 11, 12, 13, 14, 15];
+    let [id, x_bit, y_bit, x4, y4, x, y, fx, fy, result] = [16, 17, 18, 19, 20, 21, 22, 23, 24, 25];
+
+    let mut module = Module::new(26);
+    module
+        .entry_point(0, main, &[vertex_id, position])
+        .op(71, &[vertex_id, 11, 5]) // BuiltIn VertexId
+        .op(71, &[position, 11, 0]) // BuiltIn Position
+        .op(19, &[void])
+        .op(33, &[function, void])
+        .op(21, &[int, 32, 1])
+        .op(22, &[float, 32])
+        .op(23, &[vec4, float, 4])
+        .op(32, &[int_input, 1, int])
+        .op(32, &[vec4_output, 3, vec4])
+        .op(59, &[int_input, vertex_id, 1])
+        .op(59, &[vec4_output, position, 3])
+        .op(43, &[int, one, 1])
+        .op(43, &[int, four, 4])
+        .op(43, &[float, zero_f, 0.0_f32.to_bits()])
+        .op(43, &[float, one_f, 1.0_f32.to_bits()])
+        .op(54, &[void, main, 0, function])
+        .op(248, &[label])
+        .op(61, &[int, id, vertex_id])
+        .op(199, &[int, x_bit, id, one]) // id & 1
+        .op(195, &[int, y_bit, id, one]) // id >> 1
+        .op(132, &[int, x4, x_bit, four])
+        .op(132, &[int, y4, y_bit, four])
+        .op(130, &[int, x, x4, one])
+        .op(130, &[int, y, y4, one])
+        .op(111, &[float, fx, x])
+        .op(111, &[float, fy, y])
+        .op(80, &[vec4, result, fx, fy, zero_f, one_f])
+        .op(62, &[position, result])
+        .op(253, &[])
+        .op(56, &[]);
+
+    module.bytes()
+}
+
+/// Writes `vec4(red ? 1.0 : 0.0, green, float(blue), 1.0)` with the specialization constants
+/// `green` (id 0, 0.0), `red` (id 1, true) and `blue` (id 2, 0).
+fn fragment_module() -> Vec<u8> {
+    let [void, function, float, vec4, boolean, int, vec4_output] = [1, 2, 3, 4, 5, 6, 7];
+    let [color, green, red, blue, zero_f, one_f, main, label] = [8, 9, 10, 11, 12, 13, 14, 15];
+    let [red_f, blue_f, result] = [16, 17, 18];
+
+    let mut module = Module::new(19);
+    module
+        .entry_point(4, main, &[color])
+        .op(16, &[main, 8]) // OriginLowerLeft
+        .op(71, &[color, 30, 0]) // Location 0
+        .op(71, &[green, 1, 0]) // SpecId 0
+        .op(71, &[red, 1, 1])
+        .op(71, &[blue, 1, 2])
+        .op(19, &[void])
+        .op(33, &[function, void])
+        .op(22, &[float, 32])
+        .op(23, &[vec4, float, 4])
+        .op(20, &[boolean])
+        .op(21, &[int, 32, 1])
+        .op(32, &[vec4_output, 3, vec4])
+        .op(59, &[vec4_output, color, 3])
+        .op(50, &[float, green, 0.0_f32.to_bits()])
+        .op(48, &[boolean, red])
+        .op(50, &[int, blue, 0])
+        .op(43, &[float, zero_f, 0.0_f32.to_bits()])
+        .op(43, &[float, one_f, 1.0_f32.to_bits()])
+        .op(54, &[void, main, 0, function])
+        .op(248, &[label])
+        .op(169, &[float, red_f, red, one_f, zero_f])
+        .op(111, &[float, blue_f, blue])
+        .op(80, &[vec4, result, red_f, green, blue_f, one_f])
+        .op(62, &[color, result])
+        .op(253, &[])
+        .op(56, &[]);
+
+    module.bytes()
+}
+
+fn render(constants: &[SpecializationConstant]) -> [u8; 4] {
+    with_context(4, 4, |draw_layer| {
+        assert!(spirv_supported());
+
+        let vertex = Shader::<Vertex>::from_spirv(&vertex_module(), "main", &[]).unwrap();
+        let fragment =
+            Shader::<Fragment>::from_spirv(&fragment_module(), "main", constants).unwrap();
+        let program = Program::new(vertex, fragment).unwrap();
+
+        draw_layer.use_program(&program);
+        draw_fullscreen(draw_layer)
+    })
+}
+
+#[test]
+fn default_constants() {
+    assert_eq!(render(&[]), [255, 0, 0, 255]);
+}
+
+#[test]
+fn specialized_constants() {
+    let constants = [
+        SpecializationConstant::new(0, 1.0_f32),
+        SpecializationConstant::new(1, false),
+        SpecializationConstant::new(2, 1_i32),
+    ];
+    assert_eq!(render(&constants), [0, 255, 255, 255]);
+}
+
+#[test]
+fn invalid_modules() {
+    with_context(4, 4, |_| {
+        let module = vertex_module();
+        for invalid in [&module[..module.len() - 1], &module[4..], &[]] {
+            let err = Shader::<Vertex>::from_spirv(invalid, "main", &[]).err();
+            assert!(matches!(err, Some(ShaderError::InvalidSpirv(_))), "{err:?}");
+        }
+    });
+}
@@ -0,0 +1,193 @@
+mod common;
+
+use common::with_context;
+use graphics::{ClearFlags, DrawLayer, DrawMode, Program, Shader, ShaderError, ShaderType, Vao};
+
+const POINT_VERTEX: &str = r#"
+#version 400 core
+void main() {
+    gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+}
+"#;
+
+/// Expands each point into a triangle covering the viewport.
+const FULLSCREEN_GEOMETRY: &str = r#"
+#version 400 core
+layout (points) in;
+layout (triangle_strip, max_vertices = 3) out;
+void main() {
+    gl_Position = vec4(-1.0, -1.0, 0.0, 1.0);
+    EmitVertex();
+    gl_Position = vec4(3.0, -1.0, 0.0, 1.0);
+    EmitVertex();
+    gl_Position = vec4(-1.0, 3.0, 0.0, 1.0);
+    EmitVertex();
+    EndPrimitive();
+}
+"#;
+
+const PATCH_VERTEX: &str = r#"
+#version 400 core
+void main() {
+    vec2 positions[3] = vec2[](vec2(-1.0, -1.0), vec2(3.0, -1.0), vec2(-1.0, 3.0));
+    gl_Position = vec4(positions[gl_VertexID], 0.0, 1.0);
+}
+"#;
+
+const PATCH_TESS_CONTROL: &str = r#"
+#version 400 core
+layout (vertices = 3) out;
+void main() {
+    gl_out[gl_InvocationID].gl_Position = gl_in[gl_InvocationID].gl_Position;
+    gl_TessLevelOuter[0] = 4.0;
+    gl_TessLevelOuter[1] = 4.0;
+    gl_TessLevelOuter[2] = 4.0;
+    gl_TessLevelInner[0] = 4.0;
+}
+"#;
+
+const PATCH_TESS_EVALUATION: &str = r#"
+#version 400 core
+layout (triangles) in;
+void main() {
+    gl_Position = gl_TessCoord.x * gl_in[0].gl_Position
+        + gl_TessCoord.y * gl_in[1].gl_Position
+        + gl_TessCoord.z * gl_in[2].gl_Position;
+}
+"#;
+
+const GREEN_FRAGMENT: &str = r#"
+#version 400 core
+out vec4 color;
+void main() {
+    color = vec4(0.0, 1.0, 0.0, 1.0);
+}
+"#;
+
+fn center_pixel(draw_layer: &DrawLayer, program: &Program, mode: DrawMode, count: i32) -> [u8; 4] {
+    let vao = Vao::new();
+    draw_layer.use_program(program);
+    draw_layer.clear(ClearFlags::COLOR);
+    draw_layer.draw_arrays(&vao, mode, 0, count);
+
+    draw_layer.read_pixels(0, 0, 4, 4).pixel(2, 2)
+}
+
+#[test]
+fn geometry_stage() {
+    with_context(4, 4, |draw_layer| {
+        let program = Program::builder()
+            .vertex(Shader::compile(POINT_VERTEX).unwrap())
+            .geometry(Shader::compile(FULLSCREEN_GEOMETRY).unwrap())
+            .fragment(Shader::compile(GREEN_FRAGMENT).unwrap())
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            center_pixel(draw_layer, &program, DrawMode::Points, 1),
+            [0, 255, 0, 255]
+        );
+    });
+}
+
+#[test]
+fn tessellation_stages() {
+    with_context(4, 4, |draw_layer| {
+        let program = Program::builder()
+            .vertex(Shader::compile(PATCH_VERTEX).unwrap())
+            .tess_control(Shader::compile(PATCH_TESS_CONTROL).unwrap())
+            .tess_evaluation(Shader::compile(PATCH_TESS_EVALUATION).unwrap())
+            .fragment(Shader::compile(GREEN_FRAGMENT).unwrap())
+            .build()
+            .unwrap();
+
+        draw_layer.set_patch_vertices(3);
+        assert_eq!(
+            center_pixel(draw_layer, &program, DrawMode::Patches, 3),
+            [0, 255, 0, 255]
+        );
+    });
+}
+
+#[test]
+fn missing_stages() {
+    with_context(1, 1, |_| {
+        let without_vertex = Program::builder()
+            .fragment(Shader::compile(GREEN_FRAGMENT).unwrap())
+            .build();
+        assert!(matches!(
+            without_vertex,
+            Err(ShaderError::MissingStage {
+                stage: ShaderType::Vertex,
+                required_by: None
+            })
+        ));
+
+        let without_evaluation = Program::builder()
+            .vertex(Shader::compile(PATCH_VERTEX).unwrap())
+            .tess_control(Shader::compile(PATCH_TESS_CONTROL).unwrap())
+            .build();
+        let err = without_evaluation.err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "The tessellation control shader requires a tessellation evaluation shader"
+        );
+    });
+}
+
+#[test]
+fn link_error_names_stages() {
+    with_context(1, 1, |_| {
+        let fragment = Shader::compile(
+            "#version 400 core
+            vec4 shade();
+            out vec4 color;
+            void main() { color = shade(); }",
+        )
+        .unwrap();
+        let result = Program::builder()
+            .vertex(Shader::compile(POINT_VERTEX).unwrap())
+            .geometry(Shader::compile(FULLSCREEN_GEOMETRY).unwrap())
+            .fragment(fragment)
+            .build();
+
+        let Err(ShaderError::LinkingError(err)) = result else {
+            panic!("The program linked without a definition of shade");
+        };
+        assert_eq!(
+            err.stages,
+            [
+                ShaderType::Vertex,
+                ShaderType::Geometry,
+                ShaderType::Fragment
+            ]
+        );
+        // The log may not say which stage calls `shade`
+        if err.stage.is_none() {
+            assert!(err
+                .to_string()
+                .starts_with("Cannot link the vertex, geometry and fragment shaders: "));
+        }
+    });
+}
+
+#[test]
+fn link_error_names_failing_stage() {
+    with_context(1, 1, |_| {
+        // The input primitive is only checked when linking
+        let geometry = FULLSCREEN_GEOMETRY.replace("layout (points) in;", "");
+        let result = Program::builder()
+            .vertex(Shader::compile(POINT_VERTEX).unwrap())
+            .geometry(Shader::compile(&geometry).unwrap())
+            .fragment(Shader::compile(GREEN_FRAGMENT).unwrap())
+            .build();
+
+        let Err(ShaderError::LinkingError(err)) = result else {
+            panic!("The program linked without an input primitive");
+        };
+        assert_eq!(err.stage, Some(ShaderType::Geometry));
+        assert!(err
+            .to_string()
+            .starts_with("Cannot link the geometry shader: "));
+    });
+}
@@ -0,0 +1,103 @@
+use graphics::Std140;
+use nalgebra_glm as glm;
+
+#[derive(Std140)]
+#[repr(C)]
+struct Light {
+    position: glm::Vec3,
+    intensity: f32,
+    color: glm::Vec3,
+}
+
+#[derive(Std140)]
+#[repr(C)]
+struct Scene {
+    ambient: f32,
+    light: Light,
+    enabled: bool,
+    weights: [f32; 3],
+    offset: glm::Vec2,
+    normal: glm::Mat3,
+    index: u32,
+}
+
+/// Without `#[repr(C)]` Rust may reorder the fields, which the std140 bytes don't follow.
+#[derive(Std140)]
+struct Material {
+    shiny: bool,
+    albedo: glm::Vec3,
+    roughness: f32,
+}
+
+fn read_f32(bytes: &[u8], offset: usize) -> f32 {
+    f32::from_ne_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+#[test]
+fn primitive_layouts() {
+    assert_eq!((<f32>::ALIGN, <f32>::SIZE), (4, 4));
+    assert_eq!((glm::Vec2::ALIGN, glm::Vec2::SIZE), (8, 8));
+    assert_eq!((glm::Vec3::ALIGN, glm::Vec3::SIZE), (16, 12));
+    assert_eq!((glm::Vec4::ALIGN, glm::Vec4::SIZE), (16, 16));
+    assert_eq!((glm::Mat3::ALIGN, glm::Mat3::SIZE), (16, 48));
+    assert_eq!((glm::Mat4::ALIGN, glm::Mat4::SIZE), (16, 64));
+    assert_eq!((<[f32; 3]>::ALIGN, <[f32; 3]>::SIZE), (16, 48));
+    assert_eq!((<[glm::Vec4; 2]>::ALIGN, <[glm::Vec4; 2]>::SIZE), (16, 32));
+}
+
+#[test]
+fn struct_layout() {
+    // vec3 position @0, float intensity @12, vec3 color @16, rounded up to 32
+    assert_eq!((Light::ALIGN, Light::SIZE), (16, 32));
+
+    // float @0, Light @16, bool @48, float[3] @64, vec2 @112, mat3 @128, uint @176
+    assert_eq!((Scene::ALIGN, Scene::SIZE), (16, 192));
+
+    let scene = Scene {
+        ambient: 0.5,
+        light: Light {
+            position: glm::vec3(1.0, 2.0, 3.0),
+            intensity: 4.0,
+            color: glm::vec3(5.0, 6.0, 7.0),
+        },
+        enabled: true,
+        weights: [8.0, 9.0, 10.0],
+        offset: glm::vec2(11.0, 12.0),
+        normal: glm::Mat3::identity() * 2.0,
+        index: 13,
+    };
+    let bytes = scene.to_std140_bytes();
+
+    assert_eq!(bytes.len(), 192);
+    assert_eq!(read_f32(&bytes, 0), 0.5);
+    assert_eq!(read_f32(&bytes, 16 + 8), 3.0);
+    assert_eq!(read_f32(&bytes, 16 + 12), 4.0);
+    assert_eq!(read_f32(&bytes, 16 + 16), 5.0);
+    assert_eq!(bytes[48..52], 1_u32.to_ne_bytes());
+    assert_eq!(read_f32(&bytes, 64), 8.0);
+    assert_eq!(read_f32(&bytes, 80), 9.0);
+    assert_eq!(read_f32(&bytes, 96), 10.0);
+    assert_eq!(read_f32(&bytes, 112), 11.0);
+    assert_eq!(read_f32(&bytes, 116), 12.0);
+    assert_eq!(read_f32(&bytes, 128), 2.0);
+    assert_eq!(read_f32(&bytes, 144 + 4), 2.0);
+    assert_eq!(read_f32(&bytes, 160 + 8), 2.0);
+    assert_eq!(bytes[176..180], 13_u32.to_ne_bytes());
+}
+
+#[test]
+fn declaration_order_without_repr_c() {
+    let material = Material {
+        shiny: true,
+        albedo: glm::vec3(1.0, 2.0, 3.0),
+        roughness: 4.0,
+    };
+
+    // bool @0, vec3 @16, float @28
+    assert_eq!((Material::ALIGN, Material::SIZE), (16, 32));
+    let bytes = material.to_std140_bytes();
+    assert_eq!(bytes[0..4], 1_u32.to_ne_bytes());
+    assert_eq!(read_f32(&bytes, 16), 1.0);
+    assert_eq!(read_f32(&bytes, 24), 3.0);
+    assert_eq!(read_f32(&bytes, 28), 4.0);
+}
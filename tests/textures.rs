@@ -0,0 +1,566 @@
+mod common;
+
+use common::{with_context, FULLSCREEN_VERTEX};
+use graphics::{
+    ActiveTexture, ClearFlags, Color, CompareFunction, DrawLayer, DrawMode, Filter, PixelBuffer,
+    Program, Sampler, SamplerDesc, Shader, Texture, TextureBuilder, TextureError, TextureFormat,
+    Vao, Wrap,
+};
+
+/// Draw `texel`, an expression of the texel under each pixel, with `image` declared as `sampler`.
+/// The context has to be the same size as `texture`.
+fn fetch(draw_layer: &DrawLayer, texture: &Texture, sampler: &str, texel: &str) -> PixelBuffer {
+    let size = (texture.width() as u32, texture.height() as u32);
+    draw(draw_layer, texture, None, size, sampler, texel)
+}
+
+/// Draw `color` over a `size` context, `position` is the pixel being drawn
+/// and `uv` its center in texture coordinates.
+fn draw(
+    draw_layer: &DrawLayer,
+    texture: &Texture,
+    sampler_object: Option<&Sampler>,
+    size: (u32, u32),
+    sampler: &str,
+    color: &str,
+) -> PixelBuffer {
+    let (width, height) = size;
+    let fragment = format!(
+        "#version 330 core
+        uniform {sampler} image;
+        out vec4 color;
+        void main() {{
+            ivec2 position = ivec2(gl_FragCoord.xy);
+            vec2 uv = gl_FragCoord.xy / vec2({width}.0, {height}.0);
+            color = {color};
+        }}"
+    );
+    let program = Program::new(
+        Shader::compile(FULLSCREEN_VERTEX).unwrap(),
+        Shader::compile(&fragment).unwrap(),
+    )
+    .unwrap();
+
+    let unit = ActiveTexture::new(0);
+    unit.bind_texture(texture);
+    match sampler_object {
+        Some(sampler_object) => unit.bind_sampler(sampler_object),
+        None => unit.unbind_sampler(),
+    }
+    draw_layer.use_program(&program);
+    draw_layer.put_uniform(&program, "image", &unit).unwrap();
+
+    let vao = Vao::new();
+    draw_layer.clear(ClearFlags::COLOR);
+    draw_layer.draw_arrays(&vao, DrawMode::Triangles, 0, 3);
+    draw_layer.read_pixels(0, 0, width, height)
+}
+
+fn fetch_color(draw_layer: &DrawLayer, texture: &Texture) -> PixelBuffer {
+    fetch(
+        draw_layer,
+        texture,
+        "sampler2D",
+        "texelFetch(image, position, 0)",
+    )
+}
+
+fn half(value: f32) -> [u8; 2] {
+    // Only exact halves are needed, 0.5 is 0x3800 and 1.0 is 0x3c00.
+    match value {
+        0.0 => 0_u16,
+        0.5 => 0x3800,
+        1.0 => 0x3c00,
+        _ => unreachable!(),
+    }
+    .to_ne_bytes()
+}
+
+#[test]
+fn byte_formats() {
+    with_context(2, 1, |draw_layer| {
+        let red = Texture::with_format(&[255, 51], 2, 1, TextureFormat::R8).unwrap();
+        let pixels = fetch_color(draw_layer, &red);
+        assert_eq!(pixels.pixel(0, 0), [255, 0, 0, 255]);
+        assert_eq!(pixels.pixel(1, 0), [51, 0, 0, 255]);
+
+        let rg = Texture::with_format(&[10, 20, 30, 40], 2, 1, TextureFormat::Rg8).unwrap();
+        let pixels = fetch_color(draw_layer, &rg);
+        assert_eq!(pixels.pixel(1, 0), [30, 40, 0, 255]);
+
+        let srgb = Texture::with_format(
+            &[188, 0, 255, 128, 255, 255, 255, 255],
+            2,
+            1,
+            TextureFormat::Srgb8Alpha8,
+        )
+        .unwrap();
+        let pixels = fetch_color(draw_layer, &srgb);
+        // 188 is about 0.5 in linear space, alpha is never converted.
+        let [r, g, b, a] = pixels.pixel(0, 0);
+        assert!((126..=130).contains(&r), "{r}");
+        assert_eq!([g, b, a], [0, 255, 128]);
+    });
+}
+
+/// Rows of 3 RGB pixels are 9 bytes long, which isn't a multiple of the default alignment.
+#[test]
+fn odd_width_rgb() {
+    with_context(3, 2, |draw_layer| {
+        let data: Vec<u8> = (0..18).map(|value| value * 10).collect();
+        let texture = Texture::with_format(&data, 3, 2, TextureFormat::Rgb8).unwrap();
+        let pixels = fetch_color(draw_layer, &texture);
+
+        // Rows are read top to bottom, the first row of the texture is the bottom one.
+        assert_eq!(pixels.pixel(0, 1), [0, 10, 20, 255]);
+        assert_eq!(pixels.pixel(2, 1), [60, 70, 80, 255]);
+        assert_eq!(pixels.pixel(0, 0), [90, 100, 110, 255]);
+        assert_eq!(pixels.pixel(2, 0), [150, 160, 170, 255]);
+    });
+}
+
+#[test]
+fn float_formats() {
+    with_context(1, 1, |draw_layer| {
+        let data = half(0.5);
+        let texture = Texture::with_format(&data, 1, 1, TextureFormat::R16F).unwrap();
+        assert_eq!(fetch_color(draw_layer, &texture).pixel(0, 0)[0], 128);
+
+        let data = [half(1.0), half(0.0), half(0.5), half(1.0)].concat();
+        let texture = Texture::with_format(&data, 1, 1, TextureFormat::Rgba16F).unwrap();
+        assert_eq!(
+            fetch_color(draw_layer, &texture).pixel(0, 0),
+            [255, 0, 128, 255]
+        );
+
+        // Values above 1 survive until they're written to the RGBA8 framebuffer.
+        let values = [4.0_f32, 0.25, 0.0, 1.0];
+        let texture =
+            Texture::with_format(bytemuck::cast_slice(&values), 1, 1, TextureFormat::Rgba32F)
+                .unwrap();
+        let pixels = fetch(
+            draw_layer,
+            &texture,
+            "sampler2D",
+            "texelFetch(image, position, 0) / vec4(8.0, 1.0, 1.0, 1.0)",
+        );
+        assert_eq!(pixels.pixel(0, 0), [128, 64, 0, 255]);
+    });
+}
+
+#[test]
+fn integer_format() {
+    with_context(2, 1, |draw_layer| {
+        let values = [100_000_u32, 7];
+        let texture =
+            Texture::with_format(bytemuck::cast_slice(&values), 2, 1, TextureFormat::R32Ui)
+                .unwrap();
+        let pixels = fetch(
+            draw_layer,
+            &texture,
+            "usampler2D",
+            "vec4(vec3(texelFetch(image, position, 0).r % 256u) / 255.0, 1.0)",
+        );
+
+        assert_eq!(pixels.pixel(0, 0), [160, 160, 160, 255]);
+        assert_eq!(pixels.pixel(1, 0), [7, 7, 7, 255]);
+    });
+}
+
+#[test]
+fn depth_formats() {
+    with_context(1, 1, |draw_layer| {
+        let depth = [0.25_f32];
+        let texture =
+            Texture::with_format(bytemuck::cast_slice(&depth), 1, 1, TextureFormat::Depth32F)
+                .unwrap();
+        assert_eq!(fetch_color(draw_layer, &texture).pixel(0, 0)[0], 64);
+
+        // Depth in the upper 24 bits, stencil in the lower 8.
+        let depth_stencil = [(0x80_0000_u32 << 8) | 0x12];
+        let texture = Texture::with_format(
+            bytemuck::cast_slice(&depth_stencil),
+            1,
+            1,
+            TextureFormat::Depth24Stencil8,
+        )
+        .unwrap();
+        assert_eq!(fetch_color(draw_layer, &texture).pixel(0, 0)[0], 128);
+
+        let shadow_map = Texture::empty_with_format(16, 16, TextureFormat::Depth32F);
+        assert_eq!(shadow_map.format(), TextureFormat::Depth32F);
+    });
+}
+
+#[test]
+fn data_length() {
+    with_context(1, 1, |_| {
+        let err = Texture::with_format(&[0; 8], 3, 1, TextureFormat::Rgb8).err();
+        assert_eq!(
+            err,
+            Some(TextureError::DataLength {
+                expected: 9,
+                actual: 8
+            })
+        );
+
+        let err = Texture::with_format(&[0; 16], 2, 1, TextureFormat::Rgba16F).err();
+        assert!(err.is_none());
+    });
+}
+
+/// A black and a white texel stretched over 4 pixels.
+fn black_and_white() -> Texture {
+    Texture::with_format(&[0, 255], 2, 1, TextureFormat::R8).unwrap()
+}
+
+fn red_row(pixels: &PixelBuffer) -> Vec<u8> {
+    (0..pixels.width).map(|x| pixels.pixel(x, 0)[0]).collect()
+}
+
+#[test]
+fn magnification_filter() {
+    with_context(4, 1, |draw_layer| {
+        let texture = black_and_white();
+        let desc = SamplerDesc::nearest().wrap(Wrap::ClampToEdge);
+        texture.set_sampler(&desc);
+        let sample = |draw_layer| {
+            red_row(&draw(
+                draw_layer,
+                &texture,
+                None,
+                (4, 1),
+                "sampler2D",
+                "texture(image, uv)",
+            ))
+        };
+        assert_eq!(sample(draw_layer), [0, 0, 255, 255]);
+
+        texture.set_sampler(&SamplerDesc {
+            mag_filter: Filter::Linear,
+            ..desc
+        });
+        assert_eq!(sample(draw_layer), [0, 64, 191, 255]);
+    });
+}
+
+#[test]
+fn wrap_modes() {
+    with_context(1, 1, |draw_layer| {
+        let texture = black_and_white();
+        // The right texel, one texture width to the right.
+        let outside = "texture(image, vec2(1.75, 0.5))";
+        let sample = |draw_layer| draw(draw_layer, &texture, None, (1, 1), "sampler2D", outside);
+
+        texture.set_sampler(&SamplerDesc::nearest());
+        assert_eq!(sample(draw_layer).pixel(0, 0), [255, 0, 0, 255]);
+
+        texture.set_sampler(&SamplerDesc {
+            border_color: Color::new(0.5, 0.0, 0.0, 1.0),
+            ..SamplerDesc::nearest().wrap(Wrap::ClampToBorder)
+        });
+        assert_eq!(sample(draw_layer).pixel(0, 0), [128, 0, 0, 255]);
+
+        texture.set_sampler(&SamplerDesc::nearest().wrap(Wrap::MirroredRepeat));
+        assert_eq!(sample(draw_layer).pixel(0, 0), [0, 0, 0, 255]);
+    });
+}
+
+/// A bound sampler object replaces the texture's own state until it's unbound.
+#[test]
+fn sampler_objects() {
+    with_context(4, 1, |draw_layer| {
+        let texture = black_and_white();
+        texture.set_sampler(&SamplerDesc::linear().wrap(Wrap::ClampToEdge));
+        let nearest = Sampler::new(&SamplerDesc::nearest().wrap(Wrap::ClampToEdge));
+
+        let pixels = draw(
+            draw_layer,
+            &texture,
+            Some(&nearest),
+            (4, 1),
+            "sampler2D",
+            "texture(image, uv)",
+        );
+        assert_eq!(red_row(&pixels), [0, 0, 255, 255]);
+
+        let pixels = draw(
+            draw_layer,
+            &texture,
+            None,
+            (4, 1),
+            "sampler2D",
+            "texture(image, uv)",
+        );
+        assert_eq!(red_row(&pixels), [0, 64, 191, 255]);
+
+        // Anisotropy is clamped to what the driver supports, or ignored.
+        nearest.set(&SamplerDesc {
+            max_anisotropy: 1000.0,
+            ..SamplerDesc::default()
+        });
+    });
+}
+
+#[test]
+fn depth_comparison() {
+    with_context(2, 1, |draw_layer| {
+        let depth = [0.25_f32, 0.75];
+        let texture =
+            Texture::with_format(bytemuck::cast_slice(&depth), 2, 1, TextureFormat::Depth32F)
+                .unwrap();
+        texture.set_sampler(&SamplerDesc {
+            compare: Some(CompareFunction::LessEqual),
+            ..SamplerDesc::nearest()
+        });
+
+        // Lit where the reference depth of 0.5 is closer than the stored one.
+        let pixels = draw(
+            draw_layer,
+            &texture,
+            None,
+            (2, 1),
+            "sampler2DShadow",
+            "vec4(vec3(texture(image, vec3(uv, 0.5))), 1.0)",
+        );
+        assert_eq!(red_row(&pixels), [0, 255]);
+    });
+}
+
+fn solid(width: i32, height: i32, pixel: [u8; 4]) -> Vec<u8> {
+    pixel.repeat((width * height) as usize)
+}
+
+#[test]
+fn precomputed_levels() {
+    with_context(1, 1, |draw_layer| {
+        let levels = [
+            solid(4, 4, [255, 0, 0, 255]),
+            solid(2, 2, [0, 255, 0, 255]),
+            solid(1, 1, [0, 0, 255, 255]),
+        ];
+        let texture = TextureBuilder::new(4, 4)
+            .full_mip_chain()
+            .level(0, &levels[0])
+            .level(1, &levels[1])
+            .level(2, &levels[2])
+            .build()
+            .unwrap();
+        assert_eq!(texture.levels(), 3);
+        assert_eq!(texture.level_size(1), (2, 2));
+
+        for (level, expected) in [[255, 0, 0, 255], [0, 255, 0, 255], [0, 0, 255, 255]]
+            .into_iter()
+            .enumerate()
+        {
+            let color = format!("textureLod(image, vec2(0.5), {level}.0)");
+            let pixels = draw(draw_layer, &texture, None, (1, 1), "sampler2D", &color);
+            assert_eq!(pixels.pixel(0, 0), expected, "level {level}");
+        }
+    });
+}
+
+#[test]
+fn generated_mipmaps() {
+    with_context(1, 1, |draw_layer| {
+        let checker = [
+            [0, 0, 0, 255],
+            [255, 255, 255, 255],
+            [255, 255, 255, 255],
+            [0, 0, 0, 255],
+        ]
+        .concat();
+        let texture = TextureBuilder::new(2, 2)
+            .mip_levels(8)
+            .level(0, &checker)
+            .generate_mipmaps()
+            .build()
+            .unwrap();
+        assert_eq!(texture.levels(), 2);
+
+        let color = "textureLod(image, vec2(0.5), 1.0)";
+        let [r, g, b, a] = draw(draw_layer, &texture, None, (1, 1), "sampler2D", color).pixel(0, 0);
+        assert!((126..=129).contains(&r), "{r}");
+        assert_eq!([r, r, a], [g, b, 255]);
+
+        // Textures without immutable storage grow a full chain.
+        let texture =
+            Texture::with_format(&solid(4, 4, [0; 4]), 4, 4, TextureFormat::Rgba8).unwrap();
+        assert_eq!(texture.levels(), 1);
+        texture.regenerate_mipmaps().unwrap();
+        assert_eq!(texture.levels(), 3);
+    });
+}
+
+#[test]
+fn builder_errors() {
+    with_context(1, 1, |_| {
+        let level = solid(1, 1, [0; 4]);
+        let err = TextureBuilder::new(4, 4)
+            .mip_levels(2)
+            .level(2, &level)
+            .build()
+            .err();
+        assert_eq!(
+            err,
+            Some(TextureError::LevelOutOfRange {
+                level: 2,
+                levels: 2
+            })
+        );
+
+        let err = TextureBuilder::new(4, 4)
+            .mip_levels(2)
+            .level(1, &level)
+            .build()
+            .err();
+        assert_eq!(
+            err,
+            Some(TextureError::DataLength {
+                expected: 16,
+                actual: 4
+            })
+        );
+
+        let err = TextureBuilder::new(4, 4)
+            .format(TextureFormat::R32Ui)
+            .full_mip_chain()
+            .generate_mipmaps()
+            .build()
+            .err();
+        assert_eq!(
+            err,
+            Some(TextureError::MipmapsUnsupported(TextureFormat::R32Ui))
+        );
+
+        let texture = Texture::empty_with_format(4, 4, TextureFormat::Depth32F);
+        assert!(texture.regenerate_mipmaps().is_err());
+    });
+}
+
+#[test]
+fn update_and_read() {
+    with_context(1, 1, |_| {
+        let texture = Texture::with_format(&[0; 18], 3, 2, TextureFormat::Rgb8).unwrap();
+        let region: Vec<u8> = (1..=12).collect();
+        texture.update_region(1, 0, 2, 2, 0, &region).unwrap();
+
+        assert_eq!(
+            texture.read(0).unwrap(),
+            [[0, 0, 0, 1, 2, 3, 4, 5, 6], [0, 0, 0, 7, 8, 9, 10, 11, 12]].concat()
+        );
+    });
+}
+
+/// Rows of a region cut from a larger image, with strides in whole pixels and not.
+#[test]
+fn strided_updates() {
+    with_context(1, 1, |_| {
+        // A 4x2 RG8 image, the region is its middle 2x2 pixels.
+        let image: Vec<u8> = (0..16).collect();
+        let texture = Texture::with_format(&[0; 8], 2, 2, TextureFormat::Rg8).unwrap();
+        texture
+            .update_region_with_stride(0, 0, 2, 2, 0, &image[2..], 8)
+            .unwrap();
+        assert_eq!(texture.read(0).unwrap(), [2, 3, 4, 5, 10, 11, 12, 13]);
+
+        // Rows padded to 7 bytes, which RGB8 pixels can't express as a row length.
+        let padded = [[1, 2, 3, 4, 5, 6, 0], [7, 8, 9, 10, 11, 12, 0]].concat();
+        let texture = Texture::with_format(&[0; 12], 2, 2, TextureFormat::Rgb8).unwrap();
+        texture
+            .update_region_with_stride(0, 0, 2, 2, 0, &padded[..13], 7)
+            .unwrap();
+        assert_eq!(texture.read(0).unwrap(), (1..=12).collect::<Vec<u8>>());
+
+        let err = texture
+            .update_region_with_stride(0, 0, 2, 2, 0, &padded, 5)
+            .err();
+        assert_eq!(err, Some(TextureError::RowStride { stride: 5, row: 6 }));
+
+        let err = texture
+            .update_region_with_stride(0, 0, 2, 2, 0, &padded, usize::MAX)
+            .err();
+        assert_eq!(
+            err,
+            Some(TextureError::StrideOverflow {
+                stride: usize::MAX,
+                height: 2
+            })
+        );
+    });
+}
+
+#[test]
+fn read_levels() {
+    with_context(1, 1, |_| {
+        let values = [1.0_f32, 0.5, 0.25, 0.0];
+        let texture = TextureBuilder::new(2, 2)
+            .format(TextureFormat::Depth32F)
+            .level(0, bytemuck::cast_slice(&values))
+            .build()
+            .unwrap();
+        assert_eq!(
+            texture.read(0).unwrap(),
+            bytemuck::cast_slice::<f32, u8>(&values)
+        );
+
+        let texture = TextureBuilder::new(2, 2)
+            .full_mip_chain()
+            .level(0, &[[200, 0, 0, 255], [0, 0, 0, 255]].repeat(2).concat())
+            .generate_mipmaps()
+            .build()
+            .unwrap();
+        assert_eq!(texture.read(1).unwrap(), [100, 0, 0, 255]);
+    });
+}
+
+#[test]
+fn region_errors() {
+    with_context(1, 1, |_| {
+        let texture = TextureBuilder::new(4, 4).full_mip_chain().build().unwrap();
+
+        let err = texture.update_region(1, 1, 2, 2, 1, &[0; 16]).err();
+        assert_eq!(
+            err,
+            Some(TextureError::RegionOutOfBounds {
+                region: [1, 1, 2, 2],
+                level_size: (2, 2)
+            })
+        );
+
+        let err = texture.update_region(i32::MAX, 0, 1, 1, 0, &[0; 4]).err();
+        assert_eq!(
+            err,
+            Some(TextureError::RegionOutOfBounds {
+                region: [i32::MAX, 0, 1, 1],
+                level_size: (4, 4)
+            })
+        );
+
+        let err = texture.update_region(0, 0, 1, 1, 3, &[0; 4]).err();
+        assert_eq!(
+            err,
+            Some(TextureError::LevelOutOfRange {
+                level: 3,
+                levels: 3
+            })
+        );
+
+        let err = texture.update_region(0, 0, 2, 1, 0, &[0; 4]).err();
+        assert_eq!(
+            err,
+            Some(TextureError::DataLength {
+                expected: 8,
+                actual: 4
+            })
+        );
+    });
+}
+
+#[test]
+#[should_panic(expected = "Mip level 32 is out of range")]
+fn level_size_out_of_range() {
+    with_context(1, 1, |_| {
+        Texture::empty(4, 4).level_size(32);
+    });
+}
@@ -0,0 +1,229 @@
+mod common;
+
+use common::{draw_fullscreen, with_context, FULLSCREEN_VERTEX};
+use graphics::{ActiveTexture, Color, GlslType, Program, Shader, UniformError};
+use nalgebra_glm as glm;
+
+const COLOR_FRAGMENT: &str = r#"
+#version 330 core
+uniform float brightness;
+uniform uint index;
+out vec4 color;
+void main() {
+    color = vec4(brightness, float(index) / 255.0, 0.0, 1.0);
+}
+"#;
+
+const TYPES_FRAGMENT: &str = r#"
+#version 330 core
+uniform uint index;
+uniform mat4 transform;
+uniform sampler2D tex;
+
+layout (std140) uniform Block {
+    float member;
+};
+
+out vec4 color;
+void main() {
+    color = texture(tex, vec2(0.0)) * member * transform[0][0] * float(index);
+}
+"#;
+
+/// Outputs green if every uniform has the expected value, red otherwise.
+const CHECK_VALUES_FRAGMENT: &str = r#"
+#version 400 core
+uniform vec3 position;
+uniform ivec2 cell;
+uniform mat3 rotation;
+uniform mat3x2 projection;
+uniform dvec2 offset;
+uniform float weights[3];
+uniform vec4 tint;
+uniform bool enabled;
+
+out vec4 color;
+void main() {
+    bool ok = position == vec3(1.0, 2.0, 3.0)
+        && cell == ivec2(-4, 5)
+        && rotation[1] == vec3(4.0, 5.0, 6.0)
+        && projection[2] == vec2(5.0, 6.0)
+        && offset == dvec2(0.25, 0.5)
+        && weights[0] == 7.0 && weights[2] == 9.0
+        && tint == vec4(0.0, 0.0, 0.0, 1.0)
+        && enabled;
+    color = ok ? vec4(0.0, 1.0, 0.0, 1.0) : vec4(1.0, 0.0, 0.0, 1.0);
+}
+"#;
+
+/// Outputs green if only the third element of the array is set, red otherwise.
+const CHECK_ELEMENT_FRAGMENT: &str = r#"
+#version 330 core
+uniform float values[4];
+out vec4 color;
+void main() {
+    bool ok = values[0] == 0.0 && values[1] == 0.0 && values[2] == 5.0 && values[3] == 0.0;
+    color = ok ? vec4(0.0, 1.0, 0.0, 1.0) : vec4(1.0, 0.0, 0.0, 1.0);
+}
+"#;
+
+const OPAQUE_FRAGMENT: &str = r#"
+#version 430 core
+uniform samplerCubeShadow shadow;
+uniform isampler3D volume;
+uniform sampler2DArrayShadow cascades;
+uniform usamplerCube ids;
+uniform sampler2DMS multisampled;
+uniform samplerBuffer texels;
+layout (rgba8) readonly uniform image2D image;
+out vec4 color;
+void main() {
+    color = vec4(texture(shadow, vec4(1.0)))
+        + vec4(texture(volume, vec3(0.0)))
+        + vec4(texture(cascades, vec4(0.0)))
+        + vec4(texture(ids, vec3(1.0)))
+        + texelFetch(multisampled, ivec2(0), 0)
+        + texelFetch(texels, 0)
+        + imageLoad(image, ivec2(0));
+}
+"#;
+
+fn program(fragment: &str) -> Program {
+    Program::new(
+        Shader::compile(FULLSCREEN_VERTEX).unwrap(),
+        Shader::compile(fragment).unwrap(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn typed_uniforms() {
+    let pixel = with_context(4, 4, |draw_layer| {
+        let program = program(COLOR_FRAGMENT);
+        let brightness = program.uniform::<f32>("brightness").unwrap();
+        let index = program.uniform::<u32>("index").unwrap();
+
+        draw_layer.use_program(&program);
+        draw_layer.set(&brightness, &1.0);
+        draw_layer.set(&index, &51);
+        draw_fullscreen(draw_layer)
+    });
+
+    assert_eq!(pixel, [255, 51, 0, 255]);
+}
+
+#[test]
+fn uniform_errors() {
+    with_context(1, 1, |_| {
+        let program = program(TYPES_FRAGMENT);
+        program.uniform::<[f32; 16]>("transform").unwrap();
+        program.uniform::<ActiveTexture>("tex").unwrap();
+
+        assert_eq!(
+            program.uniform::<f32>("missing").err(),
+            Some(UniformError::NotFound("missing".to_owned()))
+        );
+        assert_eq!(
+            program.uniform::<f32>("member").err(),
+            Some(UniformError::InUniformBlock("member".to_owned()))
+        );
+
+        let mismatch = program.uniform::<f32>("index").err().unwrap();
+        assert_eq!(
+            mismatch,
+            UniformError::TypeMismatch {
+                name: "index".to_owned(),
+                declared: GlslType::UInt,
+                rust_type: "f32",
+            }
+        );
+        assert_eq!(
+            mismatch.to_string(),
+            "The uniform `index` is declared as uint, which can't hold a f32"
+        );
+    });
+}
+
+#[test]
+fn array_element() {
+    let pixel = with_context(4, 4, |draw_layer| {
+        let program = program(CHECK_ELEMENT_FRAGMENT);
+        draw_layer.use_program(&program);
+
+        let element = program.uniform::<f32>("values[2]").unwrap();
+        draw_layer.set(&element, &5.0);
+        assert_eq!(
+            program.uniform::<f32>("values[4]").err(),
+            Some(UniformError::NotFound("values[4]".to_owned()))
+        );
+
+        draw_fullscreen(draw_layer)
+    });
+
+    assert_eq!(pixel, [0, 255, 0, 255]);
+}
+
+#[test]
+fn opaque_types() {
+    with_context(1, 1, |_| {
+        let program = program(OPAQUE_FRAGMENT);
+        let expected = [
+            ("shadow", GlslType::SamplerCubeShadow, "samplerCubeShadow"),
+            ("volume", GlslType::ISampler3D, "isampler3D"),
+            (
+                "cascades",
+                GlslType::Sampler2DArrayShadow,
+                "sampler2DArrayShadow",
+            ),
+            ("ids", GlslType::USamplerCube, "usamplerCube"),
+            ("multisampled", GlslType::Sampler2DMS, "sampler2DMS"),
+            ("texels", GlslType::SamplerBuffer, "samplerBuffer"),
+            ("image", GlslType::Image2D, "image2D"),
+        ];
+
+        let interface = program.reflect();
+        for (name, ty, keyword) in expected {
+            let declared = interface.uniform(name).unwrap().ty;
+            assert_eq!(declared, ty, "{name}");
+            assert_eq!(declared.to_string(), keyword);
+            assert_eq!(ty.is_image(), name == "image");
+            assert_eq!(ty.is_sampler(), name != "image");
+
+            program.uniform::<ActiveTexture>(name).unwrap();
+            program.uniform::<i32>(name).unwrap();
+        }
+    });
+}
+
+#[test]
+fn composite_values() {
+    let pixel = with_context(4, 4, |draw_layer| {
+        let program = program(CHECK_VALUES_FRAGMENT);
+        draw_layer.use_program(&program);
+
+        let position = program.uniform::<glm::Vec3>("position").unwrap();
+        draw_layer.set(&position, &glm::vec3(1.0, 2.0, 3.0));
+        let cell = program.uniform::<glm::IVec2>("cell").unwrap();
+        draw_layer.set(&cell, &glm::vec2(-4, 5));
+        let rotation = program.uniform::<glm::Mat3>("rotation").unwrap();
+        draw_layer.set(
+            &rotation,
+            &glm::mat3(1.0, 4.0, 7.0, 2.0, 5.0, 8.0, 3.0, 6.0, 9.0),
+        );
+        // Two rows and three columns
+        let projection = program.uniform::<glm::Mat2x3>("projection").unwrap();
+        draw_layer.set(&projection, &glm::mat2x3(1.0, 3.0, 5.0, 2.0, 4.0, 6.0));
+        let offset = program.uniform::<glm::DVec2>("offset").unwrap();
+        draw_layer.set(&offset, &glm::vec2(0.25, 0.5));
+        let weights = program.uniform::<[f32]>("weights").unwrap();
+        draw_layer.set(&weights, &[7.0, 8.0, 9.0][..]);
+        let tint = program.uniform::<Color>("tint").unwrap();
+        draw_layer.set(&tint, &Color::BLACK);
+        let enabled = program.uniform::<bool>("enabled").unwrap();
+        draw_layer.set(&enabled, &true);
+
+        draw_fullscreen(draw_layer)
+    });
+
+    assert_eq!(pixel, [0, 255, 0, 255]);
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use std::collections::HashMap;
+
+use common::{draw_fullscreen, with_context, FULLSCREEN_MAIN};
+use graphics::{Fragment, Preprocessor, ProgramVariants, Severity, ShaderError};
+
+fn shader_files() -> HashMap<String, String> {
+    [
+        ("fullscreen.vert", FULLSCREEN_MAIN),
+        (
+            "color.frag",
+            r#"
+#include "lib/color.glsl"
+out vec4 color;
+void main() {
+    color = base_color();
+}
+"#,
+        ),
+        (
+            "lib/color.glsl",
+            r#"#pragma once
+vec4 base_color() {
+#ifdef RED
+    return vec4(1.0, 0.0, 0.0, 1.0);
+#else
+    return vec4(0.0, 0.0, float(BLUE) / 255.0, 1.0);
+#endif
+}
+"#,
+        ),
+        ("broken.frag", "#include \"lib/broken.glsl\"\n"),
+        ("lib/broken.glsl", "\nvoid main() {\n    float x = ;\n}\n"),
+    ]
+    .into_iter()
+    .map(|(path, text)| (path.to_owned(), text.to_owned()))
+    .collect()
+}
+
+#[test]
+fn variants() {
+    with_context(4, 4, |draw_layer| {
+        let preprocessor = Preprocessor::new(shader_files())
+            .version("330 core")
+            .define("BLUE", "51");
+        let mut variants = ProgramVariants::new(preprocessor, "fullscreen.vert", "color.frag");
+
+        let mut render = |features: &[&str]| {
+            draw_layer.use_program(variants.get(features).unwrap());
+            draw_fullscreen(draw_layer)
+        };
+
+        assert_eq!(render(&[]), [0, 0, 51, 255]);
+        assert_eq!(render(&["RED"]), [255, 0, 0, 255]);
+        assert_eq!(render(&["RED", "RED"]), [255, 0, 0, 255]);
+        assert_eq!(variants.len(), 2);
+    });
+}
+
+#[test]
+fn error_lines() {
+    with_context(1, 1, |_| {
+        let preprocessor = Preprocessor::new(shader_files()).version("330 core");
+        let source = preprocessor.process("broken.frag", &[]).unwrap();
+        assert_eq!(source.files[1], "lib/broken.glsl");
+
+        match preprocessor.compile::<Fragment>("broken.frag", &[]) {
+            Err(ShaderError::CompilationError(err)) => {
+                let diagnostic = &err.diagnostics[0];
+                assert_eq!(diagnostic.severity, Severity::Error);
+                assert_eq!((diagnostic.file, diagnostic.line), (1, Some(3)));
+                assert_eq!(err.file_name(diagnostic), Some("lib/broken.glsl"));
+                assert_eq!(err.source_line(diagnostic), Some("    float x = ;"));
+            }
+            _ => panic!("The shader should not compile"),
+        }
+    });
+}
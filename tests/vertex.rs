@@ -0,0 +1,131 @@
+use graphics::{
+    AttributeDescriptor, AttributeType, DrawLayer, Program, RecordingBackend, Shader, Vertex,
+    VertexLayout,
+};
+use nalgebra_glm as glm;
+
+#[derive(Vertex)]
+#[repr(C)]
+struct ColoredVertex {
+    position: glm::Vec3,
+    #[vertex(name = "color")]
+    rgba: [u8; 4],
+    uv: [f32; 2],
+}
+
+#[derive(Vertex)]
+#[repr(C)]
+struct PaddedVertex {
+    weight: f32,
+    #[vertex(skip)]
+    _padding: [u8; 4],
+    #[vertex(normalized = false)]
+    bone: [u16; 2],
+    position: [f64; 2],
+}
+
+#[derive(Vertex)]
+#[repr(C)]
+struct SkinnedVertex {
+    #[vertex(integer)]
+    joints: [u16; 4],
+    index: i32,
+    #[vertex(integer)]
+    material: u32,
+}
+
+#[test]
+fn descriptors() {
+    let attributes = ColoredVertex::attributes();
+    let descriptors = attributes.descriptors();
+
+    let names: Vec<_> = descriptors.iter().map(|attr| attr.name).collect();
+    assert_eq!(names, ["position", "color", "uv"]);
+
+    let offsets: Vec<_> = descriptors.iter().map(|attr| attr.offset).collect();
+    assert_eq!(offsets, [Some(0), Some(12), Some(16)]);
+
+    let sizes: Vec<_> = descriptors.iter().map(|attr| attr.vector_size).collect();
+    assert_eq!(sizes, [3, 4, 2]);
+
+    let normalized: Vec<_> = descriptors.iter().map(|attr| attr.normalized).collect();
+    assert_eq!(normalized, [false, true, false]);
+
+    assert!(matches!(descriptors[1].mem_type, AttributeType::u8));
+    assert_eq!(descriptors[1].mem_size, 1);
+}
+
+#[test]
+fn padded_layout() {
+    let backend = RecordingBackend::new();
+    DrawLayer::with_backend(&backend);
+    backend.set_attribute_location("weight", 0);
+    backend.set_attribute_location("bone", 1);
+    backend.set_attribute_location("position", 2);
+
+    let vertex = Shader::compile("").unwrap();
+    let fragment = Shader::compile("").unwrap();
+    let program = Program::new(vertex, fragment).unwrap();
+    PaddedVertex::attributes().calculate_for(&program).unwrap();
+
+    let calls: Vec<_> = backend
+        .calls_to("VertexAttribPointer")
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(
+        calls,
+        [
+            format!("VertexAttribPointer(0, 1, {}, 0, 32, 0)", gl::FLOAT),
+            format!(
+                "VertexAttribPointer(1, 2, {}, 0, 32, 8)",
+                gl::UNSIGNED_SHORT
+            ),
+            format!("VertexAttribPointer(2, 2, {}, 0, 32, 16)", gl::DOUBLE),
+        ]
+    );
+}
+
+#[test]
+fn integer_attributes() {
+    let attributes = SkinnedVertex::attributes();
+    let descriptors = attributes.descriptors();
+    let integer: Vec<_> = descriptors.iter().map(|attr| attr.integer).collect();
+    assert_eq!(integer, [true, false, true]);
+    // 32 bit integers are converted to floats as is unless asked otherwise
+    assert!(!descriptors[1].normalized);
+
+    let backend = RecordingBackend::new();
+    DrawLayer::with_backend(&backend);
+    backend.set_attribute_location("joints", 0);
+    backend.set_attribute_location("index", 1);
+    backend.set_attribute_location("material", 2);
+
+    let vertex = Shader::compile("").unwrap();
+    let fragment = Shader::compile("").unwrap();
+    let program = Program::new(vertex, fragment).unwrap();
+    attributes.calculate_for(&program).unwrap();
+
+    let integer_calls: Vec<_> = backend
+        .calls_to("VertexAttribIPointer")
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(
+        integer_calls,
+        [
+            format!("VertexAttribIPointer(0, 4, {}, 16, 0)", gl::UNSIGNED_SHORT),
+            format!("VertexAttribIPointer(2, 1, {}, 16, 12)", gl::UNSIGNED_INT),
+        ]
+    );
+    assert_eq!(
+        backend.calls_to("VertexAttribPointer")[0].to_string(),
+        format!("VertexAttribPointer(1, 1, {}, 0, 16, 8)", gl::INT)
+    );
+}
+
+#[test]
+#[should_panic(expected = "The attribute uv has no integer type")]
+fn integer_float_attribute() {
+    AttributeDescriptor::new("uv", 2, 4, AttributeType::f32).integer(true);
+}